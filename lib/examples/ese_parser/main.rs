@@ -30,7 +30,7 @@ fn main() {
         return;
     }
     if args[0].contains("help") {
-        eprintln!("[/m mode] [/t table] db path");
+        eprintln!("[/m mode] [/t table] [--export-csv table] [--info [--json]] db path");
         eprintln!("where mode one of [EseAPI, EseParser, *Both - default]");
         std::process::exit(0);
     }
@@ -51,11 +51,34 @@ fn main() {
         table = args[1].clone();
         args.drain(..2);
     }
+    let mut csv_table = None;
+    if args[0] == "--export-csv" {
+        csv_table = Some(args[1].clone());
+        args.drain(..2);
+    }
+    let mut info = false;
+    let mut json = false;
+    while let Some(flag) = args.first() {
+        match flag.as_str() {
+            "--info" => info = true,
+            "--json" => json = true,
+            _ => break,
+        }
+        args.remove(0);
+    }
     if args.is_empty() {
         eprintln!("db path required");
         std::process::exit(-1);
     }
     let dbpath = args.concat();
 
+    if info {
+        print_db_info(&dbpath, json);
+        return;
+    }
+    if let Some(csv_table) = csv_table {
+        export_csv(&dbpath, mode, &csv_table);
+        return;
+    }
     process_table(&dbpath, None, mode, table);
 }
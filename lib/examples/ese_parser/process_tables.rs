@@ -1,9 +1,9 @@
 extern crate ese_parser_lib;
 
+use ese_parser_lib::error::EseError;
 #[cfg(all(feature = "nt_comparison", target_os = "windows"))]
 use ese_parser_lib::parser::ese_both::*;
 use ese_parser_lib::{ese_parser::*, ese_trait::*, vartime::*};
-use simple_error::SimpleError;
 use std::convert::TryFrom;
 use std::fs::File;
 use std::io::{self, Error, Write};
@@ -21,14 +21,14 @@ fn get_column<T: FromBytes>(
     jdb: &dyn EseDb,
     table: u64,
     column: u32,
-) -> Result<Option<T>, SimpleError> {
+) -> Result<Option<T>, EseError> {
     match jdb.get_column(table, column)? {
         Some(v) => Ok(Some(T::from_bytes(&v))),
         None => Ok(None),
     }
 }
 
-fn get_column_val(jdb: &dyn EseDb, table_id: u64, c: &ColumnInfo) -> Result<String, SimpleError> {
+fn get_column_val(jdb: &dyn EseDb, table_id: u64, c: &ColumnInfo) -> Result<String, EseError> {
     let val;
     match c.typ {
         ESE_coltypBit => {
@@ -169,7 +169,7 @@ fn get_column_val(jdb: &dyn EseDb, table_id: u64, c: &ColumnInfo) -> Result<Stri
             }
         }
         _ => {
-            return Err(SimpleError::new(format!(
+            return Err(EseError::new(format!(
                 "Incorrect column type: {}, max is 19",
                 c.typ
             )));
@@ -182,7 +182,7 @@ type Row = Vec<Vec<String>>;
 type Col = Vec<ColumnInfo>;
 type Table = (Col, Row);
 
-fn dump_table(jdb: &dyn EseDb, t: &str) -> Result<Option<Table>, SimpleError> {
+fn dump_table(jdb: &dyn EseDb, t: &str) -> Result<Option<Table>, EseError> {
     let table_id = jdb.open_table(t)?;
     let cols = jdb.get_columns(t)?;
     if !jdb.move_row(table_id, ESE_MoveFirst)? {
@@ -387,3 +387,42 @@ impl FromBytes for f64 {
         f64::from_le_bytes(bytes.try_into().unwrap())
     }
 }
+
+pub fn export_csv(dbpath: &str, mode: Mode, table: &str) {
+    let jdb = alloc_jdb(&mode, dbpath);
+    let stdout = io::stdout();
+    let mut out = io::BufWriter::new(stdout.lock());
+    match jdb.export_table_to_csv(table, &mut out, &ese_parser_lib::csv::CsvOptions::default()) {
+        Ok(rows) => eprintln!("{} rows exported", rows),
+        Err(e) => {
+            eprintln!("export of {} failed: {}", table, e);
+            std::process::exit(-1);
+        }
+    }
+}
+
+// database header information, as a table or as JSON (with the `serde` feature)
+pub fn print_db_info(dbpath: &str, json: bool) {
+    let info = EseParser::load_from_path(CACHE_SIZE_ENTRIES, dbpath).and_then(|jdb| jdb.db_info());
+    let info = match info {
+        Ok(info) => info,
+        Err(e) => {
+            eprintln!("can't read the header of {}: {}", dbpath, e);
+            std::process::exit(-1);
+        }
+    };
+    if !json {
+        print!("{}", info);
+        return;
+    }
+    #[cfg(feature = "serde")]
+    match serde_json::to_string_pretty(&info) {
+        Ok(s) => println!("{}", s),
+        Err(e) => eprintln!("JSON serialization failed: {}", e),
+    }
+    #[cfg(not(feature = "serde"))]
+    {
+        eprintln!("--json requires the serde feature");
+        std::process::exit(-1);
+    }
+}
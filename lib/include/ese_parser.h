@@ -0,0 +1,63 @@
+/*
+ * C interface of ese_parser_lib, built with the `ffi` feature:
+ *   cargo rustc --release --features ffi --crate-type cdylib
+ */
+#ifndef ESE_PARSER_H
+#define ESE_PARSER_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define ESE_PARSER_OK 0
+#define ESE_PARSER_NULL_POINTER (-1)
+#define ESE_PARSER_INVALID_STRING (-2)
+#define ESE_PARSER_NOT_LOADED (-3)
+#define ESE_PARSER_ERROR (-4)
+#define ESE_PARSER_BUFFER_TOO_SMALL (-5)
+/* a panic of the library, caught before reaching the caller */
+#define ESE_PARSER_PANIC (-6)
+/* ese_parser_get_column: the value is null */
+#define ESE_PARSER_NULL_VALUE 1
+/* ese_parser_open_table failed */
+#define ESE_PARSER_INVALID_TABLE UINT64_MAX
+
+/* directions of ese_parser_move_row */
+#define ESE_MoveFirst 0x80000000u
+#define ESE_MovePrevious 0xffffffffu
+#define ESE_MoveNext 1u
+#define ESE_MoveLast 0x7fffffffu
+
+typedef struct EseParser EseParser;
+
+/* NULL on a panic */
+EseParser *ese_parser_create(uint32_t cache_size);
+
+/* path is UTF-8 */
+int ese_parser_load(EseParser *p, const char *path);
+
+/* *names: NULL-terminated array of the table names, freed by ese_parser_free_tables;
+ * *count: the number of names */
+int ese_parser_get_tables(EseParser *p, char ***names, uint32_t *count);
+void ese_parser_free_tables(char **names);
+
+/* returns ESE_PARSER_INVALID_TABLE on errors and panics */
+uint64_t ese_parser_open_table(EseParser *p, const char *name);
+
+/* returns 1 on a row, 0 past the first or last row */
+int ese_parser_move_row(EseParser *p, uint64_t table_id, uint32_t direction);
+
+/* *out_len: the size of out_buf on input, the size of the value on output;
+ * ESE_PARSER_BUFFER_TOO_SMALL if out_buf is NULL or too small */
+int ese_parser_get_column(EseParser *p, uint64_t table_id, uint32_t col_id,
+                          uint8_t *out_buf, uint32_t *out_len);
+
+void ese_parser_destroy(EseParser *p);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* ESE_PARSER_H */
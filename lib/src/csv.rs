@@ -0,0 +1,150 @@
+//csv.rs
+use crate::error::EseError;
+use crate::ese_trait::*;
+use crate::utils::guid_to_string;
+use crate::value::Value;
+use std::io::Write;
+
+/// How binary columns are written
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum BinaryEncoding {
+    #[default]
+    Hex,
+    Base64,
+}
+
+#[derive(Clone, Debug)]
+pub struct CsvOptions {
+    pub delimiter: char,
+    pub binary_encoding: BinaryEncoding,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: ',',
+            binary_encoding: BinaryEncoding::Hex,
+        }
+    }
+}
+
+impl BinaryEncoding {
+    pub(crate) fn encode(self, v: Vec<u8>) -> String {
+        match self {
+            BinaryEncoding::Hex => Value::Binary(v).to_string(),
+            BinaryEncoding::Base64 => to_base64(&v),
+        }
+    }
+}
+
+pub(crate) fn to_base64(v: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut s = String::with_capacity(v.len().div_ceil(3) * 4);
+    for chunk in v.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                s.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                s.push('=');
+            }
+        }
+    }
+    s
+}
+
+// quoted if it holds the delimiter, a quote or a line break
+fn quote_field(field: &str, delimiter: char) -> String {
+    if field.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn to_csv_field(column: &ColumnInfo, v: Vec<u8>, opts: &CsvOptions) -> Result<String, EseError> {
+    // invalid characters of text values are replaced, the export goes on
+    let field = match Value::from_column(column, &v, true)? {
+        Value::Bool(b) => (b as u8).to_string(),
+        // the raw value, scaled by 10000
+        Value::Currency(raw) => raw.to_string(),
+        Value::Guid(g) => guid_to_string(&g.0),
+        Value::Binary(_) => opts.binary_encoding.encode(v),
+        value => value.to_string(),
+    };
+    Ok(field)
+}
+
+/// Writes `table` as CSV, a header row with the column names and then a line per row.
+/// Null values are empty fields, multi-valued columns are written with their first value.
+/// Rows are written as they are read, returns the number of rows.
+/// The table is closed on errors too.
+pub fn export_table<D: EseDb + ?Sized>(
+    db: &D,
+    table: &str,
+    out: &mut dyn Write,
+    opts: &CsvOptions,
+) -> Result<u64, EseError> {
+    let delimiter = opts.delimiter.to_string();
+    let columns = db.get_columns(table)?;
+    let header = columns
+        .iter()
+        .map(|c| quote_field(&c.name, opts.delimiter))
+        .collect::<Vec<_>>()
+        .join(&delimiter);
+    writeln!(out, "{}", header)?;
+
+    let table_id = db.open_table(table)?;
+    let rows = write_rows(db, table_id, &columns, out, opts);
+    db.close_table(table_id);
+    rows
+}
+
+fn write_rows<D: EseDb + ?Sized>(
+    db: &D,
+    table_id: u64,
+    columns: &[ColumnInfo],
+    out: &mut dyn Write,
+    opts: &CsvOptions,
+) -> Result<u64, EseError> {
+    let delimiter = opts.delimiter.to_string();
+    let mut rows = 0;
+    let mut has_row = db.move_row(table_id, ESE_MoveFirst)?;
+    while has_row {
+        let mut fields = Vec::with_capacity(columns.len());
+        for c in columns {
+            fields.push(match db.get_column(table_id, c.id)? {
+                Some(v) => quote_field(&to_csv_field(c, v, opts)?, opts.delimiter),
+                None => String::new(),
+            });
+        }
+        writeln!(out, "{}", fields.join(&delimiter))?;
+        rows += 1;
+        has_row = db.move_row(table_id, ESE_MoveNext)?;
+    }
+    Ok(rows)
+}
+
+#[test]
+fn test_to_base64() {
+    assert_eq!(to_base64(b""), "");
+    assert_eq!(to_base64(b"f"), "Zg==");
+    assert_eq!(to_base64(b"fo"), "Zm8=");
+    assert_eq!(to_base64(b"foo"), "Zm9v");
+    assert_eq!(to_base64(b"foobar"), "Zm9vYmFy");
+    assert_eq!(to_base64(&[0xfb, 0xff]), "+/8=");
+}
+
+#[test]
+fn test_quote_field() {
+    assert_eq!(quote_field("abc", ','), "abc");
+    assert_eq!(quote_field("a,b", ','), "\"a,b\"");
+    assert_eq!(quote_field("a,b", ';'), "a,b");
+    assert_eq!(quote_field("say \"hi\"", ','), "\"say \"\"hi\"\"\"");
+    assert_eq!(quote_field("two\nlines", ','), "\"two\nlines\"");
+}
@@ -0,0 +1,93 @@
+//error.rs
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum EseError {
+    Io(io::Error),
+    BadSignature,
+    // file header checksum
+    ChecksumMismatch {
+        stored: u32,
+        calculated: u32,
+    },
+    BadFormatVersion(u32),
+    BadPageNumber {
+        page: u32,
+        max: u32,
+    },
+    // page checksum, in ChecksumMode::Strict
+    PageChecksumMismatch {
+        page: u32,
+        block: usize,
+        stored: u64,
+        computed: u64,
+    },
+    CorruptCatalog(String),
+    ColumnNotFound(u32),
+    // lookups by name
+    ColumnNameNotFound(String),
+    TableNotFound(String),
+    LvKeyNotFound(u64),
+    // the table (by id) is empty or its cursor isn't on a record
+    NoCursorPosition(u64),
+    Other(String),
+}
+
+impl EseError {
+    pub fn new<S: Into<String>>(description: S) -> Self {
+        EseError::Other(description.into())
+    }
+}
+
+impl fmt::Display for EseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EseError::Io(e) => write!(f, "I/O error: {}", e),
+            EseError::BadSignature => write!(f, "bad file header signature"),
+            EseError::ChecksumMismatch { stored, calculated } => {
+                write!(f, "wrong checksum: {}, calculated {}", stored, calculated)
+            }
+            EseError::BadFormatVersion(version) => {
+                write!(f, "unsupported format version: 0x{:x}", version)
+            }
+            EseError::BadPageNumber { page, max } => {
+                write!(f, "page number {} is out of file (max {})", page, max)
+            }
+            EseError::PageChecksumMismatch {
+                page,
+                block,
+                stored,
+                computed,
+            } => write!(
+                f,
+                "wrong checksum of page {} (block {}): 0x{:x}, calculated 0x{:x}",
+                page, block, stored, computed
+            ),
+            EseError::CorruptCatalog(s) => write!(f, "corrupt catalog: {}", s),
+            EseError::ColumnNotFound(id) => write!(f, "column {} not found", id),
+            EseError::ColumnNameNotFound(name) => write!(f, "column {} not found", name),
+            EseError::TableNotFound(name) => write!(f, "table {} not found", name),
+            EseError::LvKeyNotFound(key) => write!(f, "long value key {} not found", key),
+            EseError::NoCursorPosition(table_id) => {
+                write!(f, "no cursor position in table {}", table_id)
+            }
+            EseError::Other(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl std::error::Error for EseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EseError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for EseError {
+    fn from(e: io::Error) -> Self {
+        EseError::Io(e)
+    }
+}
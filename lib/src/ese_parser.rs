@@ -1,14 +1,21 @@
 use crate::ese_trait::*;
+use crate::parser::backend::{CursorBackend, FileBackend, ReaderBackend};
 use crate::parser::reader::*;
 use crate::parser::*;
+use crate::value::Value;
+use crate::vartime::*;
 
-use simple_error::SimpleError;
+use crate::error::EseError;
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
 use std::cell::{RefCell, RefMut};
 use std::cmp::Ordering;
-use std::collections::HashMap;
-use std::fs::File;
-use std::io::BufReader;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::io::Write;
+use std::mem;
 use std::path::Path;
+use std::rc::Rc;
 
 #[derive(Debug, PartialEq)]
 enum Direction {
@@ -45,11 +52,25 @@ impl CurrentPage {
     }
 }
 
+// cursor of an outer open_table of the same table, restored by close_table
+struct SavedCursor {
+    current_page: CurrentPage,
+    page_tag_index: usize,
+    row_position: Option<u64>,
+    validity_info: ValidityInfo,
+}
+
 struct Table {
     cat: Box<jet::TableDefinition>,
     lv_tags: LV_tags,
     current_page: CurrentPage,
     page_tag_index: usize,
+    // 0-based index of the current row, None when unknown (after MoveLast or past the end)
+    row_position: Option<u64>,
+    // bumped on every move of the cursor, the LastLoadState of another row is never reused
+    row_generation: u64,
+    is_open: bool,
+    saved_cursors: Vec<SavedCursor>,
     lls: RefCell<LastLoadState>,
     validity_info: ValidityInfo,
 }
@@ -61,15 +82,23 @@ impl Table {
 
     fn review_last_load_state(&mut self, column: u32) {
         let mut lls = self.lls.borrow_mut();
-        if lls.page_number != self.page().page_number
+        if lls.row_generation != self.row_generation
+            || lls.page_number != self.page().page_number
             || lls.page_tag_index != self.page_tag_index
             || column <= lls.last_column
         {
             // reset
             *lls = LastLoadState::init(self.page().page_number, self.page_tag_index);
+            lls.row_generation = self.row_generation;
         }
     }
 
+    // moves the cursor to the record `page_tag_index` of the current page
+    fn set_row(&mut self, page_tag_index: usize) {
+        self.page_tag_index = page_tag_index;
+        self.row_generation = self.row_generation.wrapping_add(1);
+    }
+
     fn update_validity_info_for_crow(&mut self, crow: i32) {
         if crow == ESE_MoveFirst {
             self.validity_info.visited_pages.clear(); // if we're going to the beginning, clear out any previous visited into
@@ -103,9 +132,9 @@ impl Table {
         self.validity_info.visited_pages.push(page)
     }
 
-    fn set_current_page(&mut self, page: jet::DbPage) -> Result<bool, SimpleError> {
+    fn set_current_page(&mut self, page: jet::DbPage) -> Result<bool, EseError> {
         if self.already_visited_page(page.page_number) {
-            Err(SimpleError::new(format!(
+            Err(EseError::new(format!(
                 "Circular page reference identified for page_number: {}",
                 page.page_number
             )))
@@ -117,31 +146,700 @@ impl Table {
     }
 }
 
-pub struct EseParser<R: ReadSeek> {
+// column value -> (page number, page tag index) of the rows holding it
+pub type InMemoryIndex = BTreeMap<Vec<u8>, Vec<(u32, usize)>>;
+
+/// Index of a table, as defined in the catalog
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IndexDefinition {
+    pub name: String,
+    pub identifier: u32,
+    // object identifier of the father data page of the table
+    pub father_data_page_object_identifier: u32,
+    // root page of the index B-tree
+    pub father_data_page_number: u32,
+    pub flags: jet::IndexFlags,
+    pub lcmap_flags: u32,
+    // KeyFldIDs, in key order
+    pub key_column_ids: Vec<u32>,
+    pub conditional_column_ids: Vec<u32>,
+}
+
+/// Column of a table, as defined in the catalog
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnMeta {
+    pub name: String,
+    pub id: u32,
+    pub typ: u32,
+    pub cbmax: u32,
+    pub cp: u16,
+    pub kind: ColumnStorageClass,
+    pub flags: jet::ColumnFlags,
+    pub has_default_value: bool,
+}
+
+/// Table space information, from the catalog and the root page header of the table
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableMeta {
+    pub name: String,
+    pub object_identifier: u32,
+    // father data page (root page) of the table B-tree
+    pub father_data_page_number: u32,
+    // initial number of pages, as defined in the catalog
+    pub space_usage: u32,
+    pub flags: u32,
+    // from the root page header
+    pub initial_number_of_pages: u32,
+    pub parent_fdp: u32,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct IndexStatistics {
+    pub depth: u32,
+    pub leaf_page_count: u32,
+    pub branch_page_count: u32,
+    pub entry_count: u64,
+    pub avg_key_length: f64,
+    pub unique_key_count: u64,
+}
+
+// the walk is depth first: the page visited after a branch tag is its child, one level below the branch page
+struct IndexStatisticsVisitor<'a, R: ReaderBackend> {
+    reader: &'a Reader<R>,
+    unique_key_limit: u64,
+    stats: IndexStatistics,
+    keys: HashSet<Vec<u8>>,
+    key_length_total: u64,
+    // depth (0 on the root page) of the branch pages visited
+    page_depths: HashMap<u32, u32>,
+    // depth of the next page visited
+    child_depth: u32,
+}
+
+impl<'a, R: ReaderBackend> IndexStatisticsVisitor<'a, R> {
+    fn visit_page(&mut self, page: &jet::DbPage) {
+        self.stats.depth = self.stats.depth.max(self.child_depth + 1);
+        self.page_depths.insert(page.page_number, self.child_depth);
+    }
+}
+
+impl<'a, R: ReaderBackend> btree::BTreeVisitor for IndexStatisticsVisitor<'a, R> {
+    fn visit_branch_page(&mut self, page: &jet::DbPage) -> Result<btree::VisitDecision, EseError> {
+        self.stats.branch_page_count += 1;
+        self.visit_page(page);
+        Ok(btree::VisitDecision::Continue)
+    }
+
+    fn visit_leaf_page(&mut self, page: &jet::DbPage) -> Result<btree::VisitDecision, EseError> {
+        self.stats.leaf_page_count += 1;
+        self.visit_page(page);
+        Ok(btree::VisitDecision::Continue)
+    }
+
+    fn visit_tag(
+        &mut self,
+        page: &jet::DbPage,
+        tag: &ese_db::PageTag,
+        _tag_index: usize,
+    ) -> Result<btree::VisitDecision, EseError> {
+        if !page.flags().contains(jet::PageFlags::IS_LEAF) {
+            self.child_depth = self.page_depths[&page.page_number] + 1;
+            return Ok(btree::VisitDecision::Continue);
+        }
+        let key = self.reader.load_page_tag_key(page, tag)?;
+        self.stats.entry_count += 1;
+        self.key_length_total += key.len() as u64;
+        if self.stats.entry_count <= self.unique_key_limit {
+            self.keys.insert(key);
+        }
+        Ok(btree::VisitDecision::Continue)
+    }
+}
+
+/// Numbers of catalog entries by entry type.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CatalogEntryCounts {
+    pub tables: usize,
+    pub columns: usize,
+    pub indexes: usize,
+    pub callbacks: usize,
+    pub long_values: usize,
+}
+
+impl CatalogEntryCounts {
+    fn add(&mut self, cat: &jet::TableDefinition) {
+        self.tables += cat.table_catalog_definition.iter().count();
+        self.columns += cat.column_catalog_definition_array.len();
+        self.indexes += cat.index_catalog_definition_array.len();
+        self.callbacks += cat.callback_catalog_definition_array.len();
+        self.long_values += cat.long_value_catalog_definition.iter().count();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthIssue {
+    pub severity: Severity,
+    pub description: String,
+}
+
+/// Health of the database from its file header and catalogs, see `get_database_health_report`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthReport {
+    pub ecc_fix_success_count: u32,
+    pub ecc_fix_error_count: u32,
+    pub bad_checksum_error_count: u32,
+    pub dirty_shutdown: bool,
+    pub shadow_catalog_diverges: bool,
+    pub repair_count: u32,
+    pub format_description: String,
+    pub issues: Vec<HealthIssue>,
+}
+
+/// Signature of the database, see `DbInfo`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DbSignature {
+    pub random: u32,
+    pub computer_name: String,
+    pub creation_time: Option<DateTime<Utc>>,
+}
+
+/// Database information from the file header, see `db_info`.
+/// Its `Display` is a table of the values, one per line.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DbInfo {
+    pub format_version: u32,
+    pub format_revision: u32,
+    pub page_size: u32,
+    pub database_state: jet::DbState,
+    pub attach_time: Option<DateTime<Utc>>,
+    pub detach_time: Option<DateTime<Utc>>,
+    pub signature: DbSignature,
+    pub last_object_identifier: u32,
+    pub ecc_fix_success_count: u32,
+    pub ecc_fix_error_count: u32,
+    pub bad_checksum_error_count: u32,
+    // pages of the file, without the two file header pages
+    pub page_count: u64,
+}
+
+impl fmt::Display for DbInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let time = |t: &Option<DateTime<Utc>>| match t {
+            Some(t) => t.to_rfc3339(),
+            None => "-".to_string(),
+        };
+        let rows = [
+            (
+                "Format",
+                jet::revision_to_string(self.format_version, self.format_revision),
+            ),
+            ("Page size", self.page_size.to_string()),
+            ("Pages", self.page_count.to_string()),
+            ("State", self.database_state.to_string()),
+            ("Created", time(&self.signature.creation_time)),
+            ("Attached", time(&self.attach_time)),
+            ("Detached", time(&self.detach_time)),
+            ("Signature", format!("0x{:08x}", self.signature.random)),
+            ("Computer name", self.signature.computer_name.clone()),
+            (
+                "Last object identifier",
+                self.last_object_identifier.to_string(),
+            ),
+            (
+                "ECC fixes",
+                format!(
+                    "{} ({} failed)",
+                    self.ecc_fix_success_count, self.ecc_fix_error_count
+                ),
+            ),
+            ("Checksum errors", self.bad_checksum_error_count.to_string()),
+        ];
+        for (name, value) in rows {
+            writeln!(f, "{:<24}{}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Page failing its checksum, see `integrity_check`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorruptPage {
+    pub page_number: u32,
+    // checksum block of the page, pages larger than 8K have 4 blocks
+    pub block: usize,
+    pub stored: u64,
+    pub computed: u64,
+}
+
+/// Page which couldn't be read, see `integrity_check`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnreadablePage {
+    pub page_number: u32,
+    pub error: String,
+}
+
+/// Result of the scan of all the pages of the database, see `integrity_check`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IntegrityReport {
+    pub total_pages: u32,
+    pub corrupt_pages: Vec<CorruptPage>,
+    pub unreadable_pages: Vec<UnreadablePage>,
+    // never used (zeroed) or emptied pages
+    pub free_pages: u32,
+    pub lv_pages: u32,
+}
+
+/// Extent of pages from the space trees.
+/// Pages used by the database itself (its root and space tree pages) are owned by object 1,
+/// free pages of the database have no owner.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageAllocation {
+    pub start_page: u32,
+    pub extent_size: u32,
+    pub is_free: bool,
+    pub owner_object_id: Option<u32>,
+}
+
+// leaf pages read by estimate_row_count for the average record count
+const ESTIMATE_SAMPLE_PAGES: u64 = 4;
+
+const ALLOCATION_MAP_WIDTH: usize = 64;
+const ALLOCATION_MAP_MAX_LINES: usize = 16;
+const ALLOCATION_MAP_SYMBOLS: &[u8] =
+    b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// Draws the allocation map of pages 1..=`total_pages`, one character per cell.
+/// Each owner gets its own symbol, free pages are `.` and pages out of any extent are blank.
+/// A cell covers several pages for large databases, its symbol is the one of most of its pages.
+pub fn format_page_allocation_map(allocs: &[PageAllocation], total_pages: u32) -> String {
+    if total_pages == 0 {
+        return String::new();
+    }
+    let owners: BTreeSet<u32> = allocs
+        .iter()
+        .filter(|a| !a.is_free)
+        .filter_map(|a| a.owner_object_id)
+        .collect();
+    let symbols: BTreeMap<u32, char> = owners
+        .into_iter()
+        .zip(ALLOCATION_MAP_SYMBOLS.iter().cycle())
+        .map(|(owner, &symbol)| (owner, symbol as char))
+        .collect();
+    let mut pages = vec![' '; total_pages as usize];
+    for a in allocs {
+        let symbol = match (a.is_free, a.owner_object_id) {
+            (false, Some(owner)) => symbols[&owner],
+            (false, None) => '?',
+            (true, _) => '.',
+        };
+        let start = a.start_page.max(1) as usize - 1;
+        let end = (a.start_page as usize + a.extent_size as usize - 1).min(pages.len());
+        for p in pages.iter_mut().take(end).skip(start) {
+            *p = symbol;
+        }
+    }
+
+    let max_cells = ALLOCATION_MAP_WIDTH * ALLOCATION_MAP_MAX_LINES;
+    let pages_per_cell = pages.len().div_ceil(max_cells);
+    let mut map = String::new();
+    for (line, line_pages) in pages
+        .chunks(ALLOCATION_MAP_WIDTH * pages_per_cell)
+        .enumerate()
+    {
+        map += &format!("{:>10} |", line * ALLOCATION_MAP_WIDTH * pages_per_cell + 1);
+        for cell in line_pages.chunks(pages_per_cell) {
+            let mut counts: BTreeMap<char, usize> = BTreeMap::new();
+            for &p in cell {
+                *counts.entry(p).or_default() += 1;
+            }
+            let symbol = counts
+                .iter()
+                .max_by_key(|(_, &count)| count)
+                .map(|(&p, _)| p)
+                .unwrap_or(' ');
+            map.push(symbol);
+        }
+        map += "|\n";
+    }
+    map += &format!("{} page(s) per cell, '.' free\n", pages_per_cell);
+    for (owner, symbol) in symbols {
+        map += &format!("'{}' object {}\n", symbol, owner);
+    }
+    map
+}
+
+/// Iterator over a chain of pages linked by `next_page`, yields page numbers with their tags.
+pub struct PageChainIter<'a, R: ReaderBackend> {
+    reader: &'a Reader<R>,
+    next_page: u32,
+    visited_pages: HashSet<u32>,
+    error: Option<EseError>,
+}
+
+impl<'a, R: ReaderBackend> Iterator for PageChainIter<'a, R> {
+    type Item = Result<(u32, Vec<ese_db::PageTag>), EseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(e) = self.error.take() {
+            self.next_page = 0;
+            return Some(Err(e));
+        }
+        if self.next_page == 0 {
+            return None;
+        }
+        let page_number = self.next_page;
+        self.next_page = 0;
+        if !self.visited_pages.insert(page_number) {
+            return Some(Err(EseError::new(format!(
+                "Circular page reference identified for page_number: {}",
+                page_number
+            ))));
+        }
+        match jet::DbPage::new(self.reader, page_number) {
+            Ok(db_page) => {
+                self.next_page = db_page.next_page();
+                Some(Ok((page_number, db_page.page_tags)))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+// column identifier -> value of a single row
+pub type RowData = HashMap<u32, Option<Vec<u8>>>;
+
+/// Iterator over the rows of an open table, moves the cursor of the table.
+pub struct RowIter<'a, R: ReaderBackend> {
+    parser: &'a EseParser<R>,
+    table_id: u64,
+    columns: Vec<u32>,
+    started: bool,
+    done: bool,
+}
+
+impl<'a, R: ReaderBackend> RowIter<'a, R> {
+    fn read_row(&mut self) -> Result<Option<RowData>, EseError> {
+        let crow = if self.started {
+            ESE_MoveNext
+        } else {
+            ESE_MoveFirst
+        };
+        self.started = true;
+        if !self.parser.move_row(self.table_id, crow)? {
+            return Ok(None);
+        }
+        let mut row = RowData::new();
+        for &column in &self.columns {
+            row.insert(column, self.parser.get_column(self.table_id, column)?);
+        }
+        Ok(Some(row))
+    }
+}
+
+impl<'a, R: ReaderBackend> Iterator for RowIter<'a, R> {
+    type Item = Result<RowData, EseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let row = self.read_row().transpose();
+        // stop after the last row or an error
+        self.done = !matches!(row, Some(Ok(_)));
+        row
+    }
+}
+
+/// Values of a row read by `TableRowIter`, looked up by column name.
+#[derive(Debug, Clone)]
+pub struct Row {
+    columns: Rc<Vec<ColumnInfo>>,
+    values: Vec<Option<Vec<u8>>>,
+    deleted: bool,
+}
+
+impl Row {
+    pub fn columns(&self) -> &[ColumnInfo] {
+        &self.columns
+    }
+
+    /// The record is deleted (its page tag is defunct), see `ParserOptions::include_deleted`.
+    pub fn is_deleted(&self) -> bool {
+        self.deleted
+    }
+
+    // names are case-insensitive, an exact match is preferred
+    fn find(&self, column_name: &str) -> Result<(&ColumnInfo, Option<&[u8]>), EseError> {
+        let names = self.columns.iter().map(|c| c.name.as_str());
+        match crate::utils::find_object_name(names, column_name) {
+            Some(i) => Ok((&self.columns[i], self.values[i].as_deref())),
+            None => Err(EseError::ColumnNameNotFound(column_name.into())),
+        }
+    }
+
+    /// Raw value of the column (the first one of a multi-valued column), `None` if it's null.
+    pub fn get(&self, column_name: &str) -> Result<Option<Vec<u8>>, EseError> {
+        Ok(self.find(column_name)?.1.map(|v| v.to_vec()))
+    }
+
+    /// Value of a fixed size column, converted to `T`.
+    pub fn get_as<T: crate::ese_trait::FromBytes>(
+        &self,
+        column_name: &str,
+    ) -> Result<Option<T>, EseError> {
+        match self.find(column_name)?.1 {
+            Some(v) if v.len() != mem::size_of::<T>() => Err(EseError::new(format!(
+                "column {}: unexpected value size {}, expected {}",
+                column_name,
+                v.len(),
+                mem::size_of::<T>()
+            ))),
+            Some(v) => Ok(Some(T::from_bytes(v))),
+            None => Ok(None),
+        }
+    }
+
+    /// Value of a text column, decoded by the code page of the column.
+    pub fn get_str(&self, column_name: &str) -> Result<Option<String>, EseError> {
+        let (column, value) = self.find(column_name)?;
+        let v = match value {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+        crate::utils::decode_text(v, column.cp, false)
+            .map(Some)
+            .map_err(|offset| {
+                EseError::new(format!(
+                    "column {}: invalid text in code page {} at byte offset {}",
+                    column_name, column.cp, offset
+                ))
+            })
+    }
+
+    /// Value of a DateTime column.
+    pub fn get_date(&self, column_name: &str) -> Result<Option<DateTime<Utc>>, EseError> {
+        match self.find(column_name)?.1 {
+            Some(v) => date_from_bytes(v),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Iterator over the rows of a table with its own position,
+/// neither the cursor of the table nor other iterators are moved.
+pub struct TableRowIter<'a, R: ReaderBackend> {
+    reader: &'a Reader<R>,
+    cat: Box<jet::TableDefinition>,
+    lv_tags: LV_tags,
+    columns: Rc<Vec<ColumnInfo>>,
+    page: Option<jet::DbPage>,
+    page_tag_index: usize,
+    visited_pages: HashSet<u32>,
+    include_deleted: bool,
+}
+
+impl<'a, R: ReaderBackend> TableRowIter<'a, R> {
+    fn read_row(&mut self) -> Result<Option<Row>, EseError> {
+        loop {
+            let page = match &self.page {
+                Some(page) => page,
+                None => return Ok(None),
+            };
+            let mut i = self.page_tag_index + 1;
+            while i < page.page_tags.len()
+                && !self.include_deleted
+                && page.page_tags[i]
+                    .flags()
+                    .intersects(jet::PageTagFlags::FLAG_IS_DEFUNCT)
+            {
+                i += 1;
+            }
+            if i < page.page_tags.len() {
+                self.page_tag_index = i;
+                return self.load_row(page).map(Some);
+            }
+            let next_page = page.next_page();
+            self.page = None;
+            if next_page == 0 {
+                return Ok(None);
+            }
+            if !self.visited_pages.insert(next_page) {
+                return Err(EseError::new(format!(
+                    "Circular page reference identified for page_number: {}",
+                    next_page
+                )));
+            }
+            self.page = Some(jet::DbPage::new(self.reader, next_page)?);
+            self.page_tag_index = 0;
+        }
+    }
+
+    fn load_row(&self, page: &jet::DbPage) -> Result<Row, EseError> {
+        let mut lls = LastLoadState::init(page.page_number, self.page_tag_index);
+        let mut values = Vec::with_capacity(self.columns.len());
+        for column in self.columns.iter() {
+            if column.id <= lls.last_column {
+                lls = LastLoadState::init(page.page_number, self.page_tag_index);
+            }
+            values.push(self.reader.load_data(
+                &mut lls,
+                &self.cat,
+                &self.lv_tags,
+                page,
+                self.page_tag_index,
+                column.id,
+                0,
+            )?);
+            lls.last_column = column.id;
+        }
+        Ok(Row {
+            columns: Rc::clone(&self.columns),
+            values,
+            deleted: page.page_tags[self.page_tag_index]
+                .flags()
+                .intersects(jet::PageTagFlags::FLAG_IS_DEFUNCT),
+        })
+    }
+}
+
+impl<'a, R: ReaderBackend> Iterator for TableRowIter<'a, R> {
+    type Item = Result<Row, EseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let row = self.read_row().transpose();
+        if matches!(row, Some(Err(_))) {
+            // stop after an error
+            self.page = None;
+        }
+        row
+    }
+}
+
+/// Options of `EseParser::load_with_options`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ParserOptions {
+    pub checksum_mode: checksum::ChecksumMode,
+    // rows of deleted records (defunct page tags) are visited too
+    pub include_deleted: bool,
+    // pages loaded by one read when a scan moves to the next leaf page, see `Reader::set_read_ahead_pages`
+    pub read_ahead_pages: u32,
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        ParserOptions {
+            checksum_mode: Default::default(),
+            include_deleted: false,
+            read_ahead_pages: DEFAULT_READ_AHEAD_PAGES,
+        }
+    }
+}
+
+pub struct EseParser<R: ReaderBackend> {
     reader: Reader<R>,
     tables: Vec<RefCell<Table>>,
+    object_names: HashMap<u32, String>,
+    object_ids: HashMap<String, u32>,
+    include_deleted: bool,
 }
 
-impl EseParser<BufReader<File>> {
+impl EseParser<FileBackend> {
     /// Instantiates an instance of the parser from a file path.
     /// Does not mutate the file contents in any way.
     /// Useful for testing and sample programs.
-    pub fn load_from_path(
+    pub fn load_from_path(cache_size: usize, filename: impl AsRef<Path>) -> Result<Self, EseError> {
+        Self::load_from_path_with_options(cache_size, filename, ParserOptions::default())
+    }
+
+    /// Same as `load_from_path`, with the `options` of `load_with_options`.
+    pub fn load_from_path_with_options(
         cache_size: usize,
         filename: impl AsRef<Path>,
-    ) -> Result<Self, SimpleError> {
+        options: ParserOptions,
+    ) -> Result<Self, EseError> {
         let f = filename.as_ref();
-        let file = File::open(f).unwrap();
-        let buf_reader = BufReader::with_capacity(4096, file);
+        let mut parser =
+            Self::load_from_backend_with_options(cache_size, FileBackend::open(f)?, options)?;
+        parser.reader.set_path(f);
+        Ok(parser)
+    }
+}
 
-        Self::load(cache_size, buf_reader)
+#[cfg(feature = "mmap")]
+impl EseParser<backend::MemoryBackend<memmap2::Mmap>> {
+    /// Same as `load_from_path`, but the file is memory mapped (see `Reader::load_db_mmap`).
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated while the parser exists.
+    pub unsafe fn load_from_path_mmap(
+        cache_size: usize,
+        filename: impl AsRef<Path>,
+    ) -> Result<Self, EseError> {
+        let reader = Reader::load_db_mmap(filename, cache_size)?;
+        Self::from_reader(reader)
     }
 }
 
-impl<R: ReadSeek> EseParser<R> {
+impl<R: ReadSeek + Send> EseParser<CursorBackend<R>> {
     // reserve room for cache_size recent entries, and cache_size frequent entries
-    pub fn load(cache_size: usize, read_seek: R) -> Result<Self, SimpleError> {
-        let reader = Reader::load_db(read_seek, cache_size)?;
+    pub fn load(cache_size: usize, read_seek: R) -> Result<Self, EseError> {
+        Self::load_from_backend(cache_size, CursorBackend::new(read_seek))
+    }
+
+    /// Same as `load`, the pages are verified by `checksum_mode` when they are loaded.
+    pub fn load_with_checksum_mode(
+        cache_size: usize,
+        read_seek: R,
+        checksum_mode: checksum::ChecksumMode,
+    ) -> Result<Self, EseError> {
+        Self::from_reader(Reader::load_db_opts(read_seek, cache_size, checksum_mode)?)
+    }
+
+    /// Same as `load_from_backend_with_options`, for a `Read + Seek` source.
+    pub fn load_with_options(
+        cache_size: usize,
+        read_seek: R,
+        options: ParserOptions,
+    ) -> Result<Self, EseError> {
+        Self::load_from_backend_with_options(cache_size, CursorBackend::new(read_seek), options)
+    }
+}
+
+impl<R: ReaderBackend> EseParser<R> {
+    /// Same as `load`, the database is read from a `ReaderBackend` (see `Reader::from_backend`),
+    /// e.g. a `Box<dyn ReaderBackend>` when the source is only known at run time.
+    pub fn load_from_backend(cache_size: usize, backend: R) -> Result<Self, EseError> {
+        Self::from_reader(Reader::from_backend(backend, cache_size)?)
+    }
+
+    /// Same as `load_from_backend`, with the checksum mode of the pages, the deleted rows option
+    /// and the read-ahead of table scans.
+    /// Deleted rows are visited by `move_row` and `iter_rows` when `include_deleted` is set,
+    /// `is_current_row_deleted` and `Row::is_deleted` tell them apart.
+    pub fn load_from_backend_with_options(
+        cache_size: usize,
+        backend: R,
+        options: ParserOptions,
+    ) -> Result<Self, EseError> {
+        let mut reader = Reader::from_backend_opts(backend, cache_size, options.checksum_mode)?;
+        reader.set_read_ahead_pages(options.read_ahead_pages);
+        let mut parser = Self::from_reader(reader)?;
+        parser.include_deleted = options.include_deleted;
+        Ok(parser)
+    }
+
+    fn from_reader(reader: Reader<R>) -> Result<Self, EseError> {
         let mut cat = reader.load_catalog()?;
 
         let mut tables = vec![];
@@ -152,6 +850,10 @@ impl<R: ReadSeek> EseParser<R> {
                     lv_tags: HashMap::new(),
                     current_page: CurrentPage::default(),
                     page_tag_index: 0,
+                    row_position: None,
+                    row_generation: 0,
+                    is_open: false,
+                    saved_cursors: vec![],
                     lls: RefCell::new(LastLoadState {
                         ..Default::default()
                     }),
@@ -164,36 +866,98 @@ impl<R: ReadSeek> EseParser<R> {
             }
         }
 
-        Ok(EseParser { reader, tables })
+        let (object_names, object_ids) = Self::build_object_identifier_maps(&tables);
+        Ok(EseParser {
+            reader,
+            tables,
+            object_names,
+            object_ids,
+            include_deleted: false,
+        })
     }
 
-    fn get_table_by_name(
-        &self,
-        table: &str,
-        index: &mut usize,
-    ) -> Result<RefMut<Table>, SimpleError> {
-        for i in 0..self.tables.len() {
-            let n = self.tables[i].borrow_mut();
-            if let Some(table_catalog_definition) = &n.cat.table_catalog_definition {
-                if table_catalog_definition.name == table {
-                    *index = i;
-                    return Ok(n);
-                }
+    // Object identifiers are unique database-wide for tables, indexes and long-value trees.
+    // A primary index shares the identifier of its table, the table name is kept then.
+    fn build_object_identifier_maps(
+        tables: &[RefCell<Table>],
+    ) -> (HashMap<u32, String>, HashMap<String, u32>) {
+        let mut object_names = HashMap::new();
+        for t in tables {
+            let t = t.borrow();
+            if let Some(table_def) = &t.cat.table_catalog_definition {
+                object_names.insert(table_def.identifier, table_def.name.clone());
+            }
+        }
+        for t in tables {
+            let t = t.borrow();
+            for cat_def in t
+                .cat
+                .index_catalog_definition_array
+                .iter()
+                .chain(t.cat.long_value_catalog_definition.iter())
+            {
+                object_names
+                    .entry(cat_def.identifier)
+                    .or_insert_with(|| cat_def.name.clone());
+            }
+        }
+        // several objects may share a name (indexes of different tables), the lowest identifier wins
+        let mut ids: Vec<&u32> = object_names.keys().collect();
+        ids.sort();
+        let mut object_ids = HashMap::new();
+        for id in ids {
+            object_ids.entry(object_names[id].clone()).or_insert(*id);
+        }
+        (object_names, object_ids)
+    }
+
+    /// Maps object identifiers of tables, indexes and long-value trees to their names.
+    /// Column identifiers are per-table and aren't included.
+    pub fn get_object_identifier_map(&self) -> &HashMap<u32, String> {
+        &self.object_names
+    }
+
+    /// Reverse of `get_object_identifier_map`.
+    pub fn get_name_to_object_id_map(&self) -> &HashMap<String, u32> {
+        &self.object_ids
+    }
+
+    // table names are case-insensitive, an exact match is preferred
+    fn get_table_by_name(&self, table: &str, index: &mut usize) -> Result<RefMut<Table>, EseError> {
+        let found = {
+            let tables: Vec<_> = self.tables.iter().map(|t| t.borrow()).collect();
+            let named: Vec<(usize, &str)> = tables
+                .iter()
+                .enumerate()
+                .filter_map(|(i, t)| {
+                    t.cat
+                        .table_catalog_definition
+                        .as_ref()
+                        .map(|def| (i, def.name.as_str()))
+                })
+                .collect();
+            crate::utils::find_object_name(named.iter().map(|&(_, name)| name), table)
+                .map(|i| named[i].0)
+        };
+        match found {
+            Some(i) => {
+                *index = i;
+                Ok(self.tables[i].borrow_mut())
             }
+            None => Err(EseError::TableNotFound(table.to_string())),
         }
-        Err(SimpleError::new(format!("can't find table name {}", table)))
     }
 
-    fn get_reader(&self) -> Result<&Reader<R>, SimpleError> {
+    fn get_reader(&self) -> Result<&Reader<R>, EseError> {
         Ok(&self.reader)
     }
 
-    fn get_table_by_id(&self, table_id: u64) -> Result<RefMut<Table>, SimpleError> {
+    fn get_table_by_id(&self, table_id: u64) -> Result<RefMut<Table>, EseError> {
         let i = table_id as usize;
         if i < self.tables.len() {
             return Ok(self.tables[i].borrow_mut());
         }
-        Err(SimpleError::new(format!("out of range index {}", table_id)))
+        Err(EseError::TableNotFound(table_id.to_string()))
     }
 
     fn get_column_dyn_helper(
@@ -201,16 +965,23 @@ impl<R: ReadSeek> EseParser<R> {
         table_id: u64,
         column: u32,
         mv_index: u32,
-    ) -> Result<Option<Vec<u8>>, SimpleError> {
+        mode: TaggedLoadMode,
+    ) -> Result<Option<Vec<u8>>, EseError> {
         let mut table = self.get_table_by_id(table_id)?;
         let reader = self.get_reader()?;
         if table.current_page.is_none() {
-            return Err(SimpleError::new(
+            return Err(EseError::new(
                 "no current page, use open_table API before this",
             ));
         }
+        if column <= 255 && mv_index > 1 {
+            // fixed and variable size columns hold a single value,
+            // the last load state is kept for the next column
+            return Ok(None);
+        }
         table.review_last_load_state(column);
         let mut lls = table.lls.borrow_mut();
+        lls.tagged_column.mode = mode;
         match reader.load_data(
             &mut lls,
             &table.cat,
@@ -228,7 +999,7 @@ impl<R: ReadSeek> EseParser<R> {
         }
     }
 
-    fn move_next_row(&self, table_id: u64, crow: i32) -> Result<bool, SimpleError> {
+    fn move_next_row(&self, table_id: u64, crow: i32) -> Result<bool, EseError> {
         let reader = self.get_reader()?;
         let mut t = self.get_table_by_id(table_id)?;
         t.update_validity_info_for_crow(crow);
@@ -250,12 +1021,14 @@ impl<R: ReadSeek> EseParser<R> {
             }
             if t.page().page_tags.len() < 2 {
                 // empty table
+                t.row_position = None;
                 return Ok(false);
             }
             i = 1;
         }
         loop {
             while i < t.page().page_tags.len()
+                && !self.include_deleted
                 && t.page().page_tags[i]
                     .flags()
                     .intersects(jet::PageTagFlags::FLAG_IS_DEFUNCT)
@@ -264,25 +1037,34 @@ impl<R: ReadSeek> EseParser<R> {
             }
             if i < t.page().page_tags.len() {
                 // found non-free data tag
-                t.page_tag_index = i;
+                t.set_row(i);
+                t.row_position = if crow == ESE_MoveFirst {
+                    Some(0)
+                } else {
+                    t.row_position.map(|p| p + 1)
+                };
                 return Ok(true);
             } else if t.page().common().next_page != 0 {
-                let page = jet::DbPage::new(self.get_reader()?, t.page().common().next_page)?;
+                // sequential scan, the following leaf pages are likely next
+                let next_page = t.page().common().next_page;
+                reader.read_ahead(next_page)?;
+                let page = jet::DbPage::new(reader, next_page)?;
                 t.set_current_page(page)?;
                 i = 1;
             } else {
                 // no more leaf pages
+                t.row_position = None;
                 return Ok(false);
             }
         }
     }
 
-    fn move_previous_row(&self, table_id: u64, crow: i32) -> Result<bool, SimpleError> {
+    fn move_previous_row(&self, table_id: u64, crow: i32) -> Result<bool, EseError> {
         let reader = self.get_reader()?;
         let mut t = self.get_table_by_id(table_id)?;
         t.update_validity_info_for_crow(crow);
 
-        let mut i = t.page_tag_index - 1;
+        let mut i = t.page_tag_index.saturating_sub(1);
         if crow == ESE_MoveLast {
             while t.page().common().next_page != 0 {
                 let page = jet::DbPage::new(reader, t.page().common().next_page)?;
@@ -290,12 +1072,14 @@ impl<R: ReadSeek> EseParser<R> {
             }
             if t.page().page_tags.len() < 2 {
                 // empty table
+                t.row_position = None;
                 return Ok(false);
             }
             i = t.page().page_tags.len() - 1;
         }
         loop {
             while i > 0
+                && !self.include_deleted
                 && t.page().page_tags[i]
                     .flags()
                     .intersects(jet::PageTagFlags::FLAG_IS_DEFUNCT)
@@ -304,7 +1088,12 @@ impl<R: ReadSeek> EseParser<R> {
             }
             if i > 0 {
                 // found non-free data tag
-                t.page_tag_index = i;
+                t.set_row(i);
+                t.row_position = if crow == ESE_MoveLast {
+                    None
+                } else {
+                    t.row_position.and_then(|p| p.checked_sub(1))
+                };
                 return Ok(true);
             } else if t.page().common().previous_page != 0 {
                 let page = jet::DbPage::new(reader, t.page().common().previous_page)?;
@@ -312,12 +1101,13 @@ impl<R: ReadSeek> EseParser<R> {
                 i = t.page().page_tags.len() - 1;
             } else {
                 // no more leaf pages
+                t.row_position = None;
                 return Ok(false);
             }
         }
     }
 
-    fn move_row_helper(&self, table_id: u64, crow: i32) -> Result<bool, SimpleError> {
+    fn move_row_helper(&self, table_id: u64, crow: i32) -> Result<bool, EseError> {
         if crow == ESE_MoveFirst || crow == ESE_MoveNext {
             self.move_next_row(table_id, crow)
         } else if crow == ESE_MoveLast || crow == ESE_MovePrevious {
@@ -359,75 +1149,1511 @@ impl<R: ReadSeek> EseParser<R> {
         }
     }
 
-    pub fn get_fixed_column<T: FromBytes>(
-        &self,
-        table: u64,
-        column: u32,
-    ) -> Result<Option<T>, SimpleError> {
-        match self.get_column(table, column)? {
-            Some(v) => Ok(Some(T::from_bytes(&v))),
-            None => Ok(None),
+    /// Positions the cursor on the record with the primary key `key`, descending the B-tree
+    /// of the table instead of scanning its rows.
+    /// Returns `false` if there is no such record, the cursor is then left on the first record
+    /// with a greater key (or on the last record), so `move_row` continues from there.
+    pub fn find_row(&self, table_id: u64, key: &[u8]) -> Result<bool, EseError> {
+        let reader = self.get_reader()?;
+        {
+            let mut t = self.get_table_by_id(table_id)?;
+            let father_data_page_number = t
+                .cat
+                .table_catalog_definition
+                .as_ref()
+                .ok_or_else(|| {
+                    EseError::new(format!("no catalog definition for table {}", table_id))
+                })?
+                .father_data_page_number;
+            let page = reader.find_leaf_page_for_key(father_data_page_number, key)?;
+
+            // first record with a key not lower than `key`
+            let mut index = page.page_tags.len();
+            for (i, tag) in page.page_tags.iter().enumerate().skip(1) {
+                if !tag.flags().intersects(jet::PageTagFlags::FLAG_IS_DEFUNCT)
+                    && reader.load_page_tag_key(&page, tag)?.as_slice() >= key
+                {
+                    index = i;
+                    break;
+                }
+            }
+            // a new walk over the leaf pages starts here
+            t.update_validity_info_for_crow(ESE_MoveFirst);
+            t.set_current_page(page)?;
+            t.set_row(index - 1);
+            t.row_position = None;
         }
-    }
-}
-
-impl<R: ReadSeek> EseDb for EseParser<R> {
-    fn error_to_string(&self, err: i32) -> String {
-        format!("EseParser: error {}", err)
+        // lands on `index` or, past the end of the page, on the next leaf page
+        if !self.move_next_row(table_id, ESE_MoveNext)? {
+            self.move_previous_row(table_id, ESE_MoveLast)?;
+            return Ok(false);
+        }
+        let t = self.get_table_by_id(table_id)?;
+        let current_key =
+            reader.load_page_tag_key(t.page(), &t.page().page_tags[t.page_tag_index])?;
+        Ok(current_key == key)
     }
 
-    fn get_tables(&self) -> Result<Vec<String>, SimpleError> {
-        let mut tables: Vec<String> = vec![];
-        for i in &self.tables {
-            let n = i.borrow();
-            tables.push(
-                n.cat
-                    .table_catalog_definition
-                    .as_ref()
-                    .unwrap()
-                    .name
-                    .clone(),
-            );
+    /// The statistics timestamp of the table, not its creation time: ESE doesn't persist
+    /// a creation time for tables. The only timestamp a catalog entry can carry is in its
+    /// `Stats` record (catalog data type 129, available in all 0x620 format revisions):
+    /// `cItems`, `cKeys`, `cPages` and `dtWhenRun`, the OLE date when statistics were last
+    /// computed by JetComputeStats. That date is returned when present;
+    /// tables without statistics return `None`.
+    pub fn get_table_creation_time(&self, table: &str) -> Result<Option<jet::DateTime>, EseError> {
+        let mut index: usize = 0;
+        let t = self.get_table_by_name(table, &mut index)?;
+        let stats = &t
+            .cat
+            .table_catalog_definition
+            .as_ref()
+            .expect("table catalog definition")
+            .stats;
+        if stats.len() < 20 {
+            return Ok(None);
         }
-        Ok(tables)
+        let when_run = f64::from_bytes(&stats[12..20]);
+        Ok(jet_date_time_from_ole(when_run))
     }
 
-    fn open_table(&self, table: &str) -> Result<u64, SimpleError> {
-        let mut index: usize = 0;
-        {
-            // used to drop borrow mut
-            let mut t = self.get_table_by_name(table, &mut index)?;
-            if let Some(long_value_catalog_definition) = &t.cat.long_value_catalog_definition {
-                let reader = self.get_reader()?;
-                t.lv_tags = reader
-                    .load_lv_metadata(long_value_catalog_definition.father_data_page_number)?;
+    /// Walks the B-tree of the index (see `btree::walk_btree`).
+    /// Unique keys are counted among the first `unique_key_limit` entries (all if `None`).
+    pub fn get_index_statistics(
+        &self,
+        table: &str,
+        index: &str,
+        unique_key_limit: Option<u64>,
+    ) -> Result<IndexStatistics, EseError> {
+        let root_page = {
+            let mut table_index: usize = 0;
+            let t = self.get_table_by_name(table, &mut table_index)?;
+            let indexes = &t.cat.index_catalog_definition_array;
+            match crate::utils::find_object_name(indexes.iter().map(|i| i.name.as_str()), index) {
+                Some(i) => indexes[i].father_data_page_number,
+                None => {
+                    return Err(EseError::new(format!(
+                        "can't find index {} in table {}",
+                        index, table
+                    )))
+                }
             }
+        };
+        let mut visitor = IndexStatisticsVisitor {
+            reader: self.get_reader()?,
+            unique_key_limit: unique_key_limit.unwrap_or(u64::MAX),
+            stats: IndexStatistics::default(),
+            keys: HashSet::new(),
+            key_length_total: 0,
+            page_depths: HashMap::new(),
+            child_depth: 0,
+        };
+        btree::walk_btree(visitor.reader, root_page, &mut visitor)?;
+        let mut stats = visitor.stats;
+        if stats.entry_count > 0 {
+            stats.avg_key_length = visitor.key_length_total as f64 / stats.entry_count as f64;
         }
-        // ignore return result
-        self.move_row_helper(index as u64, ESE_MoveFirst)?;
-
-        Ok(index as u64)
+        stats.unique_key_count = visitor.keys.len() as u64;
+        Ok(stats)
     }
 
-    fn close_table(&self, table: u64) -> bool {
-        let tags_index = table as usize;
-        if tags_index < self.tables.len() {
-            let mut itrnl = self.tables[tags_index].borrow_mut();
-            itrnl.lv_tags.clear();
-            return true;
+    /// Iterates over the rows of the open table from the first one,
+    /// every row maps all the column identifiers of the table to their values.
+    pub fn rows(&self, table_id: u64) -> RowIter<'_, R> {
+        let columns = match self.get_table_by_id(table_id) {
+            Ok(t) => t
+                .cat
+                .column_catalog_definition_array
+                .iter()
+                .map(|col| col.identifier)
+                .collect(),
+            Err(_) => vec![],
+        };
+        RowIter {
+            parser: self,
+            table_id,
+            columns,
+            started: false,
+            done: false,
         }
-        false
     }
 
-    fn get_columns(&self, table: &str) -> Result<Vec<ColumnInfo>, SimpleError> {
+    /// Iterates over the rows of `table` with an independent position,
+    /// several iterators can run over the same table without moving its cursor.
+    pub fn iter_rows(&self, table: &str) -> Result<TableRowIter<'_, R>, EseError> {
+        let columns = self.get_columns(table)?;
         let mut index: usize = 0;
-        let t = self.get_table_by_name(table, &mut index)?;
-        let mut columns: Vec<ColumnInfo> = vec![];
-        for i in &t.cat.column_catalog_definition_array {
-            let col_info = ColumnInfo {
-                name: i.name.clone(),
-                id: i.identifier,
-                typ: i.column_type,
+        let cat = self.get_table_by_name(table, &mut index)?.cat.clone();
+        let lv_tags = match &cat.long_value_catalog_definition {
+            Some(lv) => self.reader.load_lv_metadata(lv.father_data_page_number)?,
+            None => LV_tags::new(),
+        };
+        let fdp = cat
+            .table_catalog_definition
+            .as_ref()
+            .ok_or_else(|| EseError::new(format!("{} has no table definition", table)))?
+            .father_data_page_number;
+        let first_page = self.reader.find_first_leaf_page(fdp)?;
+        Ok(TableRowIter {
+            reader: &self.reader,
+            cat,
+            lv_tags,
+            columns: Rc::new(columns),
+            page: Some(jet::DbPage::new(&self.reader, first_page)?),
+            page_tag_index: 0,
+            visited_pages: HashSet::from([first_page]),
+            include_deleted: self.include_deleted,
+        })
+    }
+
+    /// Seeks the index B-tree for the entries whose (normalized) key starts with `value`
+    /// and returns the primary keys of their records, in index order.
+    /// The primary index holds the records themselves, its keys are returned.
+    pub fn get_index_entries_for_value(
+        &self,
+        table: &str,
+        index: &str,
+        value: &[u8],
+    ) -> Result<Vec<Vec<u8>>, EseError> {
+        let (root_page, is_primary) = {
+            let mut table_index: usize = 0;
+            let t = self.get_table_by_name(table, &mut table_index)?;
+            match t
+                .cat
+                .index_catalog_definition_array
+                .iter()
+                .find(|i| i.name == index)
+            {
+                Some(i) => (
+                    i.father_data_page_number,
+                    i.index_flags().contains(jet::IndexFlags::Primary),
+                ),
+                None => {
+                    return Err(EseError::new(format!(
+                        "can't find index {} in table {}",
+                        index, table
+                    )))
+                }
+            }
+        };
+        let entries = self
+            .get_reader()?
+            .load_btree_entries_with_key_prefix(root_page, value)?;
+        Ok(entries
+            .into_iter()
+            .map(|(key, primary_key)| if is_primary { key } else { primary_key })
+            .collect())
+    }
+
+    /// Iterates over the leaf pages of the table (raw page tags, nothing is decoded).
+    pub fn iter_pages_with_data(&self, table_id: u64) -> PageChainIter<'_, R> {
+        let first_page = self.get_table_by_id(table_id).and_then(|t| {
+            let fdp = t
+                .cat
+                .table_catalog_definition
+                .as_ref()
+                .expect("table catalog definition")
+                .father_data_page_number;
+            self.reader.find_first_leaf_page(fdp)
+        });
+        match first_page {
+            Ok(page_number) => self.iter_pages_from(page_number),
+            Err(e) => PageChainIter {
+                reader: &self.reader,
+                next_page: 0,
+                visited_pages: HashSet::new(),
+                error: Some(e),
+            },
+        }
+    }
+
+    /// Same as `iter_pages_with_data`, but starts at any page, including pages
+    /// which aren't linked into a table anymore.
+    pub fn iter_pages_from(&self, page_number: u32) -> PageChainIter<'_, R> {
+        PageChainIter {
+            reader: &self.reader,
+            next_page: page_number,
+            visited_pages: HashSet::new(),
+            error: None,
+        }
+    }
+
+    // sizes of the live page tags of the table leaf pages (tag 0 holds no record)
+    fn get_table_tag_sizes(&self, table_id: u64) -> Result<Vec<u16>, EseError> {
+        let mut sizes = vec![];
+        for page in self.iter_pages_with_data(table_id) {
+            let (_, tags) = page?;
+            sizes.extend(
+                tags.iter()
+                    .skip(1)
+                    .filter(|t| !t.flags().intersects(jet::PageTagFlags::FLAG_IS_DEFUNCT))
+                    .map(|t| t.size),
+            );
+        }
+        Ok(sizes)
+    }
+
+    /// Average size of the records as stored in the leaf pages, including their keys.
+    /// 0 for an empty table.
+    pub fn get_table_avg_tag_size(&self, table_id: u64) -> Result<f64, EseError> {
+        let sizes = self.get_table_tag_sizes(table_id)?;
+        if sizes.is_empty() {
+            return Ok(0.0);
+        }
+        let total: u64 = sizes.iter().map(|&s| s as u64).sum();
+        Ok(total as f64 / sizes.len() as f64)
+    }
+
+    /// Counts the records of the table by stored size.
+    /// `buckets` are ascending upper bounds (inclusive), the last count is for larger records.
+    pub fn get_tag_size_histogram(
+        &self,
+        table_id: u64,
+        buckets: &[u32],
+    ) -> Result<Vec<u64>, EseError> {
+        if buckets.windows(2).any(|w| w[0] >= w[1]) {
+            return Err(EseError::new(format!(
+                "histogram buckets must be ascending: {:?}",
+                buckets
+            )));
+        }
+        let mut histogram = vec![0; buckets.len() + 1];
+        for size in self.get_table_tag_sizes(table_id)? {
+            let i = buckets.partition_point(|&b| b < size as u32);
+            histogram[i] += 1;
+        }
+        Ok(histogram)
+    }
+
+    /// Reads a raw structure at `offset` from the beginning of the page `page_number`.
+    ///
+    /// # Safety
+    ///
+    /// `T` must be a plain `Copy` structure without padding bytes (e.g. `#[repr(packed)]`),
+    /// any bit pattern read from the file has to be a valid `T`.
+    pub unsafe fn read_page_as_struct<T: Copy>(
+        &self,
+        page_number: u32,
+        offset: usize,
+    ) -> Result<T, EseError> {
+        let reader = self.get_reader()?;
+        let page_size = reader.page_size() as u64;
+        let last_page = (reader.file_size()? / page_size).saturating_sub(2);
+        if page_number == 0 || page_number as u64 > last_page {
+            return Err(EseError::BadPageNumber {
+                page: page_number,
+                max: last_page as u32,
+            });
+        }
+        let end = offset.checked_add(mem::size_of::<T>());
+        if !matches!(end, Some(end) if end as u64 <= page_size) {
+            return Err(EseError::new(format!(
+                "can't read {} bytes at offset {}, page size is {}",
+                mem::size_of::<T>(),
+                offset,
+                page_size
+            )));
+        }
+        let file_offset = (page_number as u64 + 1) * page_size + offset as u64;
+        reader.read_struct::<T>(file_offset)
+    }
+
+    /// CRC32 of the current record data, suitable to detect changed records between snapshots.
+    pub fn get_record_checksum(&self, table_id: u64) -> Result<u32, EseError> {
+        let table = self.get_table_by_id(table_id)?;
+        if table.current_page.is_none() {
+            return Err(EseError::new(
+                "no current page, use open_table API before this",
+            ));
+        }
+        let page = table.page();
+        let page_tag = page
+            .page_tags
+            .get(table.page_tag_index)
+            .ok_or_else(|| EseError::new(format!("no current record in table {}", table_id)))?;
+        let data = self.get_reader()?.load_page_tag_data(page, page_tag)?;
+        Ok(crc32fast::hash(&data))
+    }
+
+    /// XOR of the CRC32s of the keys (upper 32 bits) and values (lower 32 bits) of all the records.
+    /// It doesn't depend on the order or placement of the records in the pages,
+    /// but it isn't collision resistant: use `compute_table_sha256` for integrity proofs.
+    pub fn compute_table_checksum(&self, table_id: u64) -> Result<u64, EseError> {
+        let mut checksum = 0u64;
+        self.for_each_record(table_id, |key, value| {
+            checksum ^= ((crc32fast::hash(key) as u64) << 32) | crc32fast::hash(value) as u64;
+        })?;
+        Ok(checksum)
+    }
+
+    /// SHA-256 of all the records (length prefixed keys and values) in key order.
+    pub fn compute_table_sha256(&self, table_id: u64) -> Result<[u8; 32], EseError> {
+        let mut sha = Sha256::new();
+        self.for_each_record(table_id, |key, value| {
+            for v in [key, value] {
+                sha.update((v.len() as u32).to_le_bytes());
+                sha.update(v);
+            }
+        })?;
+        Ok(sha.finalize().into())
+    }
+
+    // scans the table and calls `f(key, value)` with the data of every record
+    fn for_each_record(
+        &self,
+        table_id: u64,
+        mut f: impl FnMut(&[u8], &[u8]),
+    ) -> Result<(), EseError> {
+        let reader = self.get_reader()?;
+        let mut has_row = self.move_row(table_id, ESE_MoveFirst)?;
+        while has_row {
+            let (key, value) = {
+                let t = self.get_table_by_id(table_id)?;
+                let page = t.page();
+                let page_tag = &page.page_tags[t.page_tag_index];
+                (
+                    reader.load_page_tag_key(page, page_tag)?,
+                    reader.load_page_tag_value(page, page_tag)?,
+                )
+            };
+            f(&key, &value);
+            has_row = self.move_row(table_id, ESE_MoveNext)?;
+        }
+        Ok(())
+    }
+
+    /// File header of the database, as read when it was loaded.
+    pub fn get_file_header(&self) -> &ese_db::FileHeader {
+        self.reader.file_header()
+    }
+
+    /// Format, state, times, signature, error counters and size of the database from its file header.
+    /// A database which is not in `jet::DbState::CleanShutdown` state may miss
+    /// the changes which are still in its log files.
+    pub fn db_info(&self) -> Result<DbInfo, EseError> {
+        let reader = self.get_reader()?;
+        let header = reader.file_header();
+        let signature = &header.database_signature;
+        let computer_name = signature
+            .computer_name
+            .split(|&c| c == 0)
+            .next()
+            .unwrap_or_default();
+        Ok(DbInfo {
+            format_version: header.format_version,
+            format_revision: header.format_revision,
+            page_size: header.page_size,
+            database_state: header.database_state,
+            attach_time: header.attach_time.to_utc(),
+            detach_time: header.detach_time.to_utc(),
+            signature: DbSignature {
+                random: signature.random,
+                computer_name: String::from_utf8_lossy(computer_name).into_owned(),
+                creation_time: signature.logtime_create.to_utc(),
+            },
+            last_object_identifier: header.last_object_identifier,
+            ecc_fix_success_count: header.ecc_fix_success_count,
+            ecc_fix_error_count: header.ecc_fix_error_count,
+            bad_checksum_error_count: header.bad_checksum_error_count,
+            page_count: (reader.file_size()? / header.page_size as u64).saturating_sub(2),
+        })
+    }
+
+    /// Minimum (format version, format revision) of the ESE able to open the database
+    /// without upgrade.
+    pub fn get_database_compat_version(&self) -> Result<(u32, u32), EseError> {
+        let header = self.get_reader()?.file_header();
+        Ok((
+            header.creation_format_version,
+            header.creation_format_revision,
+        ))
+    }
+
+    pub fn can_open_with_version(&self, version: u32, revision: u32) -> bool {
+        self.get_database_compat_version()
+            .map(|min_version| (version, revision) >= min_version)
+            .unwrap_or(false)
+    }
+
+    /// Returns column name -> default value for the columns of `table` which have a default value.
+    pub fn extract_default_values(
+        &self,
+        table: &str,
+    ) -> Result<HashMap<String, Vec<u8>>, EseError> {
+        let mut index: usize = 0;
+        let t = self.get_table_by_name(table, &mut index)?;
+        Ok(t.cat
+            .column_catalog_definition_array
+            .iter()
+            .filter(|col| !col.default_value.is_empty())
+            .map(|col| (col.name.clone(), col.default_value.clone()))
+            .collect())
+    }
+
+    /// Identifier of the column `column_name` of the table. Names are case-insensitive,
+    /// an exact match is preferred.
+    pub fn get_column_id(&self, table: &str, column_name: &str) -> Result<u32, EseError> {
+        let mut index: usize = 0;
+        let t = self.get_table_by_name(table, &mut index)?;
+        let columns = &t.cat.column_catalog_definition_array;
+        crate::utils::find_object_name(columns.iter().map(|c| c.name.as_str()), column_name)
+            .map(|i| columns[i].identifier)
+            .ok_or_else(|| EseError::ColumnNameNotFound(column_name.to_string()))
+    }
+
+    /// Default value of a column, `None` if the column has no default value.
+    pub fn get_column_default(
+        &self,
+        table: &str,
+        column: &str,
+    ) -> Result<Option<Vec<u8>>, EseError> {
+        let mut index: usize = 0;
+        let t = self.get_table_by_name(table, &mut index)?;
+        match t
+            .cat
+            .column_catalog_definition_array
+            .iter()
+            .find(|col| col.name == column)
+        {
+            Some(col) if col.default_value.is_empty() => Ok(None),
+            Some(col) => Ok(Some(col.default_value.clone())),
+            None => Err(EseError::ColumnNameNotFound(column.to_string())),
+        }
+    }
+
+    /// Raw `lcmap_flags` of a column from the catalog, see `utils::lcmap_flags_to_description`.
+    /// Text comparison flags are usually kept by the indexes, the columns have 0 then.
+    pub fn get_column_lcmap_flags(&self, table: &str, column_id: u32) -> Result<u32, EseError> {
+        let mut index: usize = 0;
+        let t = self.get_table_by_name(table, &mut index)?;
+        match t
+            .cat
+            .column_catalog_definition_array
+            .iter()
+            .find(|col| col.identifier == column_id)
+        {
+            Some(col) => Ok(col.lcmap_flags),
+            None => Err(EseError::ColumnNotFound(column_id)),
+        }
+    }
+
+    /// Sorted keys of the long values loaded by `open_table`.
+    pub fn list_all_lv_keys(&self, table_id: u64) -> Result<Vec<u64>, EseError> {
+        let table = self.get_table_by_id(table_id)?;
+        let mut keys: Vec<u64> = table.lv_tags.keys().copied().collect();
+        keys.sort_unstable();
+        Ok(keys)
+    }
+
+    pub fn has_lv_key(&self, table_id: u64, key: u64) -> bool {
+        self.get_table_by_id(table_id)
+            .map(|t| t.lv_tags.contains_key(&key))
+            .unwrap_or(false)
+    }
+
+    /// Tables whose catalog parent object is `table`.
+    pub fn get_table_linked_tables(&self, table: &str) -> Result<Vec<String>, EseError> {
+        let mut index: usize = 0;
+        let identifier = self
+            .get_table_by_name(table, &mut index)?
+            .cat
+            .table_catalog_definition
+            .as_ref()
+            .map(|cat| cat.identifier)
+            .ok_or_else(|| EseError::new(format!("no catalog definition for {}", table)))?;
+        let mut linked = vec![];
+        for (i, t) in self.tables.iter().enumerate() {
+            if i == index {
+                continue;
+            }
+            if let Some(cat) = &t.borrow().cat.table_catalog_definition {
+                if cat.father_data_page_object_identifier == identifier {
+                    linked.push(cat.name.clone());
+                }
+            }
+        }
+        Ok(linked)
+    }
+
+    /// Writes the table as CSV with the default `csv::CsvOptions`: comma separated fields,
+    /// binary columns in hex. Returns the number of rows.
+    pub fn export_table_csv<W: Write>(&self, table: &str, writer: &mut W) -> Result<u64, EseError> {
+        crate::csv::export_table(self, table, writer, &crate::csv::CsvOptions::default())
+    }
+
+    /// Writes the table as a JSON array of row objects (see `json::export_table`),
+    /// returns the number of rows.
+    #[cfg(feature = "json")]
+    pub fn export_table_json<W: Write>(
+        &self,
+        table: &str,
+        writer: &mut W,
+    ) -> Result<u64, EseError> {
+        crate::json::export_table(self, table, writer)
+    }
+
+    /// Value of `column` in the current row, converted by its catalog type and code page.
+    /// A tagged column with several values is returned as `Value::MultiValue`.
+    pub fn get_column_value(&self, table: u64, column: u32) -> Result<Value, EseError> {
+        let (column_type, cp) = self
+            .get_table_by_id(table)?
+            .cat
+            .column_catalog_definition_array
+            .iter()
+            .find(|col| col.identifier == column)
+            .map(|col| (col.column_type, col.codepage as u16))
+            .ok_or(EseError::ColumnNotFound(column))?;
+        if ColumnStorageClass::from_column_id(column) == ColumnStorageClass::Tagged {
+            let count = self.get_column_mv_count(table, column)?;
+            if count > 1 {
+                let mut values = Vec::with_capacity(count as usize);
+                for i in 1..=count {
+                    values.push(match self.get_column_mv(table, column, i)? {
+                        Some(v) => Value::from_column_bytes(column_type, cp, &v)?,
+                        None => Value::Null,
+                    });
+                }
+                return Ok(Value::MultiValue(values));
+            }
+        }
+        match self.get_column(table, column)? {
+            Some(v) => Value::from_column_bytes(column_type, cp, &v),
+            None => Ok(Value::Null),
+        }
+    }
+
+    /// Exports the record stored at (`page_number`, `tag_index`) of the table leaf page as
+    /// a JSON object, with the location in the `_page` and `_tag` fields.
+    /// Deleted (defunct) records are exported too, their unreadable columns are set to null.
+    #[cfg(feature = "json")]
+    pub fn export_record_to_json(
+        &self,
+        table_id: u64,
+        page_number: u32,
+        tag_index: usize,
+    ) -> Result<String, EseError> {
+        let mut row = self.with_record_at(table_id, page_number, tag_index, |table, defunct| {
+            let columns = self.get_columns(table)?;
+            crate::json::row_to_json(
+                self,
+                table_id,
+                &columns,
+                defunct,
+                crate::csv::BinaryEncoding::Hex,
+            )
+        })?;
+        row.insert("_page".to_string(), page_number.into());
+        row.insert("_tag".to_string(), tag_index.into());
+        Ok(serde_json::Value::Object(row).to_string())
+    }
+
+    /// Reads all the columns of the record stored at (`page_number`, `tag_index`)
+    /// of the table leaf page. The cursor of the table isn't moved.
+    pub fn get_record_at_page(
+        &self,
+        table_id: u64,
+        page_number: u32,
+        tag_index: usize,
+    ) -> Result<RowValues, EseError> {
+        self.with_record_at(table_id, page_number, tag_index, |table, _| {
+            let columns = self.get_columns(table)?;
+            read_row(self, table_id, &columns)
+        })
+    }
+
+    // Temporarily moves the cursor of the table to the record at (page_number, tag_index)
+    // and calls `read(table name, is defunct)`, the table position is restored after.
+    fn with_record_at<T>(
+        &self,
+        table_id: u64,
+        page_number: u32,
+        tag_index: usize,
+        read: impl FnOnce(&str, bool) -> Result<T, EseError>,
+    ) -> Result<T, EseError> {
+        let page = jet::DbPage::new(self.get_reader()?, page_number)?;
+        let (table, identifier) = {
+            let t = self.get_table_by_id(table_id)?;
+            let cat = t.cat.table_catalog_definition.as_ref().ok_or_else(|| {
+                EseError::new(format!("no catalog definition for table {}", table_id))
+            })?;
+            (cat.name.clone(), cat.identifier)
+        };
+        if !page.flags().contains(jet::PageFlags::IS_LEAF)
+            || page
+                .flags()
+                .intersects(jet::PageFlags::IS_LONG_VALUE | jet::PageFlags::IS_SPACE_TREE)
+            || page.common().father_data_page_object_identifier != identifier
+        {
+            return Err(EseError::new(format!(
+                "page {} is not a leaf page of table {}",
+                page_number, table
+            )));
+        }
+        if tag_index == 0 || tag_index >= page.page_tags.len() {
+            return Err(EseError::new(format!(
+                "wrong page tag index {}, page {} has {} tags",
+                tag_index,
+                page_number,
+                page.page_tags.len()
+            )));
+        }
+        let defunct = page.page_tags[tag_index]
+            .flags()
+            .intersects(jet::PageTagFlags::FLAG_IS_DEFUNCT);
+
+        let saved = {
+            let mut t = self.get_table_by_id(table_id)?;
+            let saved_lls = t.lls.replace(LastLoadState::init(page_number, tag_index));
+            let saved_page = mem::take(&mut t.current_page);
+            let saved_tag_index = mem::replace(&mut t.page_tag_index, tag_index);
+            t.current_page.set(page);
+            (saved_page, saved_tag_index, saved_lls)
+        };
+        let result = read(&table, defunct);
+        {
+            let mut t = self.get_table_by_id(table_id)?;
+            let (saved_page, saved_tag_index, saved_lls) = saved;
+            t.current_page = saved_page;
+            t.page_tag_index = saved_tag_index;
+            t.lls.replace(saved_lls);
+        }
+        result
+    }
+
+    /// Path of the opened database file, `None` if the parser wasn't loaded from a path.
+    pub fn get_database_path(&self) -> Option<&Path> {
+        self.reader.path()
+    }
+
+    /// Verifies the checksums of the given pages, zeroed (never used) pages are reported as Ok.
+    pub fn verify_page_checksums(
+        &self,
+        page_numbers: &[u32],
+    ) -> Result<Vec<checksum::ChecksumResult>, EseError> {
+        let reader = self.get_reader()?;
+        Ok(page_numbers
+            .iter()
+            .map(|&page_number| reader.verify_page_checksum(page_number))
+            .collect())
+    }
+
+    /// Verifies the checksums of all the pages of the database and counts the free and
+    /// long-value pages. Corrupt and unreadable pages don't stop the scan, they are all reported.
+    /// Each page is read once, without going through the cache.
+    pub fn integrity_check(&self) -> Result<IntegrityReport, EseError> {
+        let reader = self.get_reader()?;
+        let mut report = IntegrityReport {
+            total_pages: (reader.file_size()? / reader.page_size() as u64).saturating_sub(2) as u32,
+            ..Default::default()
+        };
+        for page_number in 1..=report.total_pages {
+            let page = match reader.read_page_uncached(page_number) {
+                Ok(page) => page,
+                Err(error) => {
+                    report.unreadable_pages.push(UnreadablePage {
+                        page_number,
+                        error: error.to_string(),
+                    });
+                    continue;
+                }
+            };
+            if page.iter().all(|&b| b == 0) {
+                report.free_pages += 1;
+                continue;
+            }
+            if let checksum::ChecksumResult::Mismatch {
+                block,
+                stored,
+                computed,
+                ..
+            } = reader.page_checksum_result(&page, page_number)
+            {
+                report.corrupt_pages.push(CorruptPage {
+                    page_number,
+                    block,
+                    stored,
+                    computed,
+                });
+                continue;
+            }
+            // the scan goes on for pages which can't be parsed
+            let flags = match reader.page_flags_of(&page) {
+                Ok(flags) => flags,
+                Err(_) => continue,
+            };
+            if flags.contains(jet::PageFlags::IS_EMPTY) {
+                report.free_pages += 1;
+            } else if flags.contains(jet::PageFlags::IS_LONG_VALUE) {
+                report.lv_pages += 1;
+            }
+        }
+        Ok(report)
+    }
+
+    /// Data type flags of a tagged column in the current row,
+    /// `None` for fixed and variable size columns and for the columns absent from the row.
+    pub fn get_tagged_data_type_flags(
+        &self,
+        table_id: u64,
+        column_id: u32,
+    ) -> Result<Option<jet::TaggedDataTypeFlag>, EseError> {
+        if ColumnStorageClass::from_column_id(column_id) != ColumnStorageClass::Tagged {
+            return Ok(None);
+        }
+        self.get_column_dyn_helper(table_id, column_id, 1, TaggedLoadMode::Value)?;
+        let table = self.get_table_by_id(table_id)?;
+        let flags = table.lls.borrow().tagged_column.data_type_flags;
+        Ok(flags)
+    }
+
+    /// Key of the long value held by a tagged column of the current row, the long value itself
+    /// isn't read. `None` if the column is null or stored in the record.
+    /// Multi-valued columns aren't handled, `None` is returned for them too.
+    pub fn get_lv_key_for_column(
+        &self,
+        table_id: u64,
+        column_id: u32,
+    ) -> Result<Option<u64>, EseError> {
+        if ColumnStorageClass::from_column_id(column_id) != ColumnStorageClass::Tagged {
+            return Ok(None);
+        }
+        self.get_column_dyn_helper(table_id, column_id, 1, TaggedLoadMode::LvKey)?;
+        let table = self.get_table_by_id(table_id)?;
+        let key = table.lls.borrow().tagged_column.lv_key;
+        Ok(key)
+    }
+
+    /// Streaming reader over the value of a column of the current row.
+    /// Long values are read segment by segment instead of being assembled in memory,
+    /// values stored in the record are returned as a whole. `None` if the column is null.
+    pub fn get_column_reader(
+        &self,
+        table_id: u64,
+        column_id: u32,
+    ) -> Result<Option<LvReader<'_, R>>, EseError> {
+        let reader = self.get_reader()?;
+        match self.get_lv_key_for_column(table_id, column_id)? {
+            Some(key) => {
+                let table = self.get_table_by_id(table_id)?;
+                let compressed = table
+                    .cat
+                    .column_catalog_definition_array
+                    .iter()
+                    .find(|c| c.identifier == column_id)
+                    .is_some_and(|c| c.column_flags().contains(jet::ColumnFlags::Compressed));
+                Ok(Some(reader.lv_reader(&table.lv_tags, key, compressed)?))
+            }
+            None => Ok(self
+                .get_column(table_id, column_id)?
+                .map(|v| LvReader::from_value(reader, v))),
+        }
+    }
+
+    /// Raw bytes of the page tag, as stored in the page.
+    pub fn get_page_tag_data(
+        &self,
+        page_number: u32,
+        tag_index: usize,
+    ) -> Result<Vec<u8>, EseError> {
+        let reader = self.get_reader()?;
+        let page = jet::DbPage::new(reader, page_number)?;
+        let tag = page.page_tags.get(tag_index).ok_or_else(|| {
+            EseError::new(format!(
+                "wrong page tag index {}, page {} has {} tags",
+                tag_index,
+                page_number,
+                page.page_tags.len()
+            ))
+        })?;
+        reader.read_bytes(tag.offset(&page), tag.size as usize)
+    }
+
+    /// Writes the tags of any page as CSV (with a header row):
+    /// index, flags, offset, size, defunct flag and hex of the first 32 bytes of the tag data.
+    pub fn export_page_tags_csv<W: Write>(
+        &self,
+        page_number: u32,
+        mut writer: W,
+    ) -> Result<(), EseError> {
+        const PREVIEW_SIZE: usize = 32;
+        let reader = self.get_reader()?;
+        let page = jet::DbPage::new(reader, page_number)?;
+        writeln!(
+            writer,
+            "tag_index,flags,offset,size,is_defunct,data_hex_preview"
+        )?;
+        for (tag_index, tag) in page.page_tags.iter().enumerate() {
+            // the raw tag data, like get_page_tag_data, read from the page loaded once
+            let data = reader.read_bytes(tag.offset(&page), tag.size as usize)?;
+            let preview: String = data
+                .iter()
+                .take(PREVIEW_SIZE)
+                .map(|b| format!("{:02x}", b))
+                .collect();
+            writeln!(
+                writer,
+                "{},0x{:x},{},{},{},{}",
+                tag_index,
+                tag.flags,
+                tag.offset,
+                tag.size,
+                tag.flags().intersects(jet::PageTagFlags::FLAG_IS_DEFUNCT),
+                preview
+            )?;
+        }
+        Ok(())
+    }
+
+    /// (page number, page tag index) of the current record of the table.
+    pub fn get_record_page_location(&self, table_id: u64) -> Result<(u32, usize), EseError> {
+        let t = self.get_table_by_id(table_id)?;
+        // page tag 0 of a leaf page holds no record, it is set for an empty table
+        if t.current_page.is_none() || t.page_tag_index == 0 {
+            return Err(EseError::NoCursorPosition(table_id));
+        }
+        Ok((t.page().page_number, t.page_tag_index))
+    }
+
+    /// The current record of the table is deleted (its page tag is defunct).
+    /// Deleted records are visited only with `ParserOptions::include_deleted`.
+    pub fn is_current_row_deleted(&self, table_id: u64) -> Result<bool, EseError> {
+        let t = self.get_table_by_id(table_id)?;
+        if t.current_page.is_none() || t.page_tag_index == 0 {
+            return Err(EseError::NoCursorPosition(table_id));
+        }
+        Ok(t.page().page_tags[t.page_tag_index]
+            .flags()
+            .intersects(jet::PageTagFlags::FLAG_IS_DEFUNCT))
+    }
+
+    /// Scans the table and maps every value of the column (all values for multi-valued columns)
+    /// to the (page number, page tag index) locations of the rows holding it.
+    /// Fails if the index would take more than `max_memory` bytes.
+    pub fn build_in_memory_index(
+        &self,
+        table_id: u64,
+        column_id: u32,
+        max_memory: Option<usize>,
+    ) -> Result<InMemoryIndex, EseError> {
+        let mut index = InMemoryIndex::new();
+        let mut memory = 0;
+        let mut has_row = self.move_row(table_id, ESE_MoveFirst)?;
+        while has_row {
+            let location = self.get_record_page_location(table_id)?;
+            let mut multi_value_index = 1;
+            while let Some(v) = self.get_column_mv(table_id, column_id, multi_value_index)? {
+                memory += mem::size_of::<(u32, usize)>();
+                if !index.contains_key(&v) {
+                    memory += v.len();
+                }
+                if let Some(max) = max_memory {
+                    if memory > max {
+                        return Err(EseError::new(format!(
+                            "index of column {} exceeds {} bytes",
+                            column_id, max
+                        )));
+                    }
+                }
+                index.entry(v).or_default().push(location);
+                multi_value_index += 1;
+            }
+            has_row = self.move_row(table_id, ESE_MoveNext)?;
+        }
+        Ok(index)
+    }
+
+    /// Fraction of the rows of the table where the column isn't null. 0 for an empty table.
+    pub fn get_column_page_coverage(&self, table_id: u64, column_id: u32) -> Result<f64, EseError> {
+        let (rows, present) = self
+            .get_column_page_coverage_counts(table_id, column_id)?
+            .iter()
+            .fold((0, 0), |(rows, present), (_, r, p)| (rows + r, present + p));
+        if rows == 0 {
+            return Ok(0.0);
+        }
+        Ok(present as f64 / rows as f64)
+    }
+
+    /// Same as `get_column_page_coverage` for every leaf page of the table,
+    /// as (page number, rate) in the order of the pages in the leaf chain.
+    pub fn get_column_page_coverage_histogram(
+        &self,
+        table_id: u64,
+        column_id: u32,
+    ) -> Result<Vec<(u32, f64)>, EseError> {
+        Ok(self
+            .get_column_page_coverage_counts(table_id, column_id)?
+            .into_iter()
+            .map(|(page, rows, present)| (page, present as f64 / rows as f64))
+            .collect())
+    }
+
+    // (page number, rows, rows with the column) of the pages holding records
+    fn get_column_page_coverage_counts(
+        &self,
+        table_id: u64,
+        column_id: u32,
+    ) -> Result<Vec<(u32, u64, u64)>, EseError> {
+        let mut pages: Vec<(u32, u64, u64)> = vec![];
+        let mut has_row = self.move_row(table_id, ESE_MoveFirst)?;
+        while has_row {
+            let (page_number, _) = self.get_record_page_location(table_id)?;
+            let present = self.get_column(table_id, column_id)?.is_some() as u64;
+            match pages.last_mut() {
+                Some((last, rows, count)) if *last == page_number => {
+                    *rows += 1;
+                    *count += present;
+                }
+                _ => pages.push((page_number, 1, present)),
+            }
+            has_row = self.move_row(table_id, ESE_MoveNext)?;
+        }
+        Ok(pages)
+    }
+
+    /// Walks the space trees of the database and of every table, index and long-value tree.
+    /// Returns the page extents ordered by page number, every page is reported
+    /// for its innermost owner only.
+    pub fn get_page_allocation_map(&self) -> Result<Vec<PageAllocation>, EseError> {
+        let reader = self.get_reader()?;
+        // father data page number -> object identifier
+        let mut objects = BTreeMap::new();
+        objects.insert(
+            jet::FixedPageNumber::Database as u32,
+            jet::FixedFDPNumber::Database as u32,
+        );
+        for t in &self.tables {
+            let t = t.borrow();
+            for cat_def in t
+                .cat
+                .table_catalog_definition
+                .iter()
+                .chain(t.cat.index_catalog_definition_array.iter())
+                .chain(t.cat.long_value_catalog_definition.iter())
+            {
+                objects
+                    .entry(cat_def.father_data_page_number)
+                    .or_insert(cat_def.identifier);
+            }
+        }
+
+        let mut headers = BTreeMap::new();
+        for &fdp in objects.keys() {
+            let db_page = jet::DbPage::new(reader, fdp)?;
+            let header = reader.load_root_page_header(&db_page, &db_page.page_tags[0])?;
+            headers.insert(fdp, header);
+        }
+        // space of an object is taken from its parent, so parents go first
+        let mut fdps: Vec<(usize, u32)> = headers
+            .keys()
+            .map(|&fdp| {
+                let mut depth = 0;
+                let mut parent = headers[&fdp].parent_fdp();
+                while depth < headers.len() && headers.contains_key(&parent) {
+                    depth += 1;
+                    parent = headers[&parent].parent_fdp();
+                }
+                (depth, fdp)
+            })
+            .collect();
+        fdps.sort_unstable();
+
+        // page number -> (owner, is free)
+        let mut pages: Vec<Option<(u32, bool)>> = vec![];
+        for (_, fdp) in fdps {
+            let object_id = objects[&fdp];
+            let header = &headers[&fdp];
+            let space_tree = header.space_tree_page_number();
+            let (owned, available) = if space_tree == 0 {
+                // single extent, no space tree
+                (vec![(fdp, header.initial_number_of_pages())], vec![])
+            } else {
+                // the available extents tree follows the owned extents tree
+                (
+                    reader.load_space_tree_extents(space_tree)?,
+                    reader.load_space_tree_extents(space_tree + 1)?,
+                )
+            };
+            for (first_page, number_of_pages) in owned {
+                let end = first_page as usize + number_of_pages as usize;
+                if pages.len() < end {
+                    pages.resize(end, None);
+                }
+                for p in &mut pages[first_page as usize..end] {
+                    *p = Some((object_id, false));
+                }
+            }
+            for (first_page, number_of_pages) in available {
+                let end = (first_page as usize + number_of_pages as usize).min(pages.len());
+                for p in pages.iter_mut().take(end).skip(first_page as usize) {
+                    if *p == Some((object_id, false)) {
+                        *p = Some((object_id, true));
+                    }
+                }
+            }
+        }
+
+        let mut allocs: Vec<PageAllocation> = vec![];
+        for (page_number, page) in pages.iter().enumerate() {
+            let (owner, is_free) = match *page {
+                Some(page) => page,
+                None => continue,
+            };
+            let owner_object_id = if is_free && owner == jet::FixedFDPNumber::Database as u32 {
+                None
+            } else {
+                Some(owner)
+            };
+            if let Some(last) = allocs.last_mut() {
+                if last.start_page + last.extent_size == page_number as u32
+                    && last.is_free == is_free
+                    && last.owner_object_id == owner_object_id
+                {
+                    last.extent_size += 1;
+                    continue;
+                }
+            }
+            allocs.push(PageAllocation {
+                start_page: page_number as u32,
+                extent_size: 1,
+                is_free,
+                owner_object_id,
+            });
+        }
+        Ok(allocs)
+    }
+
+    /// Counts the entries of the catalog loaded with the database, the tables don't need to be open.
+    pub fn get_catalog_entry_count(&self) -> CatalogEntryCounts {
+        let mut counts = CatalogEntryCounts::default();
+        for t in &self.tables {
+            counts.add(&t.borrow().cat);
+        }
+        counts
+    }
+
+    /// Same as `get_catalog_entry_count`, for the entries of one table.
+    pub fn get_catalog_entry_counts_for_table(
+        &self,
+        table: &str,
+    ) -> Result<CatalogEntryCounts, EseError> {
+        let mut table_index: usize = 0;
+        let t = self.get_table_by_name(table, &mut table_index)?;
+        let mut counts = CatalogEntryCounts::default();
+        counts.add(&t.cat);
+        Ok(counts)
+    }
+
+    /// Indexes of the table, in catalog order.
+    pub fn get_indexes(&self, table: &str) -> Result<Vec<IndexDefinition>, EseError> {
+        let mut table_index: usize = 0;
+        let t = self.get_table_by_name(table, &mut table_index)?;
+        Ok(t.cat
+            .index_catalog_definition_array
+            .iter()
+            .map(|i| IndexDefinition {
+                name: i.name.clone(),
+                identifier: i.identifier,
+                father_data_page_object_identifier: i.father_data_page_object_identifier,
+                father_data_page_number: i.father_data_page_number,
+                flags: i.index_flags(),
+                lcmap_flags: i.lcmap_flags,
+                key_column_ids: i.key_segments.iter().map(|s| s.column_identifier).collect(),
+                conditional_column_ids: i.conditional_columns.clone(),
+            })
+            .collect())
+    }
+
+    /// Columns of the table with their storage kind, flags and default value presence.
+    pub fn get_columns_meta(&self, table: &str) -> Result<Vec<ColumnMeta>, EseError> {
+        let mut table_index: usize = 0;
+        let t = self.get_table_by_name(table, &mut table_index)?;
+        Ok(t.cat
+            .column_catalog_definition_array
+            .iter()
+            .map(|c| ColumnMeta {
+                name: c.name.clone(),
+                id: c.identifier,
+                typ: c.column_type,
+                cbmax: c.size,
+                cp: c.codepage as u16,
+                kind: ColumnStorageClass::from_column_id(c.identifier),
+                flags: c.column_flags(),
+                has_default_value: !c.default_value.is_empty(),
+            })
+            .collect())
+    }
+
+    /// FDP and initial page count of the table, as `esentutl /mh`-style reports show them.
+    pub fn get_table_meta(&self, table: &str) -> Result<TableMeta, EseError> {
+        let mut table_index: usize = 0;
+        let t = self.get_table_by_name(table, &mut table_index)?;
+        let cat_def = t
+            .cat
+            .table_catalog_definition
+            .as_ref()
+            .ok_or_else(|| EseError::TableNotFound(table.to_string()))?;
+        let db_page = jet::DbPage::new(&self.reader, cat_def.father_data_page_number)?;
+        let header = self
+            .reader
+            .load_root_page_header(&db_page, &db_page.page_tags[0])?;
+        Ok(TableMeta {
+            name: cat_def.name.clone(),
+            object_identifier: cat_def.identifier,
+            father_data_page_number: cat_def.father_data_page_number,
+            space_usage: cat_def.size,
+            flags: cat_def.flags,
+            initial_number_of_pages: header.initial_number_of_pages(),
+            parent_fdp: header.parent_fdp(),
+        })
+    }
+
+    /// Estimated number of rows of the table, reading one page per level of its B-tree
+    /// and a few leaf pages. Exact when the root page is a leaf, otherwise the number of
+    /// leaf pages (the product of the child counts of the leftmost branch pages) times
+    /// the average record count of the first `ESTIMATE_SAMPLE_PAGES` leaf pages.
+    pub fn estimate_row_count(&self, table: &str) -> Result<u64, EseError> {
+        let mut table_index: usize = 0;
+        let fdp = self
+            .get_table_by_name(table, &mut table_index)?
+            .cat
+            .table_catalog_definition
+            .as_ref()
+            .ok_or_else(|| EseError::TableNotFound(table.to_string()))?
+            .father_data_page_number;
+        fn live_tags(page: &jet::DbPage) -> Vec<&ese_db::PageTag> {
+            page.page_tags
+                .iter()
+                .skip(1)
+                .filter(|tag| !tag.flags().intersects(jet::PageTagFlags::FLAG_IS_DEFUNCT))
+                .collect()
+        }
+
+        let mut page = jet::DbPage::new(&self.reader, fdp)?;
+        let mut leaf_pages: u64 = 1;
+        let mut depth = 0;
+        while !page.flags().contains(jet::PageFlags::IS_LEAF) {
+            let children = live_tags(&page);
+            let first_child = match children.first() {
+                Some(tag) => self
+                    .reader
+                    .page_tag_get_branch_child_page_number(&page, tag)?,
+                None => return Ok(0),
+            };
+            leaf_pages = leaf_pages.saturating_mul(children.len() as u64);
+            page = jet::DbPage::new(&self.reader, first_child)?;
+            depth += 1;
+            if depth == btree::MAX_BTREE_DEPTH {
+                return Err(EseError::new(format!(
+                    "no leaf page within {} levels of the tree of page {}",
+                    depth, fdp
+                )));
+            }
+        }
+        if leaf_pages == 1 {
+            return Ok(live_tags(&page).len() as u64);
+        }
+
+        let mut sampled = 0;
+        let mut sampled_records = 0;
+        loop {
+            sampled_records += live_tags(&page).len() as u64;
+            sampled += 1;
+            if sampled == ESTIMATE_SAMPLE_PAGES || page.next_page() == 0 {
+                break;
+            }
+            page = jet::DbPage::new(&self.reader, page.next_page())?;
+        }
+        Ok(leaf_pages * (sampled_records / sampled).max(1))
+    }
+
+    /// Number of rows of the table, counted over all of its leaf pages
+    /// (see `EseDb::get_row_count`), deleted rows too with `ParserOptions::include_deleted`.
+    /// The cursor of the table isn't moved.
+    pub fn exact_row_count(&self, table: &str) -> Result<u64, EseError> {
+        self.get_row_count(table)
+    }
+
+    /// Key column names of the primary index of the table, in key order.
+    /// Empty if the table has no primary index (its rows are stored in insertion order).
+    pub fn get_table_primary_key_columns(&self, table: &str) -> Result<Vec<String>, EseError> {
+        let mut table_index: usize = 0;
+        let t = self.get_table_by_name(table, &mut table_index)?;
+        let primary_index = t
+            .cat
+            .index_catalog_definition_array
+            .iter()
+            .find(|i| i.index_flags().contains(jet::IndexFlags::Primary));
+        let primary_index = match primary_index {
+            Some(i) => i,
+            None => return Ok(vec![]),
+        };
+        primary_index
+            .key_segments
+            .iter()
+            .map(|segment| {
+                t.cat
+                    .column_catalog_definition_array
+                    .iter()
+                    .find(|c| c.identifier == segment.column_identifier)
+                    .map(|c| c.name.clone())
+                    .ok_or_else(|| {
+                        EseError::new(format!(
+                            "index {} of table {}: can't find key column {}",
+                            primary_index.name, table, segment.column_identifier
+                        ))
+                    })
+            })
+            .collect()
+    }
+
+    /// Compares the catalog with its shadow copy: table names and column definitions.
+    /// They differ only after an interrupted catalog update.
+    pub fn get_database_shadow_catalog_diverges(&self) -> Result<bool, EseError> {
+        let shadow = self.get_reader()?.load_shadow_catalog()?;
+        if shadow.len() != self.tables.len() {
+            return Ok(true);
+        }
+        let same_column = |a: &jet::CatalogDefinition, b: &jet::CatalogDefinition| {
+            a.identifier == b.identifier
+                && a.name == b.name
+                && a.column_type == b.column_type
+                && a.size == b.size
+                && a.codepage == b.codepage
+                && a.flags == b.flags
+        };
+        for (t, shadow_t) in self.tables.iter().zip(&shadow) {
+            let t = t.borrow();
+            let name = t.cat.table_catalog_definition.as_ref().map(|d| &d.name);
+            let shadow_name = shadow_t.table_catalog_definition.as_ref().map(|d| &d.name);
+            if name != shadow_name
+                || t.cat.column_catalog_definition_array.len()
+                    != shadow_t.column_catalog_definition_array.len()
+                || !t
+                    .cat
+                    .column_catalog_definition_array
+                    .iter()
+                    .zip(&shadow_t.column_catalog_definition_array)
+                    .all(|(a, b)| same_column(a, b))
+            {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Checks the counters and state of the file header and compares the catalog with its shadow.
+    /// Doesn't read the table pages.
+    pub fn get_database_health_report(&self) -> Result<HealthReport, EseError> {
+        let header = self.get_reader()?.file_header();
+        let mut issues = vec![];
+        let mut issue = |severity, description: String| {
+            issues.push(HealthIssue {
+                severity,
+                description,
+            })
+        };
+
+        let dirty_shutdown = matches!(header.database_state, jet::DbState::DirtyShutdown);
+        match header.database_state {
+            jet::DbState::CleanShutdown => {}
+            jet::DbState::DirtyShutdown => issue(
+                Severity::Warning,
+                "database was not shut down cleanly, log files are needed to recover it"
+                    .to_string(),
+            ),
+            state => issue(Severity::Warning, format!("database state is {}", state)),
+        }
+        let shadow_catalog_diverges = match self.get_database_shadow_catalog_diverges() {
+            Ok(diverges) => diverges,
+            Err(e) => {
+                issue(
+                    Severity::Error,
+                    format!("can't load the shadow catalog: {}", e),
+                );
+                true
+            }
+        };
+        if shadow_catalog_diverges {
+            issue(
+                Severity::Error,
+                "catalog differs from its shadow copy".to_string(),
+            );
+        }
+        if header.repair_count > 0 {
+            issue(
+                Severity::Warning,
+                format!("database was repaired {} time(s)", header.repair_count),
+            );
+        }
+        if header.ecc_fix_success_count > 0 {
+            issue(
+                Severity::Info,
+                format!(
+                    "{} page(s) had single bit errors fixed by ECC",
+                    header.ecc_fix_success_count
+                ),
+            );
+        }
+        if header.ecc_fix_error_count > 0 {
+            issue(
+                Severity::Error,
+                format!(
+                    "{} page(s) had errors ECC couldn't fix",
+                    header.ecc_fix_error_count
+                ),
+            );
+        }
+        if header.bad_checksum_error_count > 0 {
+            issue(
+                Severity::Error,
+                format!(
+                    "{} page(s) had bad checksums",
+                    header.bad_checksum_error_count
+                ),
+            );
+        }
+
+        Ok(HealthReport {
+            ecc_fix_success_count: header.ecc_fix_success_count,
+            ecc_fix_error_count: header.ecc_fix_error_count,
+            bad_checksum_error_count: header.bad_checksum_error_count,
+            dirty_shutdown,
+            shadow_catalog_diverges,
+            repair_count: header.repair_count,
+            format_description: jet::revision_to_string(
+                header.format_version,
+                header.format_revision,
+            ),
+            issues,
+        })
+    }
+
+    /// Walks the B-tree rooted at `page_number`, calling the visitor for every page and tag.
+    pub fn walk_btree(
+        &self,
+        page_number: u32,
+        visitor: &mut dyn btree::BTreeVisitor,
+    ) -> Result<(), EseError> {
+        btree::walk_btree(self.get_reader()?, page_number, visitor)
+    }
+
+    pub fn close_table_by_name(&self, table: &str) -> Result<(), EseError> {
+        let mut index: usize = 0;
+        drop(self.get_table_by_name(table, &mut index)?);
+        if self.close_table(index as u64) {
+            Ok(())
+        } else {
+            Err(EseError::new(format!("can't close table {}", table)))
+        }
+    }
+
+    /// Returns true between `open_table` and `close_table` calls.
+    pub fn is_table_open(&self, table: &str) -> bool {
+        let mut index: usize = 0;
+        self.get_table_by_name(table, &mut index)
+            .map(|t| t.is_open)
+            .unwrap_or(false)
+    }
+
+    pub fn get_fixed_column<T: FromBytes>(
+        &self,
+        table: u64,
+        column: u32,
+    ) -> Result<Option<T>, EseError> {
+        match self.get_column(table, column)? {
+            Some(v) => Ok(Some(T::from_bytes(&v))),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<R: ReaderBackend> EseDb for EseParser<R> {
+    fn error_to_string(&self, err: i32) -> String {
+        format!("EseParser: error {}", err)
+    }
+
+    fn get_tables(&self) -> Result<Vec<String>, EseError> {
+        let mut tables: Vec<String> = vec![];
+        for i in &self.tables {
+            let n = i.borrow();
+            tables.push(
+                n.cat
+                    .table_catalog_definition
+                    .as_ref()
+                    .unwrap()
+                    .name
+                    .clone(),
+            );
+        }
+        Ok(tables)
+    }
+
+    // A table has a single cursor: opening an open table saves its position,
+    // the matching close_table restores it. Default methods of EseDb open and close
+    // the tables they scan without moving the cursor of the caller.
+    fn open_table(&self, table: &str) -> Result<u64, EseError> {
+        let mut index: usize = 0;
+        {
+            // used to drop borrow mut
+            let mut t = self.get_table_by_name(table, &mut index)?;
+            if t.is_open {
+                let saved = SavedCursor {
+                    current_page: mem::take(&mut t.current_page),
+                    page_tag_index: t.page_tag_index,
+                    row_position: t.row_position,
+                    validity_info: mem::replace(
+                        &mut t.validity_info,
+                        ValidityInfo {
+                            visited_pages: vec![],
+                            direction: Direction::None,
+                        },
+                    ),
+                };
+                t.saved_cursors.push(saved);
+            } else if let Some(long_value_catalog_definition) = &t.cat.long_value_catalog_definition
+            {
+                let reader = self.get_reader()?;
+                t.lv_tags = reader
+                    .load_lv_metadata(long_value_catalog_definition.father_data_page_number)?;
+            }
+            t.is_open = true;
+        }
+        // ignore return result
+        self.move_row_helper(index as u64, ESE_MoveFirst)?;
+
+        Ok(index as u64)
+    }
+
+    fn close_table(&self, table: u64) -> bool {
+        let tags_index = table as usize;
+        if tags_index < self.tables.len() {
+            let mut itrnl = self.tables[tags_index].borrow_mut();
+            match itrnl.saved_cursors.pop() {
+                Some(saved) => {
+                    itrnl.current_page = saved.current_page;
+                    itrnl.set_row(saved.page_tag_index);
+                    itrnl.row_position = saved.row_position;
+                    itrnl.validity_info = saved.validity_info;
+                }
+                None => {
+                    itrnl.lv_tags.clear();
+                    itrnl.is_open = false;
+                }
+            }
+            return true;
+        }
+        false
+    }
+
+    fn get_columns(&self, table: &str) -> Result<Vec<ColumnInfo>, EseError> {
+        let mut index: usize = 0;
+        let t = self.get_table_by_name(table, &mut index)?;
+        let mut columns: Vec<ColumnInfo> = vec![];
+        for i in &t.cat.column_catalog_definition_array {
+            let col_info = ColumnInfo {
+                name: i.name.clone(),
+                id: i.identifier,
+                typ: i.column_type,
                 cbmax: i.size,
                 cp: i.codepage as u16,
             };
@@ -436,13 +2662,12 @@ impl<R: ReadSeek> EseDb for EseParser<R> {
         Ok(columns)
     }
 
-    fn move_row(&self, table: u64, crow: i32) -> Result<bool, SimpleError> {
+    fn move_row(&self, table: u64, crow: i32) -> Result<bool, EseError> {
         self.move_row_helper(table, crow)
-            .map_err(|e| SimpleError::new(format!("move_row failed: {:?}", e)))
     }
 
-    fn get_column(&self, table: u64, column: u32) -> Result<Option<Vec<u8>>, SimpleError> {
-        self.get_column_dyn_helper(table, column, 0)
+    fn get_column(&self, table: u64, column: u32) -> Result<Option<Vec<u8>>, EseError> {
+        self.get_column_dyn_helper(table, column, 0, TaggedLoadMode::Value)
     }
 
     fn get_column_mv(
@@ -450,8 +2675,114 @@ impl<R: ReadSeek> EseDb for EseParser<R> {
         table: u64,
         column: u32,
         multi_value_index: u32,
-    ) -> Result<Option<Vec<u8>>, SimpleError> {
-        self.get_column_dyn_helper(table, column, multi_value_index)
+    ) -> Result<Option<Vec<u8>>, EseError> {
+        self.get_column_dyn_helper(table, column, multi_value_index, TaggedLoadMode::Value)
+    }
+
+    // counts the multi-value offsets, without reading the values
+    fn get_column_mv_count(&self, table: u64, column: u32) -> Result<u32, EseError> {
+        if ColumnStorageClass::from_column_id(column) == ColumnStorageClass::Tagged {
+            self.get_column_dyn_helper(table, column, 1, TaggedLoadMode::ValueCount)?;
+            let count = self
+                .get_table_by_id(table)?
+                .lls
+                .borrow()
+                .tagged_column
+                .value_count;
+            if let Some(count) = count {
+                return Ok(count);
+            }
+        }
+        // not in the record (maybe a default value) or not tagged
+        Ok(self.get_column(table, column)?.is_some() as u32)
+    }
+
+    fn get_column_type(&self, table: u64, column: u32) -> Result<u32, EseError> {
+        let t = self.get_table_by_id(table)?;
+        t.cat
+            .column_catalog_definition_array
+            .iter()
+            .find(|col| col.identifier == column)
+            .map(|col| col.column_type)
+            .ok_or(EseError::ColumnNotFound(column))
+    }
+
+    // counts the records of the leaf pages, without moving the cursor of the table
+    fn get_row_count(&self, table: &str) -> Result<u64, EseError> {
+        let mut index: usize = 0;
+        self.get_table_by_name(table, &mut index)?;
+        let mut rows = 0;
+        for page in self.iter_pages_with_data(index as u64) {
+            let (_, page_tags) = page?;
+            rows += page_tags
+                .iter()
+                .skip(1)
+                .filter(|tag| {
+                    self.include_deleted
+                        || !tag.flags().intersects(jet::PageTagFlags::FLAG_IS_DEFUNCT)
+                })
+                .count() as u64;
+        }
+        Ok(rows)
+    }
+
+    fn get_page_size(&self) -> u32 {
+        self.get_reader().map(|r| r.page_size()).unwrap_or(0)
+    }
+
+    fn get_database_file_size(&self) -> Result<u64, EseError> {
+        self.get_reader()?.file_size()
+    }
+
+    // counts the records of the leaf pages and loads only the page holding the row
+    fn move_row_to_index(&self, table: u64, row_index: u64) -> Result<bool, EseError> {
+        let reader = self.get_reader()?;
+        let mut remaining = row_index;
+        for page in self.iter_pages_with_data(table) {
+            let (page_number, page_tags) = page?;
+            let records: Vec<usize> = page_tags
+                .iter()
+                .enumerate()
+                .skip(1)
+                .filter(|(_, tag)| {
+                    self.include_deleted
+                        || !tag.flags().intersects(jet::PageTagFlags::FLAG_IS_DEFUNCT)
+                })
+                .map(|(i, _)| i)
+                .collect();
+            if remaining < records.len() as u64 {
+                let page = jet::DbPage::new(reader, page_number)?;
+                let mut t = self.get_table_by_id(table)?;
+                // a new walk over the leaf pages starts here
+                t.update_validity_info_for_crow(ESE_MoveFirst);
+                t.set_current_page(page)?;
+                t.set_row(records[remaining as usize]);
+                t.row_position = Some(row_index);
+                return Ok(true);
+            }
+            remaining -= records.len() as u64;
+        }
+        Ok(false)
+    }
+
+    fn get_record_at_absolute_position(&self, table: u64, position: u64) -> Result<bool, EseError> {
+        // skip ahead from the current row instead of rescanning from the first one
+        let current = self.get_table_by_id(table)?.row_position;
+        let steps = match current {
+            Some(cur) if cur <= position => position - cur,
+            _ => {
+                if !self.move_row_helper(table, ESE_MoveFirst)? {
+                    return Ok(false);
+                }
+                position
+            }
+        };
+        for _ in 0..steps {
+            if !self.move_row_helper(table, ESE_MoveNext)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
     }
 }
 
@@ -532,6 +2863,9 @@ mod tests {
             table_catalog_definition: None,
             column_catalog_definition_array: vec![],
             long_value_catalog_definition: None,
+            index_catalog_definition_array: vec![],
+            callback_catalog_definition_array: vec![],
+            template_table: None,
         };
 
         Table {
@@ -539,6 +2873,10 @@ mod tests {
             lv_tags: HashMap::new(),
             current_page: CurrentPage::default(),
             page_tag_index: 0,
+            row_position: None,
+            row_generation: 0,
+            is_open: false,
+            saved_cursors: vec![],
             lls: RefCell::new(LastLoadState {
                 ..Default::default()
             }),
@@ -549,6 +2887,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_row_get_str() {
+        let column = |name: &str, cp: u16| ColumnInfo {
+            name: name.to_string(),
+            id: 128,
+            typ: ESE_coltypText,
+            cbmax: 255,
+            cp,
+        };
+        let row = Row {
+            columns: Rc::new(vec![
+                column("Western", 1252),
+                column("Unicode", 1200),
+                column("Odd", 1200),
+            ]),
+            values: vec![
+                Some(b"caf\xe9 \x80".to_vec()),
+                Some(vec![b'R', 0, 0xe9, 0]),
+                Some(vec![b'R', 0, 0xe9]),
+            ],
+            deleted: false,
+        };
+        assert_eq!(row.get_str("Western").unwrap().unwrap(), "café €");
+        assert_eq!(row.get_str("Unicode").unwrap().unwrap(), "Ré");
+        assert!(row.get_str("Odd").is_err());
+    }
+
     #[test]
     fn test_validity_info_direction() {
         let mut table = init_table();
@@ -743,10 +3108,11 @@ mod tests {
             "set_current_page failed for a fresh page"
         );
         assert_eq!(
-            Err(SimpleError::new(
-                "Circular page reference identified for page_number: 82"
-            )),
-            table.set_current_page(db_page),
+            "Circular page reference identified for page_number: 82",
+            table
+                .set_current_page(db_page)
+                .map_err(|e| e.to_string())
+                .unwrap_err(),
             "set_current_page didn't error for a revisited page"
         );
     }
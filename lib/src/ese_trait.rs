@@ -1,11 +1,17 @@
+use crate::error::EseError;
+use crate::utils::{guid_to_rfc4122, HyperLogLog};
 use crate::vartime::*;
 use byteorder::*;
-use chrono::{DateTime, TimeZone, Utc};
-use simple_error::SimpleError;
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::convert::TryInto;
 use std::mem;
 
-#[derive(Debug)]
+// memory limit of get_column_values_sorted
+const DEFAULT_SORT_MEMORY: usize = 100 * 1024 * 1024;
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ColumnInfo {
     pub name: String,
     pub id: u32,
@@ -14,6 +20,30 @@ pub struct ColumnInfo {
     pub cp: u16,
 }
 
+impl ColumnInfo {
+    pub fn storage_class(&self) -> ColumnStorageClass {
+        ColumnStorageClass::from_column_id(self.id)
+    }
+}
+
+// where the column value is stored in a record
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColumnStorageClass {
+    Fixed,
+    Variable,
+    Tagged,
+}
+
+impl ColumnStorageClass {
+    pub fn from_column_id(column_id: u32) -> ColumnStorageClass {
+        match column_id {
+            0..=127 => ColumnStorageClass::Fixed,
+            128..=255 => ColumnStorageClass::Variable,
+            _ => ColumnStorageClass::Tagged,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum ESE_CP {
     None = 0,
@@ -54,6 +84,39 @@ pub const ESE_coltypUnsignedShort: u32 = 17;
 pub const ESE_coltypUnsignedLongLong: u32 = 18;
 pub const ESE_coltypMax: u32 = 19;
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CatalogTableType {
+    // MSys* tables maintained by the database engine
+    System,
+    User,
+    All,
+}
+
+// lengths of the non null column values, percentiles are nearest-rank
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LengthDistribution {
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+    pub p50: usize,
+    pub p90: usize,
+    pub p99: usize,
+    pub null_count: u64,
+    pub non_null_count: u64,
+}
+
+// number of rows by presence (non null value) of two columns
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CoOccurrenceMatrix {
+    pub both_present: u64,
+    pub only_first: u64,
+    pub only_second: u64,
+    pub neither: u64,
+}
+
+// column name -> value of a single row
+pub type RowValues = HashMap<String, Option<Vec<u8>>>;
+
 pub const ESE_MoveFirst: i32 = -2147483648;
 pub const ESE_MovePrevious: i32 = -1;
 pub const ESE_MoveNext: i32 = 1;
@@ -62,89 +125,887 @@ pub const ESE_MoveLast: i32 = 2147483647;
 pub trait EseDb {
     fn error_to_string(&self, err: i32) -> String;
 
-    fn open_table(&self, table: &str) -> Result<u64, SimpleError>;
+    fn open_table(&self, table: &str) -> Result<u64, EseError>;
     fn close_table(&self, table: u64) -> bool;
 
-    fn get_tables(&self) -> Result<Vec<String>, SimpleError>;
-    fn get_columns(&self, table: &str) -> Result<Vec<ColumnInfo>, SimpleError>;
+    fn get_tables(&self) -> Result<Vec<String>, EseError>;
+    fn get_columns(&self, table: &str) -> Result<Vec<ColumnInfo>, EseError>;
+    fn get_column_type(&self, table: u64, column: u32) -> Result<u32, EseError>;
 
-    fn get_column(&self, table: u64, column: u32) -> Result<Option<Vec<u8>>, SimpleError>;
+    fn get_column(&self, table: u64, column: u32) -> Result<Option<Vec<u8>>, EseError>;
     fn get_column_mv(
         &self,
         table: u64,
         column: u32,
         multi_value_index: u32,
-    ) -> Result<Option<Vec<u8>>, SimpleError>;
+    ) -> Result<Option<Vec<u8>>, EseError>;
+
+    fn move_row(&self, table: u64, crow: i32) -> Result<bool, EseError>;
+
+    fn get_page_size(&self) -> u32;
+    fn get_database_file_size(&self) -> Result<u64, EseError>;
+
+    /// Number of values of `column` in the current row, 0 if it's null.
+    fn get_column_mv_count(&self, table: u64, column: u32) -> Result<u32, EseError> {
+        let mut count = 0;
+        while self.get_column_mv(table, column, count + 1)?.is_some() {
+            count += 1;
+        }
+        Ok(count)
+    }
 
-    fn move_row(&self, table: u64, crow: i32) -> Result<bool, SimpleError>;
+    /// Value of a date column, decoded by the column type: DateTime columns hold OLE automation
+    /// dates (see `date_from_bytes`), 8 bytes integer columns FILETIMEs. Other columns return an error.
+    fn get_column_date(&self, table: u64, column: u32) -> Result<Option<DateTime<Utc>>, EseError> {
+        let column_type = self.get_column_type(table, column)?;
+        let value = match self.get_column(table, column)? {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+        match column_type {
+            ESE_coltypDateTime => date_from_bytes(&value),
+            ESE_coltypLongLong | ESE_coltypUnsignedLongLong => {
+                filetime_from_bytes(&value).map(Some)
+            }
+            _ => Err(EseError::new(format!(
+                "column {} of type {} doesn't hold dates",
+                column, column_type
+            ))),
+        }
+    }
+
+    /// Value of a GUID column in RFC 4122 byte order
+    /// (the first three fields are stored little-endian).
+    fn get_column_guid(&self, table: u64, column: u32) -> Result<Option<[u8; 16]>, EseError> {
+        Ok(
+            typed_column::<_, [u8; 16]>(self, table, column, ESE_coltypGUID)?
+                .map(|v| guid_to_rfc4122(&v)),
+        )
+    }
+
+    #[cfg(feature = "uuid")]
+    fn get_column_uuid(&self, table: u64, column: u32) -> Result<Option<uuid::Uuid>, EseError> {
+        Ok(self
+            .get_column_guid(table, column)?
+            .map(uuid::Uuid::from_bytes))
+    }
 
-    fn get_column_date(
+    /// Value of a DateTime column, read as an OLE automation date.
+    /// Columns holding FILETIMEs (read as doubles they are close to 0) return an error,
+    /// `get_column_date` reads them.
+    fn get_column_datetime(
         &self,
         table: u64,
         column: u32,
-    ) -> Result<Option<DateTime<Utc>>, SimpleError> {
-        let r = self.get_column(table, column)?;
-        if let Some(v) = r {
-            let vartime = f64::from_le_bytes(v.clone().try_into().unwrap());
-            let mut st = SYSTEMTIME::default();
-            if VariantTimeToSystemTime(vartime as f64, &mut st) {
-                // this is obviously not the right function! I didn't know what the right one was off the top of my head.
-                // We need to include the time component. also needs to be something that returns a DateTime.
-                let datetime = Utc.with_ymd_and_hms(
-                    st.wYear as i32,
-                    st.wMonth as u32,
-                    st.wDay as u32,
-                    st.wHour as u32,
-                    st.wMinute as u32,
-                    st.wSecond as u32,
-                );
-                Ok(datetime.single())
-            } else {
-                let filetime = u64::from_le_bytes(v.try_into().unwrap());
-                let datetime = get_date_time_from_filetime(filetime);
-                Ok(Some(datetime))
+    ) -> Result<Option<DateTime<Utc>>, EseError> {
+        let typ = self.get_column_type(table, column)?;
+        if typ != ESE_coltypDateTime {
+            return Err(EseError::new(format!(
+                "column {} is of type {}, expected {}",
+                column, typ, ESE_coltypDateTime
+            )));
+        }
+        match self.get_column(table, column)? {
+            Some(v) => {
+                let date = f64::from_le_bytes(date_bytes(&v)?);
+                if is_filetime_as_double(date) {
+                    return Err(EseError::new(format!(
+                        "column {}: {:?} isn't an OLE automation date",
+                        column, v
+                    )));
+                }
+                get_date_time_from_ole(date)
+                    .map(Some)
+                    .ok_or_else(|| EseError::new(format!("wrong OLE automation date: {}", date)))
             }
-        } else {
-            Ok(None)
+            None => Ok(None),
         }
     }
 
-    fn get_column_str(
+    // typed accessors, fail if the column isn't of the matching type
+    fn get_column_bool(&self, table: u64, column: u32) -> Result<Option<bool>, EseError> {
+        Ok(typed_column::<_, u8>(self, table, column, ESE_coltypBit)?.map(|v| v != 0))
+    }
+
+    fn get_column_u8(&self, table: u64, column: u32) -> Result<Option<u8>, EseError> {
+        typed_column(self, table, column, ESE_coltypUnsignedByte)
+    }
+
+    fn get_column_i16(&self, table: u64, column: u32) -> Result<Option<i16>, EseError> {
+        typed_column(self, table, column, ESE_coltypShort)
+    }
+
+    fn get_column_u16(&self, table: u64, column: u32) -> Result<Option<u16>, EseError> {
+        typed_column(self, table, column, ESE_coltypUnsignedShort)
+    }
+
+    fn get_column_i32(&self, table: u64, column: u32) -> Result<Option<i32>, EseError> {
+        typed_column(self, table, column, ESE_coltypLong)
+    }
+
+    fn get_column_u32(&self, table: u64, column: u32) -> Result<Option<u32>, EseError> {
+        typed_column(self, table, column, ESE_coltypUnsignedLong)
+    }
+
+    fn get_column_i64(&self, table: u64, column: u32) -> Result<Option<i64>, EseError> {
+        typed_column(self, table, column, ESE_coltypLongLong)
+    }
+
+    fn get_column_f32(&self, table: u64, column: u32) -> Result<Option<f32>, EseError> {
+        typed_column(self, table, column, ESE_coltypIEEESingle)
+    }
+
+    fn get_column_f64(&self, table: u64, column: u32) -> Result<Option<f64>, EseError> {
+        typed_column(self, table, column, ESE_coltypIEEEDouble)
+    }
+
+    /// Text value of `column` in code page `cp`: 1200 is UTF-16LE, 1252 Windows-1252
+    /// and any other code page UTF-8 (see `utils::decode_text`).
+    fn get_column_str(&self, table: u64, column: u32, cp: u16) -> Result<Option<String>, EseError> {
+        match self.get_column(table, column)? {
+            Some(v) => crate::utils::decode_text(&v, cp, false)
+                .map(Some)
+                .map_err(|offset| {
+                    EseError::new(format!(
+                        "column {}: invalid text in code page {} at byte offset {}",
+                        column, cp, offset
+                    ))
+                }),
+            None => Ok(None),
+        }
+    }
+
+    /// Value `mv_index` of `column` decoded as UTF-16LE, whatever the code page of the column.
+    fn get_column_str_utf16le(
         &self,
         table: u64,
         column: u32,
-        cp: u16,
-    ) -> Result<Option<String>, SimpleError> {
-        use std::convert::TryFrom;
-        let r = self.get_column(table, column)?;
-        if let Some(v) = r {
-            if ESE_CP::try_from(cp).expect("Failed to get ESE cp") == ESE_CP::Unicode {
-                let mut vec16: Vec<u16> = vec![0; v.len() / mem::size_of::<u16>()];
-                LittleEndian::read_u16_into(&v, &mut vec16);
-                match String::from_utf16(&vec16[..]) {
-                    Ok(s) => return Ok(Some(s)),
-                    Err(e) => {
-                        return Err(SimpleError::new(format!(
-                            "String::from_utf16 failed: {}",
-                            e
+        mv_index: u32,
+    ) -> Result<Option<String>, EseError> {
+        match self.get_column_mv(table, column, mv_index)? {
+            Some(v) => crate::utils::decode_text(&v, ESE_CP::Unicode as u16, false)
+                .map(Some)
+                .map_err(|offset| {
+                    EseError::new(format!(
+                        "column {}: invalid UTF-16 text at byte offset {}",
+                        column, offset
+                    ))
+                }),
+            None => Ok(None),
+        }
+    }
+
+    /// Text value of `column` decoded by its code page from the catalog (see `utils::decode_text`).
+    /// An invalid sequence is an error, unless `lossy` replaces it with U+FFFD.
+    fn get_column_string(
+        &self,
+        table: u64,
+        column: &ColumnInfo,
+        lossy: bool,
+    ) -> Result<Option<String>, EseError> {
+        match self.get_column(table, column.id)? {
+            Some(v) => crate::utils::decode_text(&v, column.cp, lossy)
+                .map(Some)
+                .map_err(|offset| {
+                    EseError::new(format!(
+                        "column {}: invalid text in code page {} at byte offset {}",
+                        column.name, column.cp, offset
+                    ))
+                }),
+            None => Ok(None),
+        }
+    }
+
+    /// Scans every row of the table and returns the smallest and largest value
+    /// of the column, compared byte-lexicographically.
+    /// Returns `(None, None)` for an empty table or a column without values.
+    #[allow(clippy::type_complexity)]
+    fn get_column_min_max(
+        &self,
+        table: u64,
+        column: u32,
+    ) -> Result<(Option<Vec<u8>>, Option<Vec<u8>>), EseError> {
+        let mut min: Option<Vec<u8>> = None;
+        let mut max: Option<Vec<u8>> = None;
+        let mut has_row = self.move_row(table, ESE_MoveFirst)?;
+        while has_row {
+            if let Some(v) = self.get_column(table, column)? {
+                if min.is_none() || min.as_ref() > Some(&v) {
+                    min = Some(v.clone());
+                }
+                if max.as_ref() < Some(&v) {
+                    max = Some(v);
+                }
+            }
+            has_row = self.move_row(table, ESE_MoveNext)?;
+        }
+        Ok((min, max))
+    }
+
+    /// Same as `get_column_min_max`, but values are decoded as little-endian
+    /// signed integers (1, 2, 4 or 8 bytes, sign-extended to i64) and compared numerically.
+    fn get_column_min_max_i64(
+        &self,
+        table: u64,
+        column: u32,
+    ) -> Result<(Option<i64>, Option<i64>), EseError> {
+        let mut min: Option<i64> = None;
+        let mut max: Option<i64> = None;
+        let mut has_row = self.move_row(table, ESE_MoveFirst)?;
+        while has_row {
+            if let Some(v) = self.get_column(table, column)? {
+                let n = match v.len() {
+                    1 => i8::from_bytes(&v) as i64,
+                    2 => i16::from_bytes(&v) as i64,
+                    4 => i32::from_bytes(&v) as i64,
+                    8 => i64::from_bytes(&v),
+                    l => {
+                        return Err(EseError::new(format!(
+                            "column {} value of size {} can't be decoded as integer",
+                            column, l
                         )))
                     }
+                };
+                min = Some(min.map_or(n, |m| m.min(n)));
+                max = Some(max.map_or(n, |m| m.max(n)));
+            }
+            has_row = self.move_row(table, ESE_MoveNext)?;
+        }
+        Ok((min, max))
+    }
+
+    /// Positions the cursor on the row with the given 0-based index in the
+    /// sequential scan order. Returns `false` if the table has fewer rows.
+    fn get_record_at_absolute_position(&self, table: u64, position: u64) -> Result<bool, EseError> {
+        if !self.move_row(table, ESE_MoveFirst)? {
+            return Ok(false);
+        }
+        for _ in 0..position {
+            if !self.move_row(table, ESE_MoveNext)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Positions the cursor on the row with the given 0-based index, counted from the first row.
+    /// Returns `false` if the table has fewer rows.
+    fn move_row_to_index(&self, table: u64, row_index: u64) -> Result<bool, EseError> {
+        self.get_record_at_absolute_position(table, row_index)
+    }
+
+    fn get_tables_by_type(&self, table_type: CatalogTableType) -> Result<Vec<String>, EseError> {
+        let mut tables = self.get_tables()?;
+        if table_type != CatalogTableType::All {
+            let system = table_type == CatalogTableType::System;
+            tables.retain(|t| t.starts_with("MSys") == system);
+        }
+        Ok(tables)
+    }
+
+    fn get_column_storage_class(
+        &self,
+        table: &str,
+        column_id: u32,
+    ) -> Result<ColumnStorageClass, EseError> {
+        self.get_columns(table)?
+            .iter()
+            .find(|col| col.id == column_id)
+            .map(|col| col.storage_class())
+            .ok_or(EseError::ColumnNotFound(column_id))
+    }
+
+    /// Scans up to `max_rows` rows (all rows if `None`) and describes the value lengths of the column.
+    fn get_column_value_length_distribution(
+        &self,
+        table: u64,
+        column: u32,
+        max_rows: Option<u64>,
+    ) -> Result<LengthDistribution, EseError> {
+        let mut dist = LengthDistribution::default();
+        // value length -> count, bounded by the column size
+        let mut histogram: BTreeMap<usize, u64> = BTreeMap::new();
+        let mut total = 0u64;
+        let mut rows = 0u64;
+        let mut has_row = self.move_row(table, ESE_MoveFirst)?;
+        while has_row && Some(rows) != max_rows {
+            match self.get_column(table, column)? {
+                Some(v) => {
+                    *histogram.entry(v.len()).or_default() += 1;
+                    total += v.len() as u64;
+                    dist.non_null_count += 1;
+                }
+                None => dist.null_count += 1,
+            }
+            rows += 1;
+            has_row = self.move_row(table, ESE_MoveNext)?;
+        }
+        if dist.non_null_count == 0 {
+            return Ok(dist);
+        }
+
+        let non_null_count = dist.non_null_count;
+        let max = *histogram.keys().next_back().unwrap_or(&0);
+        let percentile = |p: u64| {
+            let rank = (p * non_null_count).div_ceil(100);
+            let mut seen = 0;
+            for (&len, &count) in &histogram {
+                seen += count;
+                if seen >= rank {
+                    return len;
+                }
+            }
+            max
+        };
+        dist.min = *histogram.keys().next().unwrap_or(&0);
+        dist.max = max;
+        dist.mean = total as f64 / non_null_count as f64;
+        dist.p50 = percentile(50);
+        dist.p90 = percentile(90);
+        dist.p99 = percentile(99);
+        Ok(dist)
+    }
+
+    /// Returns table name -> number of columns
+    fn get_column_count_across_tables(&self) -> Result<HashMap<String, usize>, EseError> {
+        let mut counts = HashMap::new();
+        for t in self.get_tables()? {
+            let count = self.get_columns(&t)?.len();
+            counts.insert(t, count);
+        }
+        Ok(counts)
+    }
+
+    fn get_total_column_count(&self) -> Result<usize, EseError> {
+        Ok(self.get_column_count_across_tables()?.values().sum())
+    }
+
+    /// Returns the table with the most columns (the first one in the catalog on a tie)
+    fn get_max_column_count_table(&self) -> Result<(String, usize), EseError> {
+        let mut max: Option<(String, usize)> = None;
+        for t in self.get_tables()? {
+            let count = self.get_columns(&t)?.len();
+            match &max {
+                Some((_, m)) if *m >= count => {}
+                _ => max = Some((t, count)),
+            }
+        }
+        max.ok_or_else(|| EseError::new("no tables in the database"))
+    }
+
+    /// Number of rows of the table
+    fn get_row_count(&self, table: &str) -> Result<u64, EseError> {
+        with_open_table(self, table, |table_id| {
+            let mut rows = 0;
+            let mut has_row = self.move_row(table_id, ESE_MoveFirst)?;
+            while has_row {
+                rows += 1;
+                has_row = self.move_row(table_id, ESE_MoveNext)?;
+            }
+            Ok(rows)
+        })
+    }
+
+    /// Tables having a column named `column_name` (compared case-insensitively).
+    fn find_tables_with_column_name(&self, column_name: &str) -> Result<Vec<String>, EseError> {
+        let mut tables = vec![];
+        for t in self.get_tables()? {
+            if self
+                .get_columns(&t)?
+                .iter()
+                .any(|c| crate::utils::eq_ignore_case(&c.name, column_name))
+            {
+                tables.push(t);
+            }
+        }
+        Ok(tables)
+    }
+
+    /// Tables having columns of type `col_type` (`ESE_coltyp*`), with the names of these columns.
+    fn find_tables_with_column_type(
+        &self,
+        col_type: u32,
+    ) -> Result<Vec<(String, Vec<String>)>, EseError> {
+        let mut tables = vec![];
+        for t in self.get_tables()? {
+            let columns: Vec<String> = self
+                .get_columns(&t)?
+                .into_iter()
+                .filter(|c| c.typ == col_type)
+                .map(|c| c.name)
+                .collect();
+            if !columns.is_empty() {
+                tables.push((t, columns));
+            }
+        }
+        Ok(tables)
+    }
+
+    /// Stable hash of the table column definitions (names, types, sizes and codepages),
+    /// changes whenever the table schema changes.
+    fn get_table_schema_hash(&self, table: &str) -> Result<u64, EseError> {
+        let mut columns = self.get_columns(table)?;
+        columns.sort_by_key(|c| c.id);
+        let mut ctx = md5::Context::new();
+        for c in &columns {
+            ctx.consume(c.id.to_le_bytes());
+            ctx.consume((c.name.len() as u32).to_le_bytes());
+            ctx.consume(c.name.as_bytes());
+            ctx.consume(c.typ.to_le_bytes());
+            ctx.consume(c.cbmax.to_le_bytes());
+            ctx.consume(c.cp.to_le_bytes());
+        }
+        Ok(LittleEndian::read_u64(&ctx.finalize().0))
+    }
+
+    /// Stable hash of the schemas of all tables.
+    fn get_database_schema_hash(&self) -> Result<u64, EseError> {
+        let mut tables = self.get_tables()?;
+        tables.sort();
+        let mut ctx = md5::Context::new();
+        for t in &tables {
+            ctx.consume((t.len() as u32).to_le_bytes());
+            ctx.consume(t.as_bytes());
+            ctx.consume(self.get_table_schema_hash(t)?.to_le_bytes());
+        }
+        Ok(LittleEndian::read_u64(&ctx.finalize().0))
+    }
+
+    /// Counts rows where the column holds `value` (any of the values for multi-valued columns).
+    /// An empty `value` counts rows where the column is null.
+    /// The scan stops once the count reaches `limit`.
+    fn get_column_value_frequency(
+        &self,
+        table: u64,
+        column: u32,
+        value: &[u8],
+        limit: Option<u64>,
+    ) -> Result<u64, EseError> {
+        let mut count = 0;
+        let mut has_row = self.move_row(table, ESE_MoveFirst)?;
+        while has_row && Some(count) != limit {
+            let found = if value.is_empty() {
+                self.get_column(table, column)?.is_none()
+            } else {
+                let mut found = false;
+                let mut multi_value_index = 1;
+                while let Some(v) = self.get_column_mv(table, column, multi_value_index)? {
+                    if v == value {
+                        found = true;
+                        break;
+                    }
+                    multi_value_index += 1;
+                }
+                found
+            };
+            if found {
+                count += 1;
+            }
+            has_row = self.move_row(table, ESE_MoveNext)?;
+        }
+        Ok(count)
+    }
+
+    /// Counts distinct non-null values of the column (every value of multi-valued columns).
+    /// The scan stops once `limit` distinct values are seen.
+    fn get_column_value_distinct_count(
+        &self,
+        table: u64,
+        column: u32,
+        limit: u64,
+    ) -> Result<u64, EseError> {
+        let mut values = HashSet::new();
+        let mut has_row = self.move_row(table, ESE_MoveFirst)?;
+        while has_row && (values.len() as u64) < limit {
+            let mut multi_value_index = 1;
+            while let Some(v) = self.get_column_mv(table, column, multi_value_index)? {
+                values.insert(v);
+                if values.len() as u64 == limit {
+                    break;
+                }
+                multi_value_index += 1;
+            }
+            has_row = self.move_row(table, ESE_MoveNext)?;
+        }
+        Ok(values.len() as u64)
+    }
+
+    /// Same as `get_column_value_distinct_count` without a limit, estimated with HyperLogLog
+    /// in constant memory (the error is about 1%).
+    fn get_column_value_approx_distinct_count(
+        &self,
+        table: u64,
+        column: u32,
+    ) -> Result<u64, EseError> {
+        let mut hll = HyperLogLog::new();
+        let mut has_row = self.move_row(table, ESE_MoveFirst)?;
+        while has_row {
+            let mut multi_value_index = 1;
+            while let Some(v) = self.get_column_mv(table, column, multi_value_index)? {
+                hll.insert(&v);
+                multi_value_index += 1;
+            }
+            has_row = self.move_row(table, ESE_MoveNext)?;
+        }
+        Ok(hll.estimate())
+    }
+
+    /// Shannon entropy (in bits) of the non-null values of the column over the rows.
+    /// 0 when all the values are equal or null, log2(rows) when they are all distinct.
+    fn get_column_entropy(&self, table: u64, column: u32) -> Result<f64, EseError> {
+        let mut frequencies: HashMap<Vec<u8>, u64> = HashMap::new();
+        let mut total = 0u64;
+        let mut has_row = self.move_row(table, ESE_MoveFirst)?;
+        while has_row {
+            if let Some(v) = self.get_column(table, column)? {
+                *frequencies.entry(v).or_insert(0) += 1;
+                total += 1;
+            }
+            has_row = self.move_row(table, ESE_MoveNext)?;
+        }
+        if frequencies.len() < 2 {
+            return Ok(0.0);
+        }
+        Ok(frequencies
+            .values()
+            .map(|&n| {
+                let p = n as f64 / total as f64;
+                -p * p.log2()
+            })
+            .sum())
+    }
+
+    /// Values of the column in all rows, sorted by bytes, nulls first.
+    /// Fails if the values take more than `max_memory` bytes (100MB if `None`).
+    fn get_column_values_sorted(
+        &self,
+        table: &str,
+        column: u32,
+        max_memory: Option<usize>,
+    ) -> Result<Vec<Option<Vec<u8>>>, EseError> {
+        let max_memory = max_memory.unwrap_or(DEFAULT_SORT_MEMORY);
+        let mut values = with_open_table(self, table, |table_id| {
+            let mut values = vec![];
+            let mut memory = 0;
+            let mut has_row = self.move_row(table_id, ESE_MoveFirst)?;
+            while has_row {
+                let v = self.get_column(table_id, column)?;
+                memory += mem::size_of::<Option<Vec<u8>>>() + v.as_ref().map_or(0, |v| v.len());
+                if memory > max_memory {
+                    return Err(EseError::new(format!(
+                        "values of column {} in table {} exceed {} bytes",
+                        column, table, max_memory
+                    )));
                 }
+                values.push(v);
+                has_row = self.move_row(table_id, ESE_MoveNext)?;
+            }
+            Ok(values)
+        })?;
+        values.sort_unstable();
+        Ok(values)
+    }
+
+    /// Returns up to `n` first rows of the table, each as a map of column name to value.
+    fn get_table_top_n_rows(&self, table: &str, n: usize) -> Result<Vec<RowValues>, EseError> {
+        let columns = self.get_columns(table)?;
+        with_open_table(self, table, |table_id| {
+            let mut rows = vec![];
+            let mut has_row = self.move_row(table_id, ESE_MoveFirst)?;
+            while has_row && rows.len() < n {
+                rows.push(read_row(self, table_id, &columns)?);
+                has_row = self.move_row(table_id, ESE_MoveNext)?;
+            }
+            Ok(rows)
+        })
+    }
+
+    /// Reads the named columns of the first row of the table.
+    /// Returns `None` if the table is empty.
+    fn get_first_row_values(
+        &self,
+        table: &str,
+        columns: &[&str],
+    ) -> Result<Option<RowValues>, EseError> {
+        let table_columns = self.get_columns(table)?;
+        let mut selected = vec![];
+        for &name in columns {
+            match table_columns.iter().find(|c| c.name == name) {
+                Some(c) => selected.push(c.clone()),
+                None => return Err(EseError::ColumnNameNotFound(name.to_string())),
+            }
+        }
+        with_open_table(self, table, |table_id| {
+            if self.move_row(table_id, ESE_MoveFirst)? {
+                read_row(self, table_id, &selected).map(Some)
             } else {
-                match std::str::from_utf8(&v) {
-                    Ok(s) => return Ok(Some(s.to_string())),
-                    Err(e) => {
-                        return Err(SimpleError::new(format!(
-                            "std::str::from_utf8 failed: {}",
-                            e
-                        )))
+                Ok(None)
+            }
+        })
+    }
+
+    /// Returns `n` rows picked uniformly at random (reservoir sampling), in scan order.
+    /// The same `seed` always yields the same sample.
+    fn get_table_sample(
+        &self,
+        table: &str,
+        n: usize,
+        seed: u64,
+    ) -> Result<Vec<RowValues>, EseError> {
+        let columns = self.get_columns(table)?;
+        // xorshift64*, zero seed is not allowed
+        let mut state = seed | 1;
+        let mut next_random = move || {
+            state ^= state >> 12;
+            state ^= state << 25;
+            state ^= state >> 27;
+            state.wrapping_mul(0x2545f4914f6cdd1d)
+        };
+        let mut sample = with_open_table(self, table, |table_id| {
+            let mut sample: Vec<(u64, RowValues)> = vec![];
+            let mut index = 0u64;
+            let mut has_row = n > 0 && self.move_row(table_id, ESE_MoveFirst)?;
+            while has_row {
+                if sample.len() < n {
+                    sample.push((index, read_row(self, table_id, &columns)?));
+                } else {
+                    let j = (next_random() % (index + 1)) as usize;
+                    if j < n {
+                        sample[j] = (index, read_row(self, table_id, &columns)?);
                     }
                 }
+                index += 1;
+                has_row = self.move_row(table_id, ESE_MoveNext)?;
             }
-        } else {
-            Ok(None)
+            Ok(sample)
+        })?;
+        sample.sort_by_key(|(i, _)| *i);
+        Ok(sample.into_iter().map(|(_, row)| row).collect())
+    }
+
+    /// 2x2 contingency table of the presence of two columns in the rows of the table.
+    fn get_column_co_occurrence_matrix(
+        &self,
+        table: &str,
+        col_id1: u32,
+        col_id2: u32,
+    ) -> Result<CoOccurrenceMatrix, EseError> {
+        let (rows, m) = column_presence(self, table, &[col_id1, col_id2])?;
+        Ok(CoOccurrenceMatrix {
+            both_present: m[0][1],
+            only_first: m[0][0] - m[0][1],
+            only_second: m[1][1] - m[0][1],
+            neither: rows - m[0][0] - m[1][1] + m[0][1],
+        })
+    }
+
+    /// N x N matrix, `[i][j]` is the number of rows where both `column_ids[i]` and `column_ids[j]`
+    /// are present. The diagonal holds the number of rows where the column is present.
+    fn get_column_presence_matrix(
+        &self,
+        table: &str,
+        column_ids: &[u32],
+    ) -> Result<Vec<Vec<u64>>, EseError> {
+        let (_, m) = column_presence(self, table, column_ids)?;
+        Ok(m)
+    }
+
+    /// Largest record of the table, as the total size of the column values present in the row
+    /// (all values of multi-valued columns). 0 for an empty table.
+    fn get_max_record_size(&self, table: &str) -> Result<u32, EseError> {
+        let (_, max, _) = record_sizes(self, table)?;
+        Ok(max)
+    }
+
+    /// Average record size of the table, same measure as `get_max_record_size`.
+    fn get_avg_record_size(&self, table: &str) -> Result<f64, EseError> {
+        let (rows, _, total) = record_sizes(self, table)?;
+        if rows == 0 {
+            return Ok(0.0);
         }
+        Ok(total as f64 / rows as f64)
     }
+
+    /// Writes the table into a Parquet file (see `parquet::export_table`).
+    #[cfg(feature = "parquet")]
+    fn export_to_parquet(&self, table: &str, path: &std::path::Path) -> Result<u64, EseError> {
+        crate::parquet::export_table(self, table, path)
+    }
+
+    /// Writes the table as CSV (see `csv::export_table`), returns the number of rows.
+    fn export_table_to_csv(
+        &self,
+        table: &str,
+        out: &mut dyn std::io::Write,
+        opts: &crate::csv::CsvOptions,
+    ) -> Result<u64, EseError> {
+        crate::csv::export_table(self, table, out, opts)
+    }
+
+    /// Copies the table into an SQLite database (see `sqlite::export_table`).
+    #[cfg(feature = "sqlite")]
+    fn export_table_to_sqlite(
+        &self,
+        table: &str,
+        conn: &rusqlite::Connection,
+    ) -> Result<u64, EseError> {
+        crate::sqlite::export_table(self, table, conn)
+    }
+
+    /// Copies every table of the database into SQLite, returns the total number of rows.
+    #[cfg(feature = "sqlite")]
+    fn export_all_tables_to_sqlite(&self, conn: &rusqlite::Connection) -> Result<u64, EseError> {
+        let mut rows = 0;
+        for table in self.get_tables()? {
+            rows += self.export_table_to_sqlite(&table, conn)?;
+        }
+        Ok(rows)
+    }
+}
+
+/// Converts a DateTime column value, an OLE automation date, to `DateTime<Utc>`:
+/// 0 is 1899-12-30, negative values are before it (see `vartime::get_date_time_from_ole`).
+/// Some writers store FILETIMEs in DateTime columns, they are read as FILETIMEs.
+/// Values out of the 100-01-01 - 9999-12-31 range return an error.
+pub fn date_from_bytes(v: &[u8]) -> Result<Option<DateTime<Utc>>, EseError> {
+    let bytes = date_bytes(v)?;
+    let date = f64::from_le_bytes(bytes);
+    if is_filetime_as_double(date) {
+        return Ok(Some(get_date_time_from_filetime(u64::from_le_bytes(bytes))));
+    }
+    get_date_time_from_ole(date)
+        .map(Some)
+        .ok_or_else(|| EseError::new(format!("wrong OLE automation date: {}", date)))
+}
+
+// FILETIMEs up to the year 15000 read as doubles are below 1e-9 (less than a millisecond after
+// the OLE epoch), no OLE automation date is written there
+fn is_filetime_as_double(date: f64) -> bool {
+    date != 0.0 && date.abs() < 1e-9
+}
+
+/// Converts a FILETIME stored in an 8 bytes integer column to `DateTime<Utc>`
+pub fn filetime_from_bytes(v: &[u8]) -> Result<DateTime<Utc>, EseError> {
+    Ok(get_date_time_from_filetime(u64::from_le_bytes(date_bytes(
+        v,
+    )?)))
+}
+
+// value of a fixed size column of type `column_type`
+fn typed_column<D: EseDb + ?Sized, T: FromBytes>(
+    db: &D,
+    table: u64,
+    column: u32,
+    column_type: u32,
+) -> Result<Option<T>, EseError> {
+    let typ = db.get_column_type(table, column)?;
+    if typ != column_type {
+        return Err(EseError::new(format!(
+            "column {} is of type {}, expected {}",
+            column, typ, column_type
+        )));
+    }
+    match db.get_column(table, column)? {
+        Some(v) if v.len() != mem::size_of::<T>() => Err(EseError::new(format!(
+            "column {}: unexpected value size {}, expected {}",
+            column,
+            v.len(),
+            mem::size_of::<T>()
+        ))),
+        Some(v) => Ok(Some(T::from_bytes(&v))),
+        None => Ok(None),
+    }
+}
+
+fn date_bytes(v: &[u8]) -> Result<[u8; 8], EseError> {
+    v.try_into().map_err(|_| {
+        EseError::new(format!(
+            "wrong DateTime value size: {}, expected 8 bytes",
+            v.len()
+        ))
+    })
+}
+
+// (number of rows, max record size, total size of records)
+fn record_sizes<D: EseDb + ?Sized>(db: &D, table: &str) -> Result<(u64, u32, u64), EseError> {
+    let columns = db.get_columns(table)?;
+    with_open_table(db, table, |table_id| {
+        let mut rows = 0u64;
+        let mut max = 0u32;
+        let mut total = 0u64;
+        let mut has_row = db.move_row(table_id, ESE_MoveFirst)?;
+        while has_row {
+            let mut size = 0u32;
+            for c in &columns {
+                let mut multi_value_index = 1;
+                while let Some(v) = db.get_column_mv(table_id, c.id, multi_value_index)? {
+                    size += v.len() as u32;
+                    multi_value_index += 1;
+                }
+            }
+            rows += 1;
+            max = max.max(size);
+            total += size as u64;
+            has_row = db.move_row(table_id, ESE_MoveNext)?;
+        }
+        Ok((rows, max, total))
+    })
+}
+
+// (number of rows, presence matrix of the columns)
+fn column_presence<D: EseDb + ?Sized>(
+    db: &D,
+    table: &str,
+    column_ids: &[u32],
+) -> Result<(u64, Vec<Vec<u64>>), EseError> {
+    with_open_table(db, table, |table_id| {
+        let mut rows = 0u64;
+        let mut matrix = vec![vec![0u64; column_ids.len()]; column_ids.len()];
+        let mut present = vec![false; column_ids.len()];
+        let mut has_row = db.move_row(table_id, ESE_MoveFirst)?;
+        while has_row {
+            for (p, &c) in present.iter_mut().zip(column_ids) {
+                *p = db.get_column(table_id, c)?.is_some();
+            }
+            for (i, row) in matrix.iter_mut().enumerate() {
+                if present[i] {
+                    for (j, count) in row.iter_mut().enumerate() {
+                        if present[j] {
+                            *count += 1;
+                        }
+                    }
+                }
+            }
+            rows += 1;
+            has_row = db.move_row(table_id, ESE_MoveNext)?;
+        }
+        Ok((rows, matrix))
+    })
+}
+
+// opens the table for `f` and closes it whatever `f` returns,
+// so a caller having the table open gets its cursor back on errors too
+fn with_open_table<D: EseDb + ?Sized, T>(
+    db: &D,
+    table: &str,
+    f: impl FnOnce(u64) -> Result<T, EseError>,
+) -> Result<T, EseError> {
+    let table_id = db.open_table(table)?;
+    let result = f(table_id);
+    db.close_table(table_id);
+    result
+}
+
+pub(crate) fn read_row<D: EseDb + ?Sized>(
+    db: &D,
+    table: u64,
+    columns: &[ColumnInfo],
+) -> Result<RowValues, EseError> {
+    let mut row = HashMap::new();
+    for c in columns {
+        row.insert(c.name.clone(), db.get_column(table, c.id)?);
+    }
+    Ok(row)
 }
 
 pub trait FromBytes {
@@ -210,3 +1071,9 @@ impl FromBytes for f64 {
         f64::from_le_bytes(bytes.try_into().unwrap())
     }
 }
+
+impl FromBytes for [u8; 16] {
+    fn from_bytes(bytes: &[u8]) -> Self {
+        bytes.try_into().unwrap()
+    }
+}
@@ -2,22 +2,24 @@
 use crate::ese_trait::*;
 use crate::esent::esent::*;
 
-use simple_error::SimpleError;
+use crate::error::EseError;
 
 use std::ffi::CString;
 use std::mem::{size_of, MaybeUninit};
-use std::os::raw::{c_ulong, c_void};
-use std::path::Path;
+use std::os::raw::{c_char, c_ulong, c_void};
+use std::path::{Path, PathBuf};
 
 #[derive(Debug)]
 pub struct EseAPI {
     instance: JET_INSTANCE,
     sesid: JET_SESID,
     dbid: JET_DBID,
+    page_size: u32,
+    dbpath: PathBuf,
 }
 
 impl EseAPI {
-    fn get_column_info(&self, table: &str, column: &str) -> Result<JET_COLUMNBASE_A, SimpleError> {
+    fn get_column_info(&self, table: &str, column: &str) -> Result<JET_COLUMNBASE_A, EseError> {
         let tbl = CString::new(table).unwrap();
         let col = CString::new(column).unwrap();
         let mut col_base = MaybeUninit::<JET_COLUMNBASE_A>::zeroed();
@@ -32,7 +34,7 @@ impl EseAPI {
                 JET_ColInfoBase,
             );
             if err != 0 {
-                return Err(SimpleError::new(format!(
+                return Err(EseError::new(format!(
                     "JetOpenDatabaseA failed with error {}",
                     self.error_to_string(err)
                 )));
@@ -41,7 +43,7 @@ impl EseAPI {
         }
     }
 
-    fn get_database_file_info(dbpath: &str) -> Result<JET_DBINFOMISC4, SimpleError> {
+    fn get_database_file_info(dbpath: &str) -> Result<JET_DBINFOMISC4, EseError> {
         let filename = CString::new(dbpath).unwrap();
         let db_info = MaybeUninit::<JET_DBINFOMISC4>::zeroed();
         let res_size = size_of::<JET_DBINFOMISC4>() as c_ulong;
@@ -53,7 +55,7 @@ impl EseAPI {
             if JET_errSuccess == (err as u32) {
                 Ok(*db_info.as_ptr())
             } else {
-                Err(SimpleError::new(format!(
+                Err(EseError::new(format!(
                     "JetGetDatabaseFileInfoA failed with error {}",
                     err
                 )))
@@ -89,7 +91,7 @@ impl EseAPI {
         column: u32,
         data: &mut [u8],
         size: usize,
-    ) -> Result<u32, SimpleError> {
+    ) -> Result<u32, EseError> {
         let mut bytes: c_ulong = 0;
         unsafe {
             let err = JetRetrieveColumn(
@@ -106,7 +108,7 @@ impl EseAPI {
                 if err == JET_wrnColumnNull as i32 {
                     return Ok(0);
                 }
-                return Err(SimpleError::new(format!(
+                return Err(EseError::new(format!(
                     "JetRetrieveColumn failed with error {}",
                     self.error_to_string(err)
                 )));
@@ -116,7 +118,7 @@ impl EseAPI {
         }
     }
 
-    pub fn get_fixed_column<T>(&self, table: u64, column: u32) -> Result<Option<T>, SimpleError> {
+    pub fn get_fixed_column<T>(&self, table: u64, column: u32) -> Result<Option<T>, EseError> {
         let size: c_ulong = size_of::<T>() as u32;
         let mut v = MaybeUninit::<T>::zeroed();
 
@@ -131,9 +133,9 @@ impl EseAPI {
         }
     }
 
-    pub fn load_from_path(filename: impl AsRef<Path>) -> Result<Self, SimpleError> {
+    pub fn load_from_path(filename: impl AsRef<Path>) -> Result<Self, EseError> {
         match filename.as_ref().to_str() {
-            None => Err(SimpleError::new(format!(
+            None => Err(EseError::new(format!(
                 "Unable to convert {:?}",
                 filename.as_ref()
             ))),
@@ -147,7 +149,7 @@ impl EseAPI {
                 unsafe {
                     let err = JetCreateInstanceA(&mut instance, std::ptr::null());
                     if err != 0 {
-                        return Err(SimpleError::new(format!(
+                        return Err(EseError::new(format!(
                             "JetCreateInstanceA failed with error: {}",
                             err
                         )));
@@ -157,10 +159,7 @@ impl EseAPI {
                     let err = JetInit(&mut instance);
                     if err != 0 {
                         JetTerm(instance);
-                        return Err(SimpleError::new(format!(
-                            "JetInit failed with error {}",
-                            err
-                        )));
+                        return Err(EseError::new(format!("JetInit failed with error {}", err)));
                     }
                 }
 
@@ -170,20 +169,21 @@ impl EseAPI {
                         JetBeginSessionA(instance, &mut sesid, std::ptr::null(), std::ptr::null());
                     if err != 0 {
                         JetTerm(instance);
-                        return Err(SimpleError::new(format!(
+                        return Err(EseError::new(format!(
                             "JetBeginSessionA failed with error {}",
                             err
                         )));
                     }
                 }
 
+                let path = PathBuf::from(dbpath);
                 let dbpath = CString::new(dbpath).unwrap();
                 unsafe {
                     let err = JetAttachDatabaseA(sesid, dbpath.as_ptr(), JET_bitDbReadOnly);
                     if err != 0 {
                         JetEndSession(sesid, 0);
                         JetTerm(instance);
-                        return Err(SimpleError::new(format!(
+                        return Err(EseError::new(format!(
                             "JetAttachDatabaseA failed with error {}",
                             err
                         )));
@@ -203,7 +203,7 @@ impl EseAPI {
                         JetDetachDatabaseA(sesid, std::ptr::null());
                         JetEndSession(sesid, 0);
                         JetTerm(instance);
-                        return Err(SimpleError::new(format!(
+                        return Err(EseError::new(format!(
                             "JetOpenDatabaseA failed with error {}",
                             err
                         )));
@@ -214,6 +214,8 @@ impl EseAPI {
                     instance,
                     sesid,
                     dbid,
+                    page_size: dbinfo.cbPageSize as u32,
+                    dbpath: path,
                 })
             }
         }
@@ -241,7 +243,7 @@ impl EseDb for EseAPI {
         }
     }
 
-    fn open_table(&self, table: &str) -> Result<u64, SimpleError> {
+    fn open_table(&self, table: &str) -> Result<u64, EseError> {
         let tbl = CString::new(table).unwrap();
         let mut tableid: JET_TABLEID = 0;
         unsafe {
@@ -255,7 +257,7 @@ impl EseDb for EseAPI {
                 &mut tableid,
             );
             if err != 0 {
-                return Err(SimpleError::new(format!(
+                return Err(EseError::new(format!(
                     "JetOpenTableA failed with error {}",
                     self.error_to_string(err)
                 )));
@@ -271,7 +273,7 @@ impl EseDb for EseAPI {
         }
     }
 
-    fn get_column(&self, table: u64, column: u32) -> Result<Option<Vec<u8>>, SimpleError> {
+    fn get_column(&self, table: u64, column: u32) -> Result<Option<Vec<u8>>, EseError> {
         let mut vres: Vec<u8> = Vec::new();
 
         loop {
@@ -301,7 +303,7 @@ impl EseDb for EseAPI {
                     if err == JET_wrnColumnNull as i32 {
                         return Ok(None);
                     }
-                    return Err(SimpleError::new(format!(
+                    return Err(EseError::new(format!(
                         "JetRetrieveColumn failed with error {}",
                         self.error_to_string(err)
                     )));
@@ -325,7 +327,7 @@ impl EseDb for EseAPI {
         table: u64,
         column: u32,
         multi_value_index: u32,
-    ) -> Result<Option<Vec<u8>>, SimpleError> {
+    ) -> Result<Option<Vec<u8>>, EseError> {
         let mut vres: Vec<u8> = Vec::new();
 
         loop {
@@ -355,7 +357,7 @@ impl EseDb for EseAPI {
                     if err == JET_wrnColumnNull as i32 {
                         return Ok(None);
                     }
-                    return Err(SimpleError::new(format!(
+                    return Err(EseError::new(format!(
                         "JetRetrieveColumn failed with error {}",
                         self.error_to_string(err)
                     )));
@@ -374,14 +376,24 @@ impl EseDb for EseAPI {
         Ok(Some(vres))
     }
 
-    fn move_row(&self, table: u64, crow: i32) -> Result<bool, SimpleError> {
+    fn move_row(&self, table: u64, crow: i32) -> Result<bool, EseError> {
         unsafe {
             let err = JetMove(self.sesid, table, crow as std::os::raw::c_long, 0);
             Ok(err == 0)
         }
     }
 
-    fn get_tables(&self) -> Result<Vec<String>, SimpleError> {
+    fn get_page_size(&self) -> u32 {
+        self.page_size
+    }
+
+    fn get_database_file_size(&self) -> Result<u64, EseError> {
+        std::fs::metadata(&self.dbpath)
+            .map(|m| m.len())
+            .map_err(|e| EseError::new(format!("metadata({:?}) failed: {}", self.dbpath, e)))
+    }
+
+    fn get_tables(&self) -> Result<Vec<String>, EseError> {
         let c_name_info = self.get_column_info("MSysObjects", "Name")?;
         let c_type_info = self.get_column_info("MSysObjects", "Type")?;
 
@@ -409,7 +421,7 @@ impl EseDb for EseAPI {
         Ok(err)
     }
 
-    fn get_columns(&self, table: &str) -> Result<Vec<ColumnInfo>, SimpleError> {
+    fn get_columns(&self, table: &str) -> Result<Vec<ColumnInfo>, EseError> {
         let table_id = self.open_table(table)?;
         let mut cols: Vec<ColumnInfo> = Vec::new();
         let mut col_list = MaybeUninit::<JET_COLUMNLIST>::zeroed();
@@ -423,7 +435,7 @@ impl EseDb for EseAPI {
                 JET_ColInfoList,
             );
             if err != 0 {
-                return Err(SimpleError::new(format!(
+                return Err(EseError::new(format!(
                     "JetGetTableColumnInfoA failed with error {}",
                     self.error_to_string(err)
                 )));
@@ -465,6 +477,27 @@ impl EseDb for EseAPI {
         cols.sort_by(|a, b| a.id.cmp(&b.id));
         Ok(cols)
     }
+
+    fn get_column_type(&self, table: u64, column: u32) -> Result<u32, EseError> {
+        let mut col_def = MaybeUninit::<JET_COLUMNDEF>::zeroed();
+        unsafe {
+            let err = JetGetTableColumnInfoA(
+                self.sesid,
+                table,
+                &column as *const u32 as *const c_char,
+                col_def.as_mut_ptr() as *mut c_void,
+                size_of::<JET_COLUMNDEF>() as c_ulong,
+                JET_ColInfoByColid,
+            );
+            if err != 0 {
+                return Err(EseError::new(format!(
+                    "JetGetTableColumnInfoA failed with error {}",
+                    self.error_to_string(err)
+                )));
+            }
+            Ok(col_def.assume_init().coltyp)
+        }
+    }
 }
 
 impl Drop for EseAPI {
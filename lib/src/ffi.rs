@@ -0,0 +1,208 @@
+//ffi.rs
+//! C interface of the parser, declared in `include/ese_parser.h`.
+//! Every function checks its pointer parameters and returns one of the `ESE_PARSER_*` codes.
+//! A panic doesn't unwind into the caller, it is returned as `ESE_PARSER_PANIC`.
+#![allow(clippy::missing_safety_doc)]
+
+use crate::ese_parser::EseParser;
+use crate::ese_trait::EseDb;
+use crate::parser::backend::FileBackend;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+pub const ESE_PARSER_OK: c_int = 0;
+pub const ESE_PARSER_NULL_POINTER: c_int = -1;
+pub const ESE_PARSER_INVALID_STRING: c_int = -2;
+pub const ESE_PARSER_NOT_LOADED: c_int = -3;
+pub const ESE_PARSER_ERROR: c_int = -4;
+pub const ESE_PARSER_BUFFER_TOO_SMALL: c_int = -5;
+pub const ESE_PARSER_PANIC: c_int = -6;
+// returned by ese_parser_get_column for a null value
+pub const ESE_PARSER_NULL_VALUE: c_int = 1;
+// returned by ese_parser_open_table on errors
+pub const ESE_PARSER_INVALID_TABLE: u64 = u64::MAX;
+
+/// Parser handle of the C interface, the database is loaded by `ese_parser_load`.
+pub struct EseParserHandle {
+    cache_size: usize,
+    parser: Option<EseParser<FileBackend>>,
+}
+
+unsafe fn parser<'a>(p: *mut EseParserHandle) -> Result<&'a EseParser<FileBackend>, c_int> {
+    match p.as_ref() {
+        Some(handle) => handle.parser.as_ref().ok_or(ESE_PARSER_NOT_LOADED),
+        None => Err(ESE_PARSER_NULL_POINTER),
+    }
+}
+
+unsafe fn to_str<'a>(s: *const c_char) -> Result<&'a str, c_int> {
+    if s.is_null() {
+        return Err(ESE_PARSER_NULL_POINTER);
+    }
+    CStr::from_ptr(s)
+        .to_str()
+        .map_err(|_| ESE_PARSER_INVALID_STRING)
+}
+
+// runs the body of an extern "C" function, `on_panic` is returned instead of unwinding into C
+pub(crate) fn guard<T>(on_panic: T, f: impl FnOnce() -> T) -> T {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|_| {
+        log::error!("panic in the C interface");
+        on_panic
+    })
+}
+
+fn result_code(f: impl FnOnce() -> Result<c_int, c_int>) -> c_int {
+    guard(ESE_PARSER_PANIC, || f().unwrap_or_else(|code| code))
+}
+
+/// Null on a panic.
+#[no_mangle]
+pub extern "C" fn ese_parser_create(cache_size: u32) -> *mut EseParserHandle {
+    guard(ptr::null_mut(), || {
+        Box::into_raw(Box::new(EseParserHandle {
+            cache_size: cache_size as usize,
+            parser: None,
+        }))
+    })
+}
+
+/// Loads the database file `path` (UTF-8), replacing the database loaded before.
+#[no_mangle]
+pub unsafe extern "C" fn ese_parser_load(p: *mut EseParserHandle, path: *const c_char) -> c_int {
+    result_code(|| {
+        let handle = p.as_mut().ok_or(ESE_PARSER_NULL_POINTER)?;
+        let path = to_str(path)?;
+        let parser = EseParser::load_from_path(handle.cache_size, path).map_err(|e| {
+            log::error!("ese_parser_load({}): {}", path, e);
+            ESE_PARSER_ERROR
+        })?;
+        handle.parser = Some(parser);
+        Ok(ESE_PARSER_OK)
+    })
+}
+
+/// Stores in `*names` a NULL-terminated array of the table names and their number in `*count`.
+/// The array is freed by `ese_parser_free_tables`.
+#[no_mangle]
+pub unsafe extern "C" fn ese_parser_get_tables(
+    p: *mut EseParserHandle,
+    names: *mut *mut *mut c_char,
+    count: *mut u32,
+) -> c_int {
+    result_code(|| {
+        let parser = parser(p)?;
+        if names.is_null() || count.is_null() {
+            return Err(ESE_PARSER_NULL_POINTER);
+        }
+        let tables = parser.get_tables().map_err(|_| ESE_PARSER_ERROR)?;
+        let mut strings = Vec::with_capacity(tables.len());
+        for table in tables {
+            strings.push(CString::new(table).map_err(|_| ESE_PARSER_INVALID_STRING)?);
+        }
+        *count = strings.len() as u32;
+        let array: Box<[*mut c_char]> = strings
+            .into_iter()
+            .map(CString::into_raw)
+            .chain(Some(ptr::null_mut()))
+            .collect();
+        *names = Box::into_raw(array) as *mut *mut c_char;
+        Ok(ESE_PARSER_OK)
+    })
+}
+
+/// Frees the array of names returned by `ese_parser_get_tables`.
+#[no_mangle]
+pub unsafe extern "C" fn ese_parser_free_tables(names: *mut *mut c_char) {
+    guard((), || {
+        if names.is_null() {
+            return;
+        }
+        let mut len = 0;
+        while !(*names.add(len)).is_null() {
+            drop(CString::from_raw(*names.add(len)));
+            len += 1;
+        }
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(names, len + 1)));
+    })
+}
+
+/// Identifier of the opened table, `ESE_PARSER_INVALID_TABLE` on errors and panics.
+#[no_mangle]
+pub unsafe extern "C" fn ese_parser_open_table(
+    p: *mut EseParserHandle,
+    name: *const c_char,
+) -> u64 {
+    guard(ESE_PARSER_INVALID_TABLE, || {
+        let open = || -> Result<u64, c_int> {
+            let parser = parser(p)?;
+            parser
+                .open_table(to_str(name)?)
+                .map_err(|_| ESE_PARSER_ERROR)
+        };
+        open().unwrap_or(ESE_PARSER_INVALID_TABLE)
+    })
+}
+
+/// Moves the cursor of the table by `direction` (`ESE_MoveFirst`, `ESE_MoveNext`...).
+/// Returns 1 on a row, 0 past the first or last row.
+#[no_mangle]
+pub unsafe extern "C" fn ese_parser_move_row(
+    p: *mut EseParserHandle,
+    table_id: u64,
+    direction: u32,
+) -> c_int {
+    result_code(|| {
+        let parser = parser(p)?;
+        parser
+            .move_row(table_id, direction as i32)
+            .map(c_int::from)
+            .map_err(|_| ESE_PARSER_ERROR)
+    })
+}
+
+/// Copies the value of the column in the current row into `out_buf` of `*out_len` bytes,
+/// `*out_len` is set to the size of the value. A too small buffer (or a null `out_buf`) returns
+/// `ESE_PARSER_BUFFER_TOO_SMALL` with the size needed in `*out_len`,
+/// a null value `ESE_PARSER_NULL_VALUE`.
+#[no_mangle]
+pub unsafe extern "C" fn ese_parser_get_column(
+    p: *mut EseParserHandle,
+    table_id: u64,
+    col_id: u32,
+    out_buf: *mut u8,
+    out_len: *mut u32,
+) -> c_int {
+    result_code(|| {
+        let parser = parser(p)?;
+        if out_len.is_null() {
+            return Err(ESE_PARSER_NULL_POINTER);
+        }
+        let value = match parser.get_column(table_id, col_id) {
+            Ok(Some(value)) => value,
+            Ok(None) => {
+                *out_len = 0;
+                return Ok(ESE_PARSER_NULL_VALUE);
+            }
+            Err(_) => return Err(ESE_PARSER_ERROR),
+        };
+        let buf_len = *out_len as usize;
+        *out_len = value.len() as u32;
+        if out_buf.is_null() || buf_len < value.len() {
+            return Err(ESE_PARSER_BUFFER_TOO_SMALL);
+        }
+        ptr::copy_nonoverlapping(value.as_ptr(), out_buf, value.len());
+        Ok(ESE_PARSER_OK)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ese_parser_destroy(p: *mut EseParserHandle) {
+    guard((), || {
+        if !p.is_null() {
+            drop(Box::from_raw(p));
+        }
+    })
+}
@@ -0,0 +1,97 @@
+//json.rs
+use crate::csv::BinaryEncoding;
+use crate::error::EseError;
+use crate::ese_trait::*;
+use crate::utils::guid_to_string;
+use crate::value::Value;
+use serde_json::Map;
+
+fn to_json_value(value: Value, binary_encoding: BinaryEncoding) -> serde_json::Value {
+    match value {
+        Value::Null => serde_json::Value::Null,
+        Value::Bool(v) => v.into(),
+        Value::U8(v) => v.into(),
+        Value::I16(v) => v.into(),
+        Value::U16(v) => v.into(),
+        Value::I32(v) => v.into(),
+        Value::U32(v) => v.into(),
+        Value::I64(v) => v.into(),
+        Value::U64(v) => v.into(),
+        Value::F32(v) => v.into(),
+        Value::F64(v) => v.into(),
+        Value::DateTime(v) => v.to_rfc3339().into(),
+        Value::Guid(v) => guid_to_string(&v.0).into(),
+        // the raw value, scaled by 10000
+        Value::Currency(v) => v.into(),
+        Value::Text(v) => v.into(),
+        Value::Binary(v) => binary_encoding.encode(v).into(),
+        Value::MultiValue(v) => v
+            .into_iter()
+            .map(|value| to_json_value(value, binary_encoding))
+            .collect(),
+    }
+}
+
+/// Reads the current row of `table_id` into a JSON object.
+/// With `ignore_errors` the columns which can't be read are set to null.
+pub(crate) fn row_to_json<D: EseDb + ?Sized>(
+    db: &D,
+    table_id: u64,
+    columns: &[ColumnInfo],
+    ignore_errors: bool,
+    binary_encoding: BinaryEncoding,
+) -> Result<Map<String, serde_json::Value>, EseError> {
+    let mut row = Map::new();
+    for c in columns {
+        let value = match db.get_column(table_id, c.id) {
+            Ok(Some(v)) => Value::from_column(c, &v, false),
+            Ok(None) => Ok(Value::Null),
+            Err(e) => Err(e),
+        };
+        let value = match value {
+            Ok(value) => to_json_value(value, binary_encoding),
+            Err(_) if ignore_errors => serde_json::Value::Null,
+            Err(e) => return Err(e),
+        };
+        row.insert(c.name.clone(), value);
+    }
+    Ok(row)
+}
+
+/// Writes `table` as a JSON array with an object per row, the column names are the keys.
+/// Null values are `null`, binary columns are base64 strings, dates are RFC 3339 strings.
+/// Rows are written as they are read, returns the number of rows.
+/// The table is closed on errors too.
+pub(crate) fn export_table<D: EseDb + ?Sized>(
+    db: &D,
+    table: &str,
+    out: &mut dyn std::io::Write,
+) -> Result<u64, EseError> {
+    let columns = db.get_columns(table)?;
+    let table_id = db.open_table(table)?;
+    let rows = write_rows(db, table_id, &columns, out);
+    db.close_table(table_id);
+    rows
+}
+
+fn write_rows<D: EseDb + ?Sized>(
+    db: &D,
+    table_id: u64,
+    columns: &[ColumnInfo],
+    out: &mut dyn std::io::Write,
+) -> Result<u64, EseError> {
+    out.write_all(b"[")?;
+    let mut rows = 0;
+    let mut has_row = db.move_row(table_id, ESE_MoveFirst)?;
+    while has_row {
+        let row = row_to_json(db, table_id, columns, false, BinaryEncoding::Base64)?;
+        if rows > 0 {
+            out.write_all(b",")?;
+        }
+        serde_json::to_writer(&mut *out, &row).map_err(std::io::Error::from)?;
+        rows += 1;
+        has_row = db.move_row(table_id, ESE_MoveNext)?;
+    }
+    out.write_all(b"]")?;
+    Ok(rows)
+}
@@ -11,20 +11,32 @@ pub mod parser;
 #[cfg(all(feature = "nt_comparison", target_os = "windows"))]
 pub mod esent;
 
+pub mod csv;
+pub mod error;
 pub mod ese_parser;
 pub mod ese_trait;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "parquet")]
+pub mod parquet;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
 pub mod utils;
+pub mod value;
 pub mod vartime;
 
 #[cfg(test)]
 mod tests {
     use super::ese_trait::*;
     use super::*;
+    use parser::backend::{CursorBackend, FileBackend, MemoryBackend, ReaderBackend};
     use std::collections::HashMap;
     use std::fs::File;
     use std::io::BufReader;
 
-    fn init_tests(cache_size: usize, db: Option<&str>) -> ese_parser::EseParser<BufReader<File>> {
+    fn init_tests(cache_size: usize, db: Option<&str>) -> ese_parser::EseParser<FileBackend> {
         let path = &["testdata", db.unwrap_or("test.edb")].join("/");
         ese_parser::EseParser::load_from_path(cache_size, path).unwrap();
         match ese_parser::EseParser::load_from_path(cache_size, path) {
@@ -33,7 +45,7 @@ mod tests {
         }
     }
 
-    fn check_table_names(expected_tables: Vec<&str>, jdb: ese_parser::EseParser<BufReader<File>>) {
+    fn check_table_names(expected_tables: Vec<&str>, jdb: ese_parser::EseParser<FileBackend>) {
         let tables = jdb.get_tables().unwrap();
         assert_eq!(tables.len(), expected_tables.len());
         for i in 0..tables.len() {
@@ -200,6 +212,191 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_date_from_bytes() {
+        let date = |v: &[u8]| {
+            date_from_bytes(v)
+                .unwrap()
+                .unwrap()
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        };
+        // OLE automation dates, whatever their magnitude
+        assert_eq!(date(&0.0f64.to_le_bytes()), "1899-12-30 00:00:00");
+        assert_eq!(date(&1.0f64.to_le_bytes()), "1899-12-31 00:00:00");
+        assert_eq!(date(&(-1.25f64).to_le_bytes()), "1899-12-29 06:00:00");
+        assert_eq!(date(&44284.5f64.to_le_bytes()), "2021-03-29 12:00:00");
+        // 0u64 is 0.0, FILETIMEs read as doubles are close to 0
+        assert_eq!(date(&0u64.to_le_bytes()), "1899-12-30 00:00:00");
+        assert_eq!(
+            date(&116444736000000000u64.to_le_bytes()),
+            "1970-01-01 00:00:00"
+        );
+        assert!(date_from_bytes(&f64::NAN.to_le_bytes()).is_err());
+        assert!(date_from_bytes(&1e10f64.to_le_bytes()).is_err());
+        // FILETIMEs
+        assert_eq!(
+            filetime_from_bytes(&0u64.to_le_bytes())
+                .unwrap()
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string(),
+            "1601-01-01 00:00:00"
+        );
+        assert_eq!(
+            filetime_from_bytes(&116444736000000000u64.to_le_bytes())
+                .unwrap()
+                .timestamp(),
+            0
+        );
+        assert!(date_from_bytes(&[0; 4]).is_err());
+        assert!(filetime_from_bytes(&[0; 9]).is_err());
+
+        // DateTime columns are OLE automation dates, 8 bytes integer columns FILETIMEs
+        let jdb = init_tests(5, None);
+        let columns = jdb.get_columns("TestTable").unwrap();
+        let id = |name: &str| columns.iter().find(|x| x.name == name).unwrap().id;
+        let table_id = jdb.open_table("TestTable").unwrap();
+        assert!(jdb.move_row(table_id, ESE_MoveFirst).unwrap());
+        assert!(jdb.get_column_date(table_id, id("Text")).is_err());
+        assert!(jdb.get_column_date(table_id, id("Currency")).is_err());
+        assert_eq!(
+            jdb.get_column_date(table_id, id("DateTime"))
+                .unwrap()
+                .unwrap()
+                .to_string(),
+            "2021-03-29 11:49:47 UTC"
+        );
+        let raw = jdb.get_column(table_id, id("LongLong")).unwrap().unwrap();
+        assert_eq!(
+            jdb.get_column_date(table_id, id("LongLong")).unwrap(),
+            Some(filetime_from_bytes(&raw).unwrap())
+        );
+        jdb.close_table(table_id);
+    }
+
+    #[test]
+    fn test_get_column_guid() {
+        let jdb = init_tests(5, None);
+        let columns = jdb.get_columns("TestTable").unwrap();
+        let id = |name: &str| columns.iter().find(|x| x.name == name).unwrap().id;
+        let table_id = jdb.open_table("TestTable").unwrap();
+        assert!(jdb.move_row(table_id, ESE_MoveFirst).unwrap());
+        // {4D36E96E-E325-11CE-BFC1-08002BE10318}
+        assert_eq!(
+            jdb.get_column_guid(table_id, id("GUID")).unwrap(),
+            Some([
+                0x4d, 0x36, 0xe9, 0x6e, 0xe3, 0x25, 0x11, 0xce, 0xbf, 0xc1, 0x08, 0x00, 0x2b, 0xe1,
+                0x03, 0x18
+            ])
+        );
+        #[cfg(feature = "uuid")]
+        assert_eq!(
+            jdb.get_column_uuid(table_id, id("GUID"))
+                .unwrap()
+                .unwrap()
+                .to_string(),
+            "4d36e96e-e325-11ce-bfc1-08002be10318"
+        );
+        assert!(jdb.get_column_guid(table_id, id("Binary")).is_err());
+        jdb.close_table(table_id);
+    }
+
+    #[test]
+    fn test_get_column_datetime() {
+        let jdb = init_tests(5, None);
+        let columns = jdb.get_columns("TestTable").unwrap();
+        let id = |name: &str| columns.iter().find(|x| x.name == name).unwrap().id;
+        let table_id = jdb.open_table("TestTable").unwrap();
+        assert!(jdb.move_row(table_id, ESE_MoveFirst).unwrap());
+        let date_time = jdb
+            .get_column_datetime(table_id, id("DateTime"))
+            .unwrap()
+            .unwrap();
+        let raw = jdb.get_column(table_id, id("DateTime")).unwrap().unwrap();
+        assert_eq!(
+            vartime::get_ole_from_date_time(&date_time),
+            f64::from_bytes(&raw)
+        );
+        // both getters share the OLE automation date conversion
+        assert_eq!(
+            Some(date_time),
+            jdb.get_column_date(table_id, id("DateTime")).unwrap()
+        );
+        let fraction: f64 = 44284.49290509259 + 0.123 / 86400.0;
+        assert_eq!(
+            ese_trait::date_from_bytes(&fraction.to_le_bytes()).unwrap(),
+            vartime::get_date_time_from_ole(fraction)
+        );
+        assert!(jdb.get_column_datetime(table_id, id("LongLong")).is_err());
+        assert!(jdb.get_column_datetime(table_id, id("IEEEDouble")).is_err());
+        jdb.close_table(table_id);
+
+        // FILETIMEs in DateTime columns
+        let jdb = init_tests(5, Some("Current.mdb"));
+        let columns = jdb.get_columns("CLIENTS").unwrap();
+        let insert_date = columns.iter().find(|x| x.name == "InsertDate").unwrap();
+        let table_id = jdb.open_table("CLIENTS").unwrap();
+        assert!(jdb.get_column_datetime(table_id, insert_date.id).is_err());
+        assert!(jdb
+            .get_column_date(table_id, insert_date.id)
+            .unwrap()
+            .is_some());
+        jdb.close_table(table_id);
+    }
+
+    #[test]
+    fn test_typed_columns() {
+        let jdb = init_tests(5, None);
+        let columns = jdb.get_columns("TestTable").unwrap();
+        let id = |name: &str| columns.iter().find(|x| x.name == name).unwrap().id;
+        let table_id = jdb.open_table("TestTable").unwrap();
+        assert!(jdb.move_row(table_id, ESE_MoveFirst).unwrap());
+
+        assert_eq!(
+            jdb.get_column_bool(table_id, id("Bit")).unwrap(),
+            Some(false)
+        );
+        assert_eq!(
+            jdb.get_column_u8(table_id, id("UnsignedByte")).unwrap(),
+            Some(255)
+        );
+        assert_eq!(jdb.get_column_i16(table_id, id("Short")).unwrap(), None);
+        assert_eq!(
+            jdb.get_column_u16(table_id, id("UnsignedShort")).unwrap(),
+            Some(65535)
+        );
+        assert_eq!(
+            jdb.get_column_i32(table_id, id("Long")).unwrap(),
+            Some(-2147483648)
+        );
+        assert_eq!(
+            jdb.get_column_u32(table_id, id("UnsignedLong")).unwrap(),
+            Some(4294967295)
+        );
+        assert_eq!(
+            jdb.get_column_i64(table_id, id("LongLong")).unwrap(),
+            Some(9223372036854775807)
+        );
+        assert_eq!(
+            jdb.get_column_f32(table_id, id("IEEESingle")).unwrap(),
+            Some(3.141592)
+        );
+        assert_eq!(
+            jdb.get_column_f64(table_id, id("IEEEDouble")).unwrap(),
+            Some(3.141592653589)
+        );
+
+        // declared type doesn't match
+        assert!(jdb.get_column_i32(table_id, id("UnsignedLong")).is_err());
+        assert!(jdb.get_column_i64(table_id, id("Currency")).is_err());
+        assert!(jdb.get_column_f64(table_id, id("DateTime")).is_err());
+        assert!(matches!(
+            jdb.get_column_i32(table_id, 12345),
+            Err(error::EseError::ColumnNotFound(12345))
+        ));
+        jdb.close_table(table_id);
+    }
+
     #[test]
     fn test_columns() {
         let jdb = init_tests(5, None);
@@ -378,6 +575,25 @@ mod tests {
                 let r = abc.as_bytes()[i % abc.len()] as char;
                 assert_eq!(l, r);
             }
+            let s = jdb
+                .get_column_str_utf16le(table_id, long_text.id, 1)
+                .unwrap()
+                .unwrap();
+            assert_eq!(s, ws);
+            assert_eq!(
+                jdb.get_column_str(table_id, long_text.id, long_text.cp)
+                    .unwrap()
+                    .unwrap(),
+                ws
+            );
+
+            // "Hello" and 2 bytes, not UTF-16
+            let text = columns.iter().find(|x| x.name == "Text").unwrap();
+            assert!(jdb.get_column_str_utf16le(table_id, text.id, 2).is_err());
+            assert_eq!(
+                jdb.get_column_str_utf16le(table_id, text.id, 3).unwrap(),
+                None
+            );
         }
 
         // Default value
@@ -401,4 +617,3094 @@ mod tests {
 
         jdb.close_table(table_id);
     }
+
+    #[test]
+    fn test_column_min_max() {
+        let jdb = init_tests(5, None);
+        let columns = jdb.get_columns("MSysObjids").unwrap();
+        let table_id = jdb.open_table("MSysObjids").unwrap();
+        let objid = columns.iter().find(|x| x.name == "objid").unwrap();
+        assert_eq!(
+            jdb.get_column_min_max_i64(table_id, objid.id).unwrap(),
+            (Some(2), Some(9))
+        );
+        let typ = columns.iter().find(|x| x.name == "type").unwrap();
+        assert_eq!(
+            jdb.get_column_min_max(table_id, typ.id).unwrap(),
+            (Some(vec![1, 0]), Some(vec![4, 0]))
+        );
+        jdb.close_table(table_id);
+
+        let jdb = init_tests(5, Some("Current.mdb"));
+        let columns = jdb.get_columns("VIRTUALMACHINES").unwrap();
+        let table_id = jdb.open_table("VIRTUALMACHINES").unwrap();
+        assert_eq!(
+            jdb.get_column_min_max(table_id, columns[0].id).unwrap(),
+            (None, None)
+        );
+    }
+
+    #[test]
+    fn test_record_at_absolute_position() {
+        let jdb = init_tests(5, Some("decompress_test.edb"));
+        let columns = jdb.get_columns("test_table").unwrap();
+        let table_id = jdb.open_table("test_table").unwrap();
+        let ascii = columns
+            .iter()
+            .find(|x| x.name == "compressed_ascii")
+            .unwrap();
+        for &position in &[3u64, 7, 2, 9, 0] {
+            assert!(jdb
+                .get_record_at_absolute_position(table_id, position)
+                .unwrap());
+            let v = jdb.get_column(table_id, ascii.id).unwrap().unwrap();
+            assert_eq!(*v.last().unwrap(), b'0' + position as u8);
+        }
+        assert!(!jdb.get_record_at_absolute_position(table_id, 10).unwrap());
+    }
+
+    #[test]
+    fn test_move_row_to_index() {
+        let jdb = init_tests(5, Some("Current.mdb"));
+        let columns = jdb.get_columns("MSysObjects").unwrap();
+        let name = columns.iter().find(|x| x.name == "Name").unwrap().id;
+        let table_id = jdb.open_table("MSysObjects").unwrap();
+        let mut names = vec![];
+        let mut has_row = jdb.move_row(table_id, ESE_MoveFirst).unwrap();
+        while has_row {
+            names.push(jdb.get_column(table_id, name).unwrap());
+            has_row = jdb.move_row(table_id, ESE_MoveNext).unwrap();
+        }
+        assert_eq!(names.len(), 467);
+
+        for &index in &[300u64, 0, 466, 120, 121] {
+            assert!(jdb.move_row_to_index(table_id, index).unwrap());
+            assert_eq!(
+                jdb.get_column(table_id, name).unwrap(),
+                names[index as usize]
+            );
+        }
+        // the cursor moves on from the index
+        assert!(jdb.move_row_to_index(table_id, 200).unwrap());
+        assert!(jdb.move_row(table_id, ESE_MoveNext).unwrap());
+        assert_eq!(jdb.get_column(table_id, name).unwrap(), names[201]);
+        assert!(jdb.move_row(table_id, ESE_MovePrevious).unwrap());
+        assert!(jdb.move_row(table_id, ESE_MovePrevious).unwrap());
+        assert_eq!(jdb.get_column(table_id, name).unwrap(), names[199]);
+        assert!(!jdb.move_row_to_index(table_id, 467).unwrap());
+    }
+
+    // sets the flags of page tags, the offset word of tag i is at the end of the page
+    fn set_page_tag_flags(
+        data: &mut [u8],
+        page_size: usize,
+        locations: &[(u32, usize)],
+        flags: u8,
+    ) {
+        for &(page_number, tag_index) in locations {
+            let page_end = (page_number as usize + 2) * page_size;
+            data[page_end - 1 - tag_index * 4] |= flags;
+        }
+    }
+
+    #[test]
+    fn test_include_deleted_rows() {
+        let mut data = std::fs::read("testdata/decompress_test.edb").unwrap();
+        let load = |data: &Vec<u8>, include_deleted: bool| {
+            let options = ese_parser::ParserOptions {
+                include_deleted,
+                ..Default::default()
+            };
+            let cursor = std::io::Cursor::new(data.clone());
+            ese_parser::EseParser::load_with_options(5, cursor, options).unwrap()
+        };
+        let read_rows = |jdb: &ese_parser::EseParser<MemoryBackend<Vec<u8>>>| {
+            let columns = jdb.get_columns("test_table").unwrap();
+            let ascii = columns
+                .iter()
+                .find(|x| x.name == "compressed_ascii")
+                .unwrap();
+            let table_id = jdb.open_table("test_table").unwrap();
+            let mut rows = vec![];
+            let mut has_row = jdb.move_row(table_id, ESE_MoveFirst).unwrap();
+            while has_row {
+                let v = jdb.get_column(table_id, ascii.id).unwrap().unwrap();
+                rows.push((
+                    *v.last().unwrap() - b'0',
+                    jdb.is_current_row_deleted(table_id).unwrap(),
+                    jdb.get_record_page_location(table_id).unwrap(),
+                ));
+                has_row = jdb.move_row(table_id, ESE_MoveNext).unwrap();
+            }
+            rows
+        };
+        let rows = read_rows(&load(&data, false));
+        assert_eq!(rows.len(), 10);
+        assert!(rows.iter().all(|&(_, deleted, _)| !deleted));
+
+        // FLAG_IS_DEFUNCT is the 0x4000 bit of the offset word
+        set_page_tag_flags(&mut data, 8192, &[rows[2].2, rows[5].2], 0x40);
+        let live: Vec<u8> = read_rows(&load(&data, false))
+            .iter()
+            .map(|&(n, _, _)| n)
+            .collect();
+        assert_eq!(live, vec![0, 1, 3, 4, 6, 7, 8, 9]);
+        assert_eq!(load(&data, false).get_row_count("test_table").unwrap(), 8);
+
+        let jdb = load(&data, true);
+        assert_eq!(jdb.get_row_count("test_table").unwrap(), 10);
+        assert_eq!(jdb.exact_row_count("test_table").unwrap(), 10);
+        let all: Vec<(u8, bool)> = read_rows(&jdb)
+            .iter()
+            .map(|&(n, deleted, _)| (n, deleted))
+            .collect();
+        assert_eq!(all.len(), 10);
+        for (n, deleted) in all {
+            assert_eq!(deleted, n == 2 || n == 5);
+        }
+        let deleted: Vec<String> = jdb
+            .iter_rows("test_table")
+            .unwrap()
+            .map(|r| r.unwrap())
+            .filter(|r| r.is_deleted())
+            .map(|r| r.get_str("compressed_ascii").unwrap().unwrap())
+            .collect();
+        assert_eq!(deleted.len(), 2);
+        assert!(deleted[0].ends_with('2') && deleted[1].ends_with('5'));
+        let table_id = jdb.open_table("test_table").unwrap();
+        assert!(jdb.move_row_to_index(table_id, 5).unwrap());
+        assert!(jdb.is_current_row_deleted(table_id).unwrap());
+    }
+
+    #[test]
+    fn test_truncated_record() {
+        let mut data = std::fs::read("testdata/test.edb").unwrap();
+        let jdb = ese_parser::EseParser::load(5, std::io::Cursor::new(data.clone())).unwrap();
+        let table_id = jdb.open_table("TestTable").unwrap();
+        assert!(jdb.move_row(table_id, ESE_MoveFirst).unwrap());
+        let (page_number, tag_index) = jdb.get_record_page_location(table_id).unwrap();
+
+        // the size word of the tag, before its offset word; 4 bytes can't hold the key and the record
+        let size_offset = (page_number as usize + 2) * 4096 - 4 - tag_index * 4;
+        data[size_offset] = 4;
+        data[size_offset + 1] &= 0xe0;
+        let jdb = ese_parser::EseParser::load(5, std::io::Cursor::new(data)).unwrap();
+        let table_id = jdb.open_table("TestTable").unwrap();
+        assert!(jdb.move_row(table_id, ESE_MoveFirst).unwrap());
+        for c in jdb.get_columns("TestTable").unwrap() {
+            assert!(jdb.get_column(table_id, c.id).is_err());
+        }
+    }
+
+    // reads every column of the rows of every table, errors are expected on corrupt data
+    fn read_all_tables(jdb: &ese_parser::EseParser<MemoryBackend<Vec<u8>>>) {
+        let tables = match jdb.get_tables() {
+            Ok(tables) => tables,
+            Err(_) => return,
+        };
+        for table in tables {
+            let (columns, table_id) = match (jdb.get_columns(&table), jdb.open_table(&table)) {
+                (Ok(columns), Ok(table_id)) => (columns, table_id),
+                _ => continue,
+            };
+            let mut has_row = jdb.move_row(table_id, ESE_MoveFirst).unwrap_or(false);
+            // a corrupt page chain can loop
+            for _ in 0..1000 {
+                if !has_row {
+                    break;
+                }
+                for c in &columns {
+                    let _ = jdb.get_column(table_id, c.id);
+                    let _ = jdb.get_column_mv(table_id, c.id, 2);
+                }
+                has_row = jdb.move_row(table_id, ESE_MoveNext).unwrap_or(false);
+            }
+            jdb.close_table(table_id);
+        }
+    }
+
+    #[test]
+    fn test_corrupt_pages_dont_panic() {
+        // xorshift, to get the same corruptions on every run
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as usize
+        };
+        for (db, page_size) in [("test.edb", 4096), ("decompress_test.edb", 8192)] {
+            let data = std::fs::read(["testdata", db].join("/")).unwrap();
+            // skip the file header pages, they are checksummed
+            let pages: Vec<usize> = (2..data.len() / page_size)
+                .filter(|&p| {
+                    data[p * page_size..(p + 1) * page_size]
+                        .iter()
+                        .any(|&b| b != 0)
+                })
+                .collect();
+            for i in 0..300 {
+                let mut corrupt = data.clone();
+                let page = pages[next() % pages.len()];
+                match i % 3 {
+                    0 => {
+                        // flip some bits of the page
+                        for _ in 0..8 {
+                            let offset = page * page_size + next() % page_size;
+                            corrupt[offset] ^= 1 << (next() % 8);
+                        }
+                    }
+                    1 => {
+                        // overwrite a page tag
+                        let offset = (page + 1) * page_size - 4 - 4 * (next() % 16);
+                        corrupt[offset..offset + 4].copy_from_slice(&(next() as u32).to_le_bytes());
+                    }
+                    _ => corrupt.truncate(page * page_size + next() % page_size),
+                }
+                if let Ok(jdb) = ese_parser::EseParser::load(5, std::io::Cursor::new(corrupt)) {
+                    read_all_tables(&jdb);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_column_reads_while_moving_back_and_forth() {
+        let mut state = 0x9e37_79b9_7f4a_7c15u64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as usize
+        };
+        for (db, table) in [
+            ("test.edb", "MSysObjects"),
+            ("decompress_test.edb", "test_table"),
+            ("Current.mdb", "MSysObjects"),
+        ] {
+            // every row re-parsed from scratch, the columns in order, by another parser
+            let reference = init_tests(5, Some(db));
+            let columns = reference.get_columns(table).unwrap();
+            let reference_id = reference.open_table(table).unwrap();
+            let mut rows = vec![];
+            let mut has_row = reference.move_row(reference_id, ESE_MoveFirst).unwrap();
+            while has_row {
+                reference
+                    .move_row_to_index(reference_id, rows.len() as u64)
+                    .unwrap();
+                let row: Vec<_> = columns
+                    .iter()
+                    .map(|c| reference.get_column(reference_id, c.id).unwrap())
+                    .collect();
+                rows.push(row);
+                has_row = reference.move_row(reference_id, ESE_MoveNext).unwrap();
+            }
+            assert!(rows.len() > 2, "{}", table);
+
+            let jdb = init_tests(5, Some(db));
+            let table_id = jdb.open_table(table).unwrap();
+            let mut position = 0;
+            jdb.move_row(table_id, ESE_MoveFirst).unwrap();
+            for _ in 0..2000 {
+                if next() % 2 == 0 && position + 1 < rows.len() {
+                    assert!(jdb.move_row(table_id, ESE_MoveNext).unwrap());
+                    position += 1;
+                } else if position > 0 {
+                    assert!(jdb.move_row(table_id, ESE_MovePrevious).unwrap());
+                    position -= 1;
+                }
+                for _ in 0..3 {
+                    let i = next() % columns.len();
+                    assert_eq!(
+                        jdb.get_column(table_id, columns[i].id).unwrap(),
+                        rows[position][i],
+                        "{} row {} column {}",
+                        table,
+                        position,
+                        columns[i].name
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_load_from_reader() {
+        // every table as CSV, or the error of the export
+        fn export_all<R: ReaderBackend>(jdb: &ese_parser::EseParser<R>) -> Vec<String> {
+            jdb.get_tables()
+                .unwrap()
+                .iter()
+                .map(|t| {
+                    let mut out = vec![];
+                    match jdb.export_table_csv(t, &mut out) {
+                        Ok(_) => String::from_utf8(out).unwrap(),
+                        Err(e) => e.to_string(),
+                    }
+                })
+                .collect()
+        }
+        for db in ["test.edb", "decompress_test.edb", "Current.mdb"] {
+            let path = ["testdata", db].join("/");
+            let data = std::fs::read(&path).unwrap();
+            let backend: Box<dyn ReaderBackend> =
+                Box::new(CursorBackend::new(std::io::Cursor::new(data)));
+            let jdb = ese_parser::EseParser::load_from_backend(5, backend).unwrap();
+            assert!(jdb.get_database_path().is_none());
+            let expected = export_all(&init_tests(5, Some(db)));
+            assert_eq!(export_all(&jdb), expected);
+
+            let file = FileBackend::open(&path).unwrap();
+            let jdb = ese_parser::EseParser::load_from_backend(5, file).unwrap();
+            assert_eq!(export_all(&jdb), expected);
+        }
+        let backend: Box<dyn ReaderBackend> =
+            Box::new(CursorBackend::new(std::io::Cursor::new(vec![0u8; 100])));
+        assert!(parser::reader::Reader::from_backend(backend, 5).is_err());
+        assert!(FileBackend::open("testdata/missing.edb").is_err());
+    }
+
+    #[test]
+    fn test_reader_backend() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        // counts the reads of a buffer
+        struct CountingBackend {
+            data: Vec<u8>,
+            reads: Arc<AtomicUsize>,
+        }
+        impl ReaderBackend for CountingBackend {
+            fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<(), error::EseError> {
+                self.reads.fetch_add(1, Ordering::Relaxed);
+                let data = self
+                    .data
+                    .get(offset as usize..offset as usize + buf.len())
+                    .ok_or_else(|| error::EseError::new("read past the end"))?;
+                buf.copy_from_slice(data);
+                Ok(())
+            }
+
+            fn size(&self) -> Result<u64, error::EseError> {
+                Ok(self.data.len() as u64)
+            }
+        }
+        let data = std::fs::read("testdata/test.edb").unwrap();
+        let size = data.len() as u64;
+        let reads = Arc::new(AtomicUsize::new(0));
+        let backend = CountingBackend {
+            data,
+            reads: reads.clone(),
+        };
+        let reader = parser::reader::Reader::from_backend(backend, 100).unwrap();
+        assert_eq!(reader.file_size().unwrap(), size);
+
+        let backend = CountingBackend {
+            data: std::fs::read("testdata/test.edb").unwrap(),
+            reads: reads.clone(),
+        };
+        let jdb = ese_parser::EseParser::load_from_backend(100, backend).unwrap();
+        let reads = || reads.load(Ordering::Relaxed);
+        assert!(reads() > 0);
+        assert_eq!(jdb.get_row_count("MSysObjects").unwrap(), 92);
+        // the pages are cached
+        let before = reads();
+        assert_eq!(jdb.get_row_count("MSysObjects").unwrap(), 92);
+        assert_eq!(reads(), before);
+    }
+
+    #[test]
+    fn test_read_ahead() {
+        use std::io::{Read, Seek, SeekFrom};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        // counts the reads of the file
+        struct CountingFile {
+            file: File,
+            reads: Arc<AtomicUsize>,
+        }
+        impl Read for CountingFile {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                self.reads.fetch_add(1, Ordering::Relaxed);
+                self.file.read(buf)
+            }
+        }
+        impl Seek for CountingFile {
+            fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+                self.file.seek(pos)
+            }
+        }
+        // reads of a full scan of MSysObjectsShadow (8 leaf pages), with the ObjidTable column of its rows
+        let scan = |read_ahead_pages: u32| {
+            let reads = Arc::new(AtomicUsize::new(0));
+            let file = CountingFile {
+                file: File::open("testdata/Current.mdb").unwrap(),
+                reads: reads.clone(),
+            };
+            let options = ese_parser::ParserOptions {
+                read_ahead_pages,
+                ..Default::default()
+            };
+            let jdb = ese_parser::EseParser::load_with_options(32, file, options).unwrap();
+            let table_id = jdb.open_table("MSysObjectsShadow").unwrap();
+            let before = reads.load(Ordering::Relaxed);
+            let mut rows = vec![];
+            let mut has_row = jdb.move_row(table_id, ESE_MoveFirst).unwrap();
+            while has_row {
+                rows.push(jdb.get_column(table_id, 1).unwrap());
+                has_row = jdb.move_row(table_id, ESE_MoveNext).unwrap();
+            }
+            (reads.load(Ordering::Relaxed) - before, rows)
+        };
+        assert_eq!(ese_parser::ParserOptions::default().read_ahead_pages, 16);
+        let (single_reads, rows) = scan(0);
+        let (read_ahead_reads, read_ahead_rows) = scan(16);
+        assert_eq!(rows, read_ahead_rows);
+        assert!(rows.len() > 100);
+        assert!(
+            read_ahead_reads < single_reads,
+            "{} reads with read-ahead, {} without",
+            read_ahead_reads,
+            single_reads
+        );
+    }
+
+    #[test]
+    fn test_table_top_n_rows() {
+        let jdb = init_tests(5, Some("decompress_test.edb"));
+        let rows = jdb.get_table_top_n_rows("test_table", 3).unwrap();
+        assert_eq!(rows.len(), 3);
+        for (i, row) in rows.iter().enumerate() {
+            assert_eq!(row.len(), 4);
+            let v = row["usual_text"].as_ref().unwrap();
+            assert_eq!(*v.last().unwrap(), b'0' + i as u8);
+        }
+        assert_eq!(
+            jdb.get_table_top_n_rows("test_table", 100).unwrap().len(),
+            10
+        );
+
+        let sample = jdb.get_table_sample("test_table", 4, 42).unwrap();
+        assert_eq!(sample.len(), 4);
+        assert_eq!(
+            jdb.get_table_sample("test_table", 4, 42).unwrap(),
+            sample,
+            "same seed should give the same sample"
+        );
+    }
+
+    #[test]
+    fn test_table_top_n_rows_keeps_cursor() {
+        let jdb = init_tests(5, Some("decompress_test.edb"));
+        let column = jdb.get_column_id("test_table", "usual_text").unwrap();
+        let table_id = jdb.open_table("test_table").unwrap();
+        assert!(jdb.move_row(table_id, 4).unwrap());
+        let before = jdb.get_column(table_id, column).unwrap();
+
+        jdb.get_table_top_n_rows("test_table", 3).unwrap();
+        jdb.get_table_sample("test_table", 4, 42).unwrap();
+
+        assert!(jdb.is_table_open("test_table"));
+        assert_eq!(jdb.get_column(table_id, column).unwrap(), before);
+        assert!(jdb.move_row(table_id, ESE_MoveNext).unwrap());
+        assert_ne!(jdb.get_column(table_id, column).unwrap(), before);
+        jdb.close_table(table_id);
+        assert!(!jdb.is_table_open("test_table"));
+    }
+
+    #[test]
+    fn test_get_column_value() {
+        use value::Value;
+        let jdb = init_tests(5, None);
+        let columns = jdb.get_columns("TestTable").unwrap();
+        let table_id = jdb.open_table("TestTable").unwrap();
+        let value = |name: &str| {
+            let c = columns.iter().find(|x| x.name == name).unwrap();
+            jdb.get_column_value(table_id, c.id).unwrap()
+        };
+        assert_eq!(value("AutoInc"), Value::I32(1));
+        assert_eq!(value("Bit"), Value::Bool(false));
+        assert_eq!(value("UnsignedByte"), Value::U8(255));
+        assert_eq!(value("Short"), Value::Null);
+        assert_eq!(value("Long"), Value::I32(i32::MIN));
+        assert_eq!(value("Currency"), Value::Currency(350050));
+        assert_eq!(value("Currency").currency_decimal().unwrap(), "35.0050");
+        assert_eq!(value("IEEESingle"), Value::F32(3.141592));
+        assert_eq!(value("IEEEDouble"), Value::F64(3.141592653589));
+        assert_eq!(value("DateTime").to_string(), "2021-03-29T11:49:47+00:00");
+        assert_eq!(value("UnsignedLong"), Value::U32(u32::MAX));
+        assert_eq!(value("LongLong"), Value::I64(i64::MAX));
+        assert_eq!(value("UnsignedShort"), Value::U16(u16::MAX));
+        match value("GUID") {
+            Value::Guid(guid) => {
+                assert_eq!(guid.to_string(), "{4D36E96E-E325-11CE-BFC1-08002BE10318}");
+                assert_eq!(guid.to_rfc4122()[..4], [0x4d, 0x36, 0xe9, 0x6e]);
+            }
+            v => panic!("unexpected GUID value {:?}", v),
+        }
+        match value("Binary") {
+            Value::MultiValue(v) => {
+                assert!(matches!(&v[0], Value::Binary(b) if b.len() == 128 && b[..2] == [0, 1]));
+                assert!(matches!(&v[1], Value::Binary(b) if b.len() == 64));
+            }
+            v => panic!("unexpected Binary value {:?}", v),
+        }
+        match value("LongBinary") {
+            Value::MultiValue(v) => {
+                assert_eq!(v.len(), 2);
+                assert!(matches!(&v[1], Value::Binary(b) if b.len() == 65536));
+            }
+            v => panic!("unexpected LongBinary value {:?}", v),
+        }
+        match value("Text") {
+            Value::MultiValue(v) => {
+                assert!(matches!(&v[0], Value::Text(s) if s.starts_with("ABCDEFG")));
+                assert!(matches!(&v[1], Value::Text(s) if s.starts_with("Hello")));
+            }
+            v => panic!("unexpected Text value {:?}", v),
+        }
+        match value("LongText") {
+            Value::Text(s) => assert_eq!(s.chars().count(), 4300),
+            v => panic!("unexpected LongText value {:?}", v),
+        }
+        assert_eq!(
+            value("TextDefaultValue"),
+            Value::Text("Default value.\0".to_string())
+        );
+
+        assert_eq!(value::currency_to_string(-5), "-0.0005");
+        assert_eq!(value::currency_to_string(i64::MIN), "-922337203685477.5808");
+        assert!(jdb.get_column_value(table_id, 9999).is_err());
+
+        // code page 1252, as exported to CSV and Parquet
+        assert_eq!(
+            Value::from_column_bytes_lossy(ESE_coltypText, 1252, b"caf\xe9").unwrap(),
+            Value::Text("café".to_string())
+        );
+        assert!(Value::from_column_bytes(ESE_coltypText, 1200, b"R\0\0").is_err());
+        assert_eq!(
+            Value::from_column_bytes_lossy(ESE_coltypText, 1200, b"R\0\0").unwrap(),
+            Value::Text("R\u{fffd}".to_string())
+        );
+    }
+
+    #[test]
+    fn test_export_table_to_csv() {
+        let jdb = init_tests(5, Some("decompress_test.edb"));
+        let mut out = vec![];
+        assert_eq!(
+            jdb.export_table_to_csv("test_table", &mut out, &csv::CsvOptions::default())
+                .unwrap(),
+            10
+        );
+        let mut expected =
+            "compressed_unicode,compressed_ascii,compressed_binary,usual_text\n".to_string();
+        for i in 0..10 {
+            let record = format!("Record {:>10}", i);
+            let hex: String = record.bytes().map(|b| format!("{:02x}", b)).collect();
+            expected += &format!("{},{},{},{}\n", record, record, hex, record);
+        }
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+        let mut out = vec![];
+        assert_eq!(jdb.export_table_csv("test_table", &mut out).unwrap(), 10);
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+
+        let jdb = init_tests(5, None);
+        let mut out = vec![];
+        let opts = csv::CsvOptions {
+            delimiter: ';',
+            binary_encoding: csv::BinaryEncoding::Base64,
+        };
+        assert_eq!(
+            jdb.export_table_to_csv("TestTable", &mut out, &opts)
+                .unwrap(),
+            1
+        );
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(
+            lines[0],
+            "AutoInc;Bit;UnsignedByte;Short;Long;Currency;IEEESingle;IEEEDouble;DateTime;\
+             UnsignedLong;LongLong;GUID;UnsignedShort;Binary;LongBinary;Text;LongText;\
+             TextDefaultValue"
+        );
+        let fields: Vec<&str> = lines[1].split(';').collect();
+        assert_eq!(
+            fields[..13],
+            [
+                "1",
+                "0",
+                "255",
+                "",
+                "-2147483648",
+                "350050",
+                "3.141592",
+                "3.141592653589",
+                "2021-03-29T11:49:47+00:00",
+                "4294967295",
+                "9223372036854775807",
+                "4d36e96e-e325-11ce-bfc1-08002be10318",
+                "65535"
+            ]
+        );
+        // bytes 0..127
+        assert!(fields[13].starts_with("AAECAwQFBgcICQoL"));
+        assert_eq!(fields[13].len(), 172);
+        assert!(fields[15].starts_with("ABCDEFG"));
+        assert_eq!(fields[16].chars().count(), 4300);
+        assert_eq!(fields[17], "Default value.\0");
+
+        // the table is closed when the writer fails after the header
+        struct FailingWriter(bool);
+        impl std::io::Write for FailingWriter {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                if self.0 {
+                    return Err(std::io::Error::other("write failed"));
+                }
+                self.0 = buf.contains(&b'\n');
+                Ok(buf.len())
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+        assert!(jdb
+            .export_table_to_csv("TestTable", &mut FailingWriter(false), &opts)
+            .is_err());
+        assert!(!jdb.is_table_open("TestTable"));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_export_table_json() {
+        let jdb = init_tests(5, Some("decompress_test.edb"));
+        let mut out = vec![];
+        assert_eq!(jdb.export_table_json("test_table", &mut out).unwrap(), 10);
+        let rows: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        let rows = rows.as_array().unwrap();
+        assert_eq!(rows.len(), 10);
+        for (i, row) in rows.iter().enumerate() {
+            let record = format!("Record {:>10}", i);
+            assert_eq!(row["compressed_ascii"], record.as_str());
+            assert_eq!(
+                row["compressed_binary"],
+                csv::to_base64(record.as_bytes()).as_str()
+            );
+        }
+
+        let jdb = init_tests(5, None);
+        let mut out = vec![];
+        assert_eq!(jdb.export_table_json("TestTable", &mut out).unwrap(), 1);
+        let rows: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        let row = &rows[0];
+        assert_eq!(row["Short"], serde_json::Value::Null);
+        assert_eq!(row["Currency"], 350050);
+        assert_eq!(row["DateTime"], "2021-03-29T11:49:47+00:00");
+        assert_eq!(row["GUID"], "4d36e96e-e325-11ce-bfc1-08002be10318");
+        assert!(row["Binary"]
+            .as_str()
+            .unwrap()
+            .starts_with("AAECAwQFBgcICQoL"));
+
+        let jdb = init_tests(5, Some("Current.mdb"));
+        let mut out = vec![];
+        assert_eq!(
+            jdb.export_table_json("VIRTUALMACHINES", &mut out).unwrap(),
+            0
+        );
+        assert_eq!(out, b"[]");
+
+        // the table is closed when the writer fails
+        struct FailingWriter;
+        impl std::io::Write for FailingWriter {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                if buf.starts_with(b"[") {
+                    return Ok(1);
+                }
+                Err(std::io::Error::other("write failed"))
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+        let jdb = init_tests(5, None);
+        assert!(jdb
+            .export_table_json("TestTable", &mut FailingWriter)
+            .is_err());
+        assert!(!jdb.is_table_open("TestTable"));
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_export_to_sqlite() {
+        let jdb = init_tests(5, Some("decompress_test.edb"));
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        assert_eq!(jdb.export_table_to_sqlite("test_table", &conn).unwrap(), 10);
+        let text: String = conn
+            .query_row(
+                "SELECT compressed_ascii FROM test_table LIMIT 1 OFFSET 3",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(text.starts_with("Record"));
+        assert!(text.ends_with('3'));
+
+        let jdb = init_tests(5, None);
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        assert_eq!(
+            jdb.export_all_tables_to_sqlite(&conn).unwrap(),
+            92 * 2 + 8 + 7 + 1
+        );
+        let guid: String = conn
+            .query_row("SELECT GUID FROM TestTable", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(guid, "4d36e96e-e325-11ce-bfc1-08002be10318");
+
+        // the table is closed when an insert fails, there is no room for the long text
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "max_page_count", 2).unwrap();
+        assert!(jdb.export_table_to_sqlite("TestTable", &conn).is_err());
+        assert!(!jdb.is_table_open("TestTable"));
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_export_to_parquet() {
+        use ::parquet::basic::Repetition;
+        use ::parquet::file::reader::{FileReader, SerializedFileReader};
+        use ::parquet::record::Field;
+
+        let path = std::env::temp_dir().join("ese_parser_test_export.parquet");
+        let jdb = init_tests(5, Some("decompress_test.edb"));
+        assert_eq!(jdb.export_to_parquet("test_table", &path).unwrap(), 10);
+        let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 10);
+        let row = reader.get_row_iter(None).unwrap().nth(3).unwrap().unwrap();
+        let text = row
+            .get_column_iter()
+            .find(|(name, _)| name.as_str() == "compressed_ascii")
+            .unwrap()
+            .1;
+        match text {
+            Field::Str(s) => assert!(s.starts_with("Record") && s.ends_with('3')),
+            f => panic!("unexpected field {:?}", f),
+        }
+
+        let jdb = init_tests(5, None);
+        assert_eq!(jdb.export_to_parquet("TestTable", &path).unwrap(), 1);
+        let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+        let schema = reader.metadata().file_metadata().schema_descr();
+        let repetition = |name: &str| {
+            (0..schema.num_columns())
+                .map(|i| schema.column(i))
+                .find(|c| c.name() == name)
+                .unwrap()
+                .self_type()
+                .get_basic_info()
+                .repetition()
+        };
+        assert_eq!(repetition("Text"), Repetition::REPEATED);
+        assert_eq!(repetition("Bit"), Repetition::OPTIONAL);
+        let row = reader.get_row_iter(None).unwrap().next().unwrap().unwrap();
+        for (name, field) in row.get_column_iter() {
+            match name.as_str() {
+                "Text" => match field {
+                    Field::ListInternal(list) => assert_eq!(list.elements().len(), 2),
+                    f => panic!("unexpected field {:?}", f),
+                },
+                "Short" => assert_eq!(field, &Field::Null),
+                "GUID" => match field {
+                    Field::Bytes(b) => assert_eq!(b.len(), 16),
+                    f => panic!("unexpected field {:?}", f),
+                },
+                _ => {}
+            }
+        }
+        std::fs::remove_file(&path).unwrap();
+
+        // the table is closed when the file can't be created
+        let path = std::env::temp_dir()
+            .join("ese_parser_no_such_dir")
+            .join("export.parquet");
+        assert!(jdb.export_to_parquet("TestTable", &path).is_err());
+        assert!(!jdb.is_table_open("TestTable"));
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn test_load_from_path_mmap() {
+        for db in ["test.edb", "decompress_test.edb", "Current.mdb"] {
+            let path = std::path::Path::new("testdata").join(db);
+            let jdb = init_tests(5, Some(db));
+            let mdb = unsafe { ese_parser::EseParser::load_from_path_mmap(5, &path) }.unwrap();
+            assert_eq!(mdb.get_database_path(), Some(path.as_path()));
+            let tables = jdb.get_tables().unwrap();
+            assert_eq!(mdb.get_tables().unwrap(), tables);
+            for table in &tables {
+                let columns = jdb.get_columns(table).unwrap();
+                let (t1, t2) = (
+                    jdb.open_table(table).unwrap(),
+                    mdb.open_table(table).unwrap(),
+                );
+                let mut has_row = jdb.move_row(t1, ESE_MoveFirst).unwrap();
+                assert_eq!(mdb.move_row(t2, ESE_MoveFirst).unwrap(), has_row);
+                while has_row {
+                    for c in &columns {
+                        assert_eq!(
+                            mdb.get_column(t2, c.id).unwrap(),
+                            jdb.get_column(t1, c.id).unwrap()
+                        );
+                    }
+                    has_row = jdb.move_row(t1, ESE_MoveNext).unwrap();
+                    assert_eq!(mdb.move_row(t2, ESE_MoveNext).unwrap(), has_row);
+                }
+            }
+        }
+
+        let missing =
+            unsafe { ese_parser::EseParser::load_from_path_mmap(5, "testdata/missing.edb") };
+        assert!(matches!(missing, Err(error::EseError::Io(_))));
+    }
+
+    #[test]
+    fn test_first_row_values() {
+        let jdb = init_tests(5, Some("SystemIdentity.mdb"));
+        let row = jdb
+            .get_first_row_values("CHAINED_DATABASES", &["FileName"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(row.len(), 1);
+        assert!(row["FileName"].is_some());
+        assert!(jdb
+            .get_first_row_values("CHAINED_DATABASES", &["NoSuchColumn"])
+            .is_err());
+
+        let jdb = init_tests(5, Some("Current.mdb"));
+        let columns = jdb.get_columns("VIRTUALMACHINES").unwrap();
+        assert_eq!(
+            jdb.get_first_row_values("VIRTUALMACHINES", &[&columns[0].name])
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_table_creation_time() {
+        // test databases have no computed statistics
+        let jdb = init_tests(5, None);
+        assert_eq!(jdb.get_table_creation_time("TestTable").unwrap(), None);
+        assert!(jdb.get_table_creation_time("NoSuchTable").is_err());
+    }
+
+    #[test]
+    fn test_index_statistics() {
+        let jdb = init_tests(5, None);
+        let stats = jdb.get_index_statistics("MSysObjects", "Id", None).unwrap();
+        assert_eq!(stats.depth, 2);
+        assert_eq!(stats.branch_page_count, 1);
+        assert_eq!(stats.leaf_page_count, 2);
+        assert_eq!(stats.entry_count, 92);
+        assert_eq!(stats.unique_key_count, 92);
+        let stats = jdb
+            .get_index_statistics("MSysObjects", "Id", Some(10))
+            .unwrap();
+        assert_eq!(stats.entry_count, 92);
+        assert_eq!(stats.unique_key_count, 10);
+        let stats = jdb.get_index_statistics("MSysObjects", "id", None).unwrap();
+        assert_eq!(stats.entry_count, 92);
+        assert!(jdb
+            .get_index_statistics("MSysObjects", "NoSuchIndex", None)
+            .is_err());
+
+        let jdb = init_tests(5, Some("Current.mdb"));
+        let stats = jdb
+            .get_index_statistics("CLIENTS", "Address_RoleGuid_TenantId_index", None)
+            .unwrap();
+        assert_eq!(stats.depth, 1);
+        assert_eq!(stats.entry_count, 39);
+        assert_eq!(stats.unique_key_count, 19);
+    }
+
+    #[test]
+    fn test_object_identifier_map() {
+        let jdb = init_tests(5, None);
+        let names = jdb.get_object_identifier_map();
+        assert_eq!(names[&2], "MSysObjects");
+        assert_eq!(names[&4], "Name");
+        assert_eq!(names[&8], "TestTable");
+        assert_eq!(names[&9], "LV");
+        let ids = jdb.get_name_to_object_id_map();
+        assert_eq!(ids["MSysObjectsShadow"], 3);
+        assert_eq!(ids["TestTable"], 8);
+        assert_eq!(names.len(), ids.len());
+    }
+
+    #[test]
+    fn test_iter_pages_with_data() {
+        let jdb = init_tests(5, None);
+        let table_id = jdb.open_table("MSysObjects").unwrap();
+        let pages = jdb
+            .iter_pages_with_data(table_id)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(pages.len(), 2);
+        let tags: usize = pages.iter().map(|(_, tags)| tags.len() - 1).sum();
+        assert_eq!(tags, 92);
+
+        let (second_page, _) = &pages[1];
+        assert_eq!(jdb.iter_pages_from(*second_page).count(), 1);
+        assert!(jdb.iter_pages_with_data(100).next().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_read_page_as_struct() {
+        use crate::parser::ese_db::PageHeaderCommon;
+
+        let jdb = init_tests(5, None);
+        let table_id = jdb.open_table("MSysObjects").unwrap();
+        let pages = jdb
+            .iter_pages_with_data(table_id)
+            .map(|p| p.unwrap().0)
+            .collect::<Vec<_>>();
+        // common page header follows 8 bytes of checksums
+        let header = unsafe { jdb.read_page_as_struct::<PageHeaderCommon>(pages[0], 8) }.unwrap();
+        assert_eq!({ header.next_page }, pages[1]);
+        let header = unsafe { jdb.read_page_as_struct::<PageHeaderCommon>(pages[1], 8) }.unwrap();
+        assert_eq!({ header.previous_page }, pages[0]);
+
+        let page_size = jdb.get_page_size() as usize;
+        unsafe {
+            assert!(jdb
+                .read_page_as_struct::<u32>(pages[0], page_size - 4)
+                .is_ok());
+            assert!(jdb
+                .read_page_as_struct::<u32>(pages[0], page_size - 3)
+                .is_err());
+            assert!(jdb
+                .read_page_as_struct::<u32>(pages[0], usize::MAX - 1)
+                .is_err());
+            assert!(jdb.read_page_as_struct::<u32>(0, 0).is_err());
+            assert!(jdb.read_page_as_struct::<u32>(u32::MAX, 0).is_err());
+        }
+    }
+
+    #[test]
+    fn test_record_checksum() {
+        let jdb = init_tests(5, None);
+        let checksums = |table: &str| {
+            let table_id = jdb.open_table(table).unwrap();
+            let mut v = vec![];
+            let mut has_row = jdb.move_row(table_id, ESE_MoveFirst).unwrap();
+            while has_row {
+                v.push(jdb.get_record_checksum(table_id).unwrap());
+                has_row = jdb.move_row(table_id, ESE_MoveNext).unwrap();
+            }
+            v
+        };
+        let objects = checksums("MSysObjects");
+        assert_eq!(objects.len(), 92);
+        assert_eq!(objects, checksums("MSysObjectsShadow"));
+        let unique: std::collections::HashSet<_> = objects.iter().collect();
+        assert_eq!(unique.len(), objects.len());
+    }
+
+    #[test]
+    fn test_tables_by_type() {
+        let jdb = init_tests(5, None);
+        assert_eq!(
+            jdb.get_tables_by_type(CatalogTableType::System).unwrap(),
+            [
+                "MSysObjects",
+                "MSysObjectsShadow",
+                "MSysObjids",
+                "MSysLocales"
+            ]
+        );
+        assert_eq!(
+            jdb.get_tables_by_type(CatalogTableType::User).unwrap(),
+            ["TestTable"]
+        );
+        assert_eq!(
+            jdb.get_tables_by_type(CatalogTableType::All).unwrap(),
+            jdb.get_tables().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_database_compat_version() {
+        let jdb = init_tests(5, None);
+        assert_eq!(jdb.get_database_compat_version().unwrap(), (0x620, 0x14));
+        assert!(jdb.can_open_with_version(0x620, 0x14));
+        assert!(jdb.can_open_with_version(0x620, 0x20));
+        assert!(!jdb.can_open_with_version(0x620, 0x11));
+        assert!(!jdb.can_open_with_version(0x600, 0x20));
+    }
+
+    #[test]
+    fn test_default_values() {
+        let jdb = init_tests(5, None);
+        let defaults = jdb.extract_default_values("TestTable").unwrap();
+        assert_eq!(defaults.len(), 1);
+        assert_eq!(defaults["TextDefaultValue"], b"Default value.\0");
+        assert_eq!(
+            jdb.get_column_default("TestTable", "TextDefaultValue")
+                .unwrap()
+                .unwrap(),
+            b"Default value.\0"
+        );
+        assert_eq!(jdb.get_column_default("TestTable", "Text").unwrap(), None);
+        assert!(jdb.get_column_default("TestTable", "NoSuchColumn").is_err());
+        assert!(jdb.extract_default_values("NoSuchTable").is_err());
+    }
+
+    #[test]
+    fn test_column_storage_class() {
+        let jdb = init_tests(5, None);
+        let columns = jdb.get_columns("TestTable").unwrap();
+        let class = |name: &str| {
+            let col = columns.iter().find(|x| x.name == name).unwrap();
+            let class = jdb.get_column_storage_class("TestTable", col.id).unwrap();
+            assert_eq!(class, col.storage_class());
+            class
+        };
+        assert_eq!(class("Bit"), ColumnStorageClass::Fixed);
+        assert_eq!(class("Text"), ColumnStorageClass::Tagged);
+        assert_eq!(
+            ColumnStorageClass::from_column_id(128),
+            ColumnStorageClass::Variable
+        );
+        assert!(jdb.get_column_storage_class("TestTable", 100000).is_err());
+    }
+
+    #[test]
+    fn test_lv_keys() {
+        let jdb = init_tests(5, None);
+        let table_id = jdb.open_table("TestTable").unwrap();
+        assert_eq!(jdb.list_all_lv_keys(table_id).unwrap(), [1, 2]);
+        assert!(jdb.has_lv_key(table_id, 2));
+        assert!(!jdb.has_lv_key(table_id, 3));
+        let table_id = jdb.open_table("MSysObjids").unwrap();
+        assert!(jdb.list_all_lv_keys(table_id).unwrap().is_empty());
+        assert!(jdb.list_all_lv_keys(100).is_err());
+        assert!(!jdb.has_lv_key(100, 1));
+    }
+
+    #[test]
+    fn test_table_linked_tables() {
+        let jdb = init_tests(5, None);
+        // every table in the test database is a catalog root
+        for table in jdb.get_tables().unwrap() {
+            assert!(jdb.get_table_linked_tables(&table).unwrap().is_empty());
+        }
+        assert!(jdb.get_table_linked_tables("NoSuchTable").is_err());
+
+        // none of the test databases has a linked table: TestTable is linked to MSysObjects
+        // by patching the ObjidTable of its catalog entries (ObjidTable, Type and Id of a table)
+        let ids = jdb.get_name_to_object_id_map();
+        let (table_id, parent_id) = (ids["TestTable"], ids["MSysObjects"]);
+        let entry = |objid_table: u32| {
+            let mut entry = objid_table.to_le_bytes().to_vec();
+            entry.extend_from_slice(&(parser::jet::CatalogType::Table as u16).to_le_bytes());
+            entry.extend_from_slice(&table_id.to_le_bytes());
+            entry
+        };
+        let (table_entry, linked_entry) = (entry(table_id), entry(parent_id));
+        let mut data = std::fs::read("testdata/test.edb").unwrap();
+        let mut patched = 0;
+        while let Some(offset) = data
+            .windows(table_entry.len())
+            .position(|w| w == table_entry.as_slice())
+        {
+            data[offset..offset + linked_entry.len()].copy_from_slice(&linked_entry);
+            patched += 1;
+        }
+        // the catalog and its shadow copy
+        assert_eq!(patched, 2);
+
+        let linked = ese_parser::EseParser::load(5, std::io::Cursor::new(data)).unwrap();
+        assert_eq!(
+            linked.get_table_linked_tables("MSysObjects").unwrap(),
+            vec!["TestTable".to_string()]
+        );
+        assert!(linked
+            .get_table_linked_tables("TestTable")
+            .unwrap()
+            .is_empty());
+        // the table itself is unchanged
+        assert_eq!(
+            linked.get_columns("TestTable").unwrap().len(),
+            jdb.get_columns("TestTable").unwrap().len()
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_export_record_to_json() {
+        let jdb = init_tests(5, None);
+        let table_id = jdb.open_table("TestTable").unwrap();
+        let (page, _) = jdb.iter_pages_with_data(table_id).next().unwrap().unwrap();
+        let columns = jdb.get_columns("TestTable").unwrap();
+        let text = columns.iter().find(|x| x.name == "Text").unwrap();
+        jdb.move_row(table_id, ESE_MoveFirst).unwrap();
+        let expected_text = jdb.get_column_str(table_id, text.id, text.cp).unwrap();
+
+        let json = jdb.export_record_to_json(table_id, page, 1).unwrap();
+        let record: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(record["_page"], page);
+        assert_eq!(record["_tag"], 1);
+        assert_eq!(record["AutoInc"], 1);
+        assert_eq!(record["Bit"], false);
+        assert_eq!(record["UnsignedByte"], 255);
+        assert_eq!(record["Short"], serde_json::Value::Null);
+        assert_eq!(record["Text"], expected_text.unwrap());
+        assert_eq!(record["TextDefaultValue"], "Default value.\0");
+
+        // the cursor is left where it was
+        assert_eq!(
+            jdb.get_column_str(table_id, text.id, text.cp).unwrap(),
+            record["Text"].as_str().map(String::from)
+        );
+        assert!(!jdb.move_row(table_id, ESE_MoveNext).unwrap());
+
+        assert!(jdb.export_record_to_json(table_id, page, 0).is_err());
+        assert!(jdb.export_record_to_json(table_id, page, 2).is_err());
+        let objects_id = jdb.open_table("MSysObjects").unwrap();
+        let (objects_page, _) = jdb
+            .iter_pages_with_data(objects_id)
+            .next()
+            .unwrap()
+            .unwrap();
+        assert!(jdb
+            .export_record_to_json(table_id, objects_page, 1)
+            .is_err());
+        assert!(jdb
+            .export_record_to_json(objects_id, objects_page, 1)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_database_path() {
+        let jdb = init_tests(5, None);
+        assert_eq!(
+            jdb.get_database_path(),
+            Some(std::path::Path::new("testdata/test.edb"))
+        );
+
+        let file = File::open("testdata/test.edb").unwrap();
+        let jdb = ese_parser::EseParser::load(5, BufReader::new(file)).unwrap();
+        assert_eq!(jdb.get_database_path(), None);
+    }
+
+    #[test]
+    fn test_verify_page_checksums() {
+        use crate::parser::checksum::ChecksumResult;
+
+        for db in ["test.edb", "decompress_test.edb", "Current.mdb"] {
+            let jdb = init_tests(5, Some(db));
+            let page_count =
+                (jdb.get_database_file_size().unwrap() / jdb.get_page_size() as u64) as u32 - 2;
+            let pages: Vec<u32> = (1..=page_count).collect();
+            for r in jdb.verify_page_checksums(&pages).unwrap() {
+                assert!(matches!(r, ChecksumResult::Ok(_)), "{}: {:?}", db, r);
+            }
+        }
+
+        let mut data = std::fs::read("testdata/test.edb").unwrap();
+        let page_size = 4096;
+        // flip a bit in the page 4
+        data[5 * page_size + 100] ^= 0x10;
+        let jdb = ese_parser::EseParser::load(5, std::io::Cursor::new(data)).unwrap();
+        let results = jdb.verify_page_checksums(&[3, 4, 1000]).unwrap();
+        assert!(matches!(results[0], ChecksumResult::Ok(3)));
+        match results[1] {
+            ChecksumResult::Mismatch {
+                page_number,
+                block,
+                stored,
+                computed,
+            } => {
+                assert_eq!(page_number, 4);
+                assert_eq!(block, 0);
+                assert_eq!(stored as u32 ^ computed as u32, 0x10);
+            }
+            ref r => panic!("unexpected {:?}", r),
+        }
+        assert!(matches!(
+            results[2],
+            ChecksumResult::Unreadable {
+                page_number: 1000,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_page_checksums_0x0b() {
+        use crate::parser::checksum::page_checksums;
+        use crate::parser::ese_db::ESEDB_FORMAT_REVISION_NEW_RECORD_FORMAT;
+
+        // 4K pages of revision 0x14 have the header checksum layout of revision 0x0b:
+        // ECC in the upper 32 bits, XOR of the page seeded with its number in the lower 32 bits
+        let mut data = std::fs::read("testdata/test.edb").unwrap();
+        let page_size = 4096;
+        for page_number in 1..=(data.len() / page_size) as u32 - 2 {
+            let offset = (page_number as usize + 1) * page_size;
+            let page = &data[offset..offset + page_size];
+            if page.iter().all(|&b| b == 0) {
+                continue;
+            }
+            let checksums =
+                page_checksums(page, page_number, ESEDB_FORMAT_REVISION_NEW_RECORD_FORMAT);
+            assert_eq!(checksums.len(), 1);
+            assert_eq!(checksums[0].0, checksums[0].1, "page {}", page_number);
+        }
+
+        data[5 * page_size + 200] ^= 0x80;
+        let page = &data[5 * page_size..6 * page_size];
+        let (stored, computed) =
+            page_checksums(page, 4, ESEDB_FORMAT_REVISION_NEW_RECORD_FORMAT)[0];
+        assert_eq!(stored as u32 ^ computed as u32, 0x80);
+        assert_ne!(stored >> 32, computed >> 32);
+    }
+
+    #[test]
+    fn test_page_checksums_extended_header() {
+        use crate::parser::checksum::{ecc_checksum, page_checksums};
+        use crate::parser::ese_db::{PageHeader0x11, PageHeaderCommon};
+
+        // 32K page of revision 0x14: checksum of the first quarter in the page header,
+        // of the other quarters in the extended header
+        let (page_size, page_number, format_revision) = (32 * 1024, 7, 0x14);
+        let mut page: Vec<u8> = (0..page_size as u32)
+            .map(|i| (i.wrapping_mul(2654435761) >> 24) as u8)
+            .collect();
+        let ext_offset =
+            std::mem::size_of::<PageHeader0x11>() + std::mem::size_of::<PageHeaderCommon>();
+        for block in 1..4 {
+            let range = block * page_size / 4..(block + 1) * page_size / 4;
+            let checksum = ecc_checksum(&page[range], 0, page_number);
+            let offset = ext_offset + (block - 1) * 8;
+            page[offset..offset + 8].copy_from_slice(&checksum.to_le_bytes());
+        }
+        let checksum = ecc_checksum(&page[..page_size / 4], 8, page_number);
+        page[..8].copy_from_slice(&checksum.to_le_bytes());
+
+        let checksums = page_checksums(&page, page_number, format_revision);
+        assert_eq!(checksums.len(), 4);
+        assert!(checksums
+            .iter()
+            .all(|(stored, computed)| stored == computed));
+
+        // a flipped bit in the third quarter fails its checksum only
+        page[page_size / 2 + 1000] ^= 0x04;
+        let failing: Vec<usize> = page_checksums(&page, page_number, format_revision)
+            .iter()
+            .enumerate()
+            .filter(|(_, (stored, computed))| stored != computed)
+            .map(|(block, _)| block)
+            .collect();
+        assert_eq!(failing, vec![2]);
+
+        // so does a flipped bit in an extended header checksum, and the first quarter covering it
+        page[page_size / 2 + 1000] ^= 0x04;
+        page[ext_offset] ^= 0x01;
+        let failing: Vec<usize> = page_checksums(&page, page_number, format_revision)
+            .iter()
+            .enumerate()
+            .filter(|(_, (stored, computed))| stored != computed)
+            .map(|(block, _)| block)
+            .collect();
+        assert_eq!(failing, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_checksum_mode() {
+        use crate::parser::checksum::ChecksumMode;
+
+        let load = |data: &Vec<u8>, mode| {
+            ese_parser::EseParser::load_with_checksum_mode(
+                5,
+                std::io::Cursor::new(data.clone()),
+                mode,
+            )
+            .unwrap()
+        };
+        let read_guid = |jdb: &ese_parser::EseParser<MemoryBackend<Vec<u8>>>| {
+            let table_id = jdb.open_table("TestTable")?;
+            jdb.move_row(table_id, ESE_MoveFirst)?;
+            let columns = jdb.get_columns("TestTable")?;
+            let column = columns.iter().find(|c| c.name == "GUID").unwrap();
+            jdb.get_column(table_id, column.id)
+        };
+
+        let mut data = std::fs::read("testdata/test.edb").unwrap();
+        let guid = read_guid(&load(&data, ChecksumMode::Strict))
+            .unwrap()
+            .unwrap();
+        let offset = data.windows(guid.len()).position(|w| w == guid).unwrap();
+        let page_number = (offset / 4096) as u32 - 1;
+        data[offset] ^= 0x01;
+
+        let jdb = load(&data, ChecksumMode::Strict);
+        match read_guid(&jdb) {
+            Err(error::EseError::PageChecksumMismatch { page, .. }) => {
+                assert_eq!(page, page_number)
+            }
+            r => panic!("unexpected {:?}", r),
+        }
+        let reader =
+            parser::reader::Reader::load_db(std::io::Cursor::new(data.clone()), 5).unwrap();
+        assert!(reader.verify_page(page_number - 1).is_ok());
+        assert!(matches!(
+            reader.verify_page(page_number),
+            Err(error::EseError::PageChecksumMismatch { .. })
+        ));
+
+        for mode in [ChecksumMode::Warn, ChecksumMode::Ignore] {
+            let mut corrupted = guid.clone();
+            corrupted[0] ^= 0x01;
+            assert_eq!(read_guid(&load(&data, mode)).unwrap(), Some(corrupted));
+        }
+    }
+
+    #[test]
+    fn test_checksum_mode_move_row() {
+        use crate::parser::checksum::ChecksumMode;
+
+        let mut data = std::fs::read("testdata/Current.mdb").unwrap();
+        let jdb = ese_parser::EseParser::load(5, std::io::Cursor::new(data.clone())).unwrap();
+        let page_size = jdb.db_info().unwrap().page_size as usize;
+        let table_id = jdb.open_table("MSysObjectsShadow").unwrap();
+        let pages: Vec<u32> = jdb
+            .iter_pages_with_data(table_id)
+            .map(|p| p.unwrap().0)
+            .collect();
+        jdb.close_table(table_id);
+        assert!(pages.len() > 1);
+        // the second leaf page of the table is corrupted, the first one is read by open_table
+        data[(pages[1] as usize + 1) * page_size + 100] ^= 0x01;
+
+        let jdb = ese_parser::EseParser::load_with_checksum_mode(
+            5,
+            std::io::Cursor::new(data),
+            ChecksumMode::Strict,
+        )
+        .unwrap();
+        let table_id = jdb.open_table("MSysObjectsShadow").unwrap();
+        let error = loop {
+            match jdb.move_row(table_id, ESE_MoveNext) {
+                Ok(true) => {}
+                Ok(false) => panic!("the corrupt page wasn't reached"),
+                Err(e) => break e,
+            }
+        };
+        match error {
+            error::EseError::PageChecksumMismatch { page, .. } => assert_eq!(page, pages[1]),
+            e => panic!("unexpected {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_checksum_mode_verified_once() {
+        use crate::parser::checksum::ChecksumMode;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::{Arc, Mutex};
+        // a buffer modified by the test while it is read, counting the calls of `size`
+        struct SharedData {
+            data: Arc<Mutex<Vec<u8>>>,
+            size_calls: Arc<AtomicUsize>,
+        }
+        impl ReaderBackend for SharedData {
+            fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<(), error::EseError> {
+                let data = self.data.lock().unwrap();
+                let data = data
+                    .get(offset as usize..offset as usize + buf.len())
+                    .ok_or_else(|| error::EseError::new("read past the end"))?;
+                buf.copy_from_slice(data);
+                Ok(())
+            }
+
+            fn size(&self) -> Result<u64, error::EseError> {
+                self.size_calls.fetch_add(1, Ordering::Relaxed);
+                Ok(self.data.lock().unwrap().len() as u64)
+            }
+        }
+
+        let data = Arc::new(Mutex::new(std::fs::read("testdata/test.edb").unwrap()));
+        let size_calls = Arc::new(AtomicUsize::new(0));
+        let source = SharedData {
+            data: data.clone(),
+            size_calls: size_calls.clone(),
+        };
+        let options = ese_parser::ParserOptions {
+            checksum_mode: ChecksumMode::Strict,
+            ..Default::default()
+        };
+        // a cache of 2 pages, the pages are read again from the buffer
+        let jdb =
+            ese_parser::EseParser::load_from_backend_with_options(2, source, options).unwrap();
+        let columns = jdb.get_columns("TestTable").unwrap();
+        let column = columns.iter().find(|c| c.name == "GUID").unwrap();
+        let table_id = jdb.open_table("TestTable").unwrap();
+        let guid = jdb.get_column(table_id, column.id).unwrap().unwrap();
+        jdb.close_table(table_id);
+
+        // the page was verified when it was loaded the first time, it isn't verified again
+        let offset = {
+            let mut data = data.lock().unwrap();
+            let offset = data.windows(guid.len()).position(|w| w == guid).unwrap();
+            data[offset] ^= 0x01;
+            offset
+        };
+        assert_eq!(jdb.get_row_count("MSysObjects").unwrap(), 92);
+        let table_id = jdb.open_table("TestTable").unwrap();
+        let corrupted = jdb.get_column(table_id, column.id).unwrap().unwrap();
+        assert_eq!(corrupted[0], guid[0] ^ 0x01);
+        let data = data.lock().unwrap().clone();
+        let reader = parser::reader::Reader::from_memory(data).unwrap();
+        assert!(reader.verify_page((offset / 4096) as u32 - 1).is_err());
+
+        // the file size is read once, when the database is loaded
+        let size_calls = || size_calls.load(Ordering::Relaxed);
+        assert_eq!(size_calls(), 1);
+        jdb.get_database_file_size().unwrap();
+        jdb.integrity_check().unwrap();
+        assert_eq!(size_calls(), 1);
+    }
+
+    #[test]
+    fn test_integrity_check() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        let mut data = std::fs::read("testdata/test.edb").unwrap();
+        let load = |data: &Vec<u8>| {
+            ese_parser::EseParser::load(5, std::io::Cursor::new(data.clone())).unwrap()
+        };
+        let report = load(&data).integrity_check().unwrap();
+        assert_eq!(report.total_pages as usize, data.len() / 4096 - 2);
+        assert!(report.corrupt_pages.is_empty());
+        assert!(report.lv_pages > 0);
+        assert!(report.free_pages > 0);
+
+        // a record page and a long value page, both stay readable
+        let record = 350050_i64.to_le_bytes(); // Currency of TestTable
+                                               // the last copy of the text is in a long value page
+        let long_text = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let mut corrupt = vec![];
+        for pattern in &[&record[..], &long_text[..]] {
+            let offset = data
+                .windows(pattern.len())
+                .rposition(|w| w == *pattern)
+                .unwrap();
+            data[offset] ^= 0x01;
+            corrupt.push((offset / 4096) as u32 - 1);
+        }
+        corrupt.sort_unstable();
+        let corrupt_report = load(&data).integrity_check().unwrap();
+        assert_eq!(corrupt_report.total_pages, report.total_pages);
+        let pages: Vec<u32> = corrupt_report
+            .corrupt_pages
+            .iter()
+            .map(|p| p.page_number)
+            .collect();
+        assert_eq!(pages, corrupt);
+        assert!(corrupt_report
+            .corrupt_pages
+            .iter()
+            .all(|p| p.stored != p.computed && p.block == 0));
+        assert_eq!(corrupt_report.lv_pages, report.lv_pages - 1);
+        assert!(corrupt_report.unreadable_pages.is_empty());
+
+        // a backend failing the reads of the record page, counting the reads
+        struct FailingPage {
+            data: Vec<u8>,
+            page_offset: u64,
+            reads: Arc<AtomicUsize>,
+        }
+        impl ReaderBackend for FailingPage {
+            fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<(), error::EseError> {
+                self.reads.fetch_add(1, Ordering::Relaxed);
+                if offset == self.page_offset {
+                    return Err(error::EseError::new("bad sector"));
+                }
+                buf.copy_from_slice(&self.data[offset as usize..offset as usize + buf.len()]);
+                Ok(())
+            }
+
+            fn size(&self) -> Result<u64, error::EseError> {
+                Ok(self.data.len() as u64)
+            }
+        }
+        let reads = Arc::new(AtomicUsize::new(0));
+        let backend = FailingPage {
+            data,
+            page_offset: (corrupt[0] as u64 + 1) * 4096,
+            reads: reads.clone(),
+        };
+        let jdb = ese_parser::EseParser::load_from_backend(5, backend).unwrap();
+        let before = reads.load(Ordering::Relaxed);
+        let unreadable_report = jdb.integrity_check().unwrap();
+        // each page is read once, the scan goes on after the unreadable page
+        assert_eq!(
+            reads.load(Ordering::Relaxed) - before,
+            report.total_pages as usize
+        );
+        assert_eq!(
+            unreadable_report.unreadable_pages,
+            vec![ese_parser::UnreadablePage {
+                page_number: corrupt[0],
+                error: "bad sector".to_string(),
+            }]
+        );
+        assert_eq!(
+            unreadable_report.corrupt_pages,
+            corrupt_report.corrupt_pages[1..]
+        );
+        assert_eq!(unreadable_report.lv_pages, corrupt_report.lv_pages);
+    }
+
+    #[test]
+    fn test_schema_hash() {
+        let jdb = init_tests(5, None);
+        let hash = jdb.get_table_schema_hash("TestTable").unwrap();
+        assert_eq!(hash, jdb.get_table_schema_hash("TestTable").unwrap());
+        // same schema, different table
+        assert_eq!(
+            jdb.get_table_schema_hash("MSysObjects").unwrap(),
+            jdb.get_table_schema_hash("MSysObjectsShadow").unwrap()
+        );
+        assert_ne!(hash, jdb.get_table_schema_hash("MSysObjids").unwrap());
+        assert!(jdb.get_table_schema_hash("NoSuchTable").is_err());
+
+        let db_hash = jdb.get_database_schema_hash().unwrap();
+        assert_eq!(
+            db_hash,
+            init_tests(5, None).get_database_schema_hash().unwrap()
+        );
+        assert_ne!(
+            db_hash,
+            init_tests(5, Some("decompress_test.edb"))
+                .get_database_schema_hash()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_tagged_data_type_flags() {
+        use crate::parser::jet::TaggedDataTypeFlag;
+
+        let jdb = init_tests(5, None);
+        let columns = jdb.get_columns("TestTable").unwrap();
+        let table_id = jdb.open_table("TestTable").unwrap();
+        jdb.move_row(table_id, ESE_MoveFirst).unwrap();
+        let flags = |name: &str| {
+            let col = columns.iter().find(|x| x.name == name).unwrap();
+            jdb.get_tagged_data_type_flags(table_id, col.id).unwrap()
+        };
+        assert_eq!(flags("Bit"), None);
+        assert!(flags("Text")
+            .unwrap()
+            .contains(TaggedDataTypeFlag::MULTI_VALUE));
+        assert!(flags("LongText")
+            .unwrap()
+            .contains(TaggedDataTypeFlag::LONG_VALUE));
+        // not stored in the row, default value is used
+        assert_eq!(flags("TextDefaultValue"), None);
+
+        let jdb = init_tests(5, Some("decompress_test.edb"));
+        let columns = jdb.get_columns("test_table").unwrap();
+        let table_id = jdb.open_table("test_table").unwrap();
+        jdb.move_row(table_id, ESE_MoveFirst).unwrap();
+        let flags = |name: &str| {
+            let col = columns.iter().find(|x| x.name == name).unwrap();
+            jdb.get_tagged_data_type_flags(table_id, col.id).unwrap()
+        };
+        assert!(flags("compressed_ascii")
+            .unwrap()
+            .contains(TaggedDataTypeFlag::COMPRESSED));
+        assert!(!flags("usual_text")
+            .unwrap()
+            .contains(TaggedDataTypeFlag::COMPRESSED));
+    }
+
+    #[test]
+    fn test_page_tag_data() {
+        let jdb = init_tests(5, None);
+        let table_id = jdb.open_table("TestTable").unwrap();
+        jdb.move_row(table_id, ESE_MoveFirst).unwrap();
+        let (page, tags) = jdb.iter_pages_with_data(table_id).next().unwrap().unwrap();
+        let data = jdb.get_page_tag_data(page, 1).unwrap();
+        assert_eq!(data.len(), tags[1].size as usize);
+        assert_eq!(
+            crc32fast::hash(&data),
+            jdb.get_record_checksum(table_id).unwrap()
+        );
+        assert!(jdb.get_page_tag_data(page, tags.len()).is_err());
+    }
+
+    #[test]
+    fn test_column_counts() {
+        let jdb = init_tests(5, None);
+        let counts = jdb.get_column_count_across_tables().unwrap();
+        assert_eq!(counts.len(), 5);
+        assert_eq!(counts["MSysObjects"], 28);
+        assert_eq!(counts["MSysObjids"], 3);
+        assert_eq!(counts["TestTable"], 18);
+        assert_eq!(jdb.get_total_column_count().unwrap(), 28 + 28 + 3 + 3 + 18);
+        assert_eq!(
+            jdb.get_max_column_count_table().unwrap(),
+            ("MSysObjects".to_string(), 28)
+        );
+
+        let jdb = init_tests(5, Some("Current.mdb"));
+        assert_eq!(
+            jdb.get_max_column_count_table().unwrap(),
+            ("CLIENTS".to_string(), 374)
+        );
+    }
+
+    #[test]
+    fn test_build_in_memory_index() {
+        let jdb = init_tests(5, None);
+        let columns = jdb.get_columns("MSysObjids").unwrap();
+        let objid_table = columns.iter().find(|x| x.name == "objidTable").unwrap();
+        let table_id = jdb.open_table("MSysObjids").unwrap();
+        let index = jdb
+            .build_in_memory_index(table_id, objid_table.id, None)
+            .unwrap();
+        assert_eq!(index[&vec![2, 0, 0, 0]].len(), 3);
+        assert_eq!(index.values().map(|v| v.len()).sum::<usize>(), 8);
+        for (value, locations) in &index {
+            assert_eq!(
+                jdb.get_column_value_frequency(table_id, objid_table.id, value, None)
+                    .unwrap(),
+                locations.len() as u64
+            );
+        }
+        assert!(jdb
+            .build_in_memory_index(table_id, objid_table.id, Some(16))
+            .is_err());
+
+        // every value of a multi-valued column is indexed
+        let columns = jdb.get_columns("TestTable").unwrap();
+        let text = columns.iter().find(|x| x.name == "Text").unwrap();
+        let table_id = jdb.open_table("TestTable").unwrap();
+        let index = jdb.build_in_memory_index(table_id, text.id, None).unwrap();
+        assert_eq!(index.len(), 2);
+        let (page, _) = jdb.iter_pages_with_data(table_id).next().unwrap().unwrap();
+        assert!(index.values().all(|v| v == &[(page, 1)]));
+    }
+
+    #[test]
+    fn test_page_allocation_map() {
+        let jdb = init_tests(5, None);
+        let allocs = jdb.get_page_allocation_map().unwrap();
+        // extents are contiguous and cover all pages of the database
+        assert_eq!(allocs[0].start_page, 1);
+        for w in allocs.windows(2) {
+            assert_eq!(w[0].start_page + w[0].extent_size, w[1].start_page);
+        }
+        let last = allocs.last().unwrap();
+        let total_pages = jdb.get_database_file_size().unwrap() / jdb.get_page_size() as u64 - 2;
+        assert_eq!(last.start_page + last.extent_size - 1, total_pages as u32);
+        assert!(last.is_free);
+        assert_eq!(last.owner_object_id, None);
+
+        let test_table = jdb.get_name_to_object_id_map()["TestTable"];
+        assert!(allocs
+            .iter()
+            .any(|a| a.owner_object_id == Some(test_table) && !a.is_free));
+        assert!(allocs
+            .iter()
+            .filter(|a| a.is_free)
+            .all(|a| a.owner_object_id != Some(1)));
+
+        let map = ese_parser::format_page_allocation_map(&allocs, total_pages as u32);
+        assert!(map.starts_with("         1 |000111"));
+        assert!(map.contains(&format!("object {}\n", test_table)));
+        assert_eq!(ese_parser::format_page_allocation_map(&allocs, 0), "");
+    }
+
+    #[test]
+    fn test_catalog_entry_counts() {
+        let jdb = init_tests(5, None);
+        let counts = jdb.get_catalog_entry_count();
+        assert_eq!(counts.tables, jdb.get_tables().unwrap().len());
+        assert_eq!(counts.columns, 80);
+        assert_eq!(counts.indexes, 6);
+        assert_eq!(counts.callbacks, 0);
+        assert_eq!(counts.long_values, 1);
+
+        let counts = jdb.get_catalog_entry_counts_for_table("TestTable").unwrap();
+        assert_eq!(counts.tables, 1);
+        assert_eq!(counts.columns, jdb.get_columns("TestTable").unwrap().len());
+        assert_eq!(counts.indexes, 0);
+        assert_eq!(counts.long_values, 1);
+        assert!(jdb
+            .get_catalog_entry_counts_for_table("NoSuchTable")
+            .is_err());
+    }
+
+    #[test]
+    fn test_table_primary_key_columns() {
+        let jdb = init_tests(5, None);
+        assert_eq!(
+            jdb.get_table_primary_key_columns("MSysObjects").unwrap(),
+            vec!["ObjidTable", "Type", "Id"]
+        );
+        assert_eq!(
+            jdb.get_table_primary_key_columns("MSysObjids").unwrap(),
+            vec!["objid"]
+        );
+        // no indexes at all
+        assert!(jdb
+            .get_table_primary_key_columns("TestTable")
+            .unwrap()
+            .is_empty());
+        assert!(jdb.get_table_primary_key_columns("NoSuchTable").is_err());
+
+        // secondary indexes only
+        let jdb = init_tests(5, Some("Current.mdb"));
+        assert!(jdb
+            .get_table_primary_key_columns("CLIENTS")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_column_lcmap_flags() {
+        let jdb = init_tests(5, None);
+        let columns = jdb.get_columns("TestTable").unwrap();
+        let text = columns.iter().find(|x| x.name == "Text").unwrap();
+        assert_eq!(jdb.get_column_lcmap_flags("TestTable", text.id).unwrap(), 0);
+        assert!(jdb.get_column_lcmap_flags("TestTable", 1000).is_err());
+        assert!(jdb.get_column_lcmap_flags("NoSuchTable", text.id).is_err());
+    }
+
+    #[test]
+    fn test_record_size() {
+        let jdb = init_tests(5, None);
+        let columns = jdb.get_columns("TestTable").unwrap();
+        let table_id = jdb.open_table("TestTable").unwrap();
+        let mut expected = 0;
+        for c in &columns {
+            let mut multi_value_index = 1;
+            while let Some(v) = jdb
+                .get_column_mv(table_id, c.id, multi_value_index)
+                .unwrap()
+            {
+                expected += v.len() as u32;
+                multi_value_index += 1;
+            }
+        }
+        // single row
+        assert_eq!(expected, 74_801);
+        assert_eq!(jdb.get_max_record_size("TestTable").unwrap(), expected);
+        assert_eq!(
+            jdb.get_avg_record_size("TestTable").unwrap(),
+            expected as f64
+        );
+        // the table opened above is still open, long values included
+        let mut size = 0;
+        for c in &columns {
+            let mut multi_value_index = 1;
+            while let Some(v) = jdb
+                .get_column_mv(table_id, c.id, multi_value_index)
+                .unwrap()
+            {
+                size += v.len() as u32;
+                multi_value_index += 1;
+            }
+        }
+        assert_eq!(size, expected);
+        jdb.close_table(table_id);
+        assert!(!jdb.is_table_open("TestTable"));
+
+        let max = jdb.get_max_record_size("MSysObjects").unwrap();
+        let avg = jdb.get_avg_record_size("MSysObjects").unwrap();
+        assert!(avg > 0.0 && avg <= max as f64);
+        assert!(jdb.get_max_record_size("NoSuchTable").is_err());
+    }
+
+    #[test]
+    fn test_column_value_distinct_count() {
+        let jdb = init_tests(5, None);
+        let columns = jdb.get_columns("MSysObjects").unwrap();
+        let table_id = jdb.open_table("MSysObjects").unwrap();
+        let typ = columns.iter().find(|x| x.name == "Type").unwrap();
+        let name = columns.iter().find(|x| x.name == "Name").unwrap();
+        // table, column, index and long-value entries
+        assert_eq!(
+            jdb.get_column_value_distinct_count(table_id, typ.id, 100)
+                .unwrap(),
+            4
+        );
+        assert_eq!(
+            jdb.get_column_value_distinct_count(table_id, typ.id, 2)
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            jdb.get_column_value_approx_distinct_count(table_id, typ.id)
+                .unwrap(),
+            4
+        );
+        let names = jdb
+            .get_column_value_distinct_count(table_id, name.id, u64::MAX)
+            .unwrap();
+        let approx = jdb
+            .get_column_value_approx_distinct_count(table_id, name.id)
+            .unwrap();
+        assert!(approx.abs_diff(names) <= 2);
+
+        // both values of a multi-valued column
+        let columns = jdb.get_columns("TestTable").unwrap();
+        let table_id = jdb.open_table("TestTable").unwrap();
+        let text = columns.iter().find(|x| x.name == "Text").unwrap();
+        assert_eq!(
+            jdb.get_column_value_distinct_count(table_id, text.id, 100)
+                .unwrap(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_table_tag_sizes() {
+        let jdb = init_tests(5, None);
+        let table_id = jdb.open_table("MSysObjects").unwrap();
+        let avg = jdb.get_table_avg_tag_size(table_id).unwrap();
+        assert!(avg > 0.0 && avg < jdb.get_page_size() as f64);
+
+        let histogram = jdb.get_tag_size_histogram(table_id, &[64, 128]).unwrap();
+        assert_eq!(histogram.len(), 3);
+        // one tag per catalog entry
+        assert_eq!(histogram.iter().sum::<u64>(), 92);
+        assert_eq!(jdb.get_tag_size_histogram(table_id, &[]).unwrap(), vec![92]);
+        assert!(jdb.get_tag_size_histogram(table_id, &[128, 64]).is_err());
+    }
+
+    #[test]
+    fn test_shadow_catalog() {
+        for db in &["test.edb", "decompress_test.edb", "Current.mdb"] {
+            let jdb = init_tests(5, Some(db));
+            assert!(!jdb.get_database_shadow_catalog_diverges().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_database_health_report() {
+        let jdb = init_tests(5, None);
+        let report = jdb.get_database_health_report().unwrap();
+        assert!(!report.dirty_shutdown);
+        assert!(!report.shadow_catalog_diverges);
+        assert_eq!(report.repair_count, 0);
+        assert!(report.issues.is_empty());
+        assert!(report.format_description.starts_with("0x620, 0x14"));
+
+        let jdb = init_tests(5, Some("Current.mdb"));
+        let report = jdb.get_database_health_report().unwrap();
+        assert!(report.dirty_shutdown);
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].severity, ese_parser::Severity::Warning);
+    }
+
+    #[test]
+    fn test_db_info() {
+        let jdb = init_tests(5, None);
+        let info = jdb.db_info().unwrap();
+        assert_eq!((info.format_version, info.format_revision), (0x620, 0x14));
+        assert_eq!(info.page_size, 4096);
+        assert_eq!(info.database_state, parser::jet::DbState::CleanShutdown);
+        assert_eq!(info.signature.random, 0x9d816468);
+        assert_eq!(info.signature.computer_name, "");
+        assert_eq!(
+            info.signature.creation_time.unwrap().to_rfc3339(),
+            "2021-03-29T08:49:13+00:00"
+        );
+        assert_eq!(
+            info.detach_time.unwrap().to_rfc3339(),
+            "2021-03-29T08:49:50+00:00"
+        );
+        assert_eq!(info.last_object_identifier, 9);
+        assert_eq!(info.ecc_fix_success_count, 0);
+        assert_eq!(info.ecc_fix_error_count, 0);
+        assert_eq!(info.bad_checksum_error_count, 0);
+        // 1M file of 4K pages
+        assert_eq!(info.page_count, 254);
+        assert_eq!(info.page_size, jdb.get_file_header().page_size);
+        assert!(info.detach_time.unwrap() >= info.attach_time.unwrap());
+
+        let table = info.to_string();
+        assert!(table.contains("Page size               4096\n"));
+        assert!(table.contains("Detached                2021-03-29T08:49:50+00:00\n"));
+        assert!(table.contains("ECC fixes               0 (0 failed)\n"));
+
+        let info = init_tests(5, Some("Current.mdb")).db_info().unwrap();
+        assert_eq!(info.database_state, parser::jet::DbState::DirtyShutdown);
+        assert_eq!(info.last_object_identifier, 17);
+        // still attached, never detached
+        assert!(info.attach_time.is_some());
+        assert_eq!(info.detach_time, None);
+        assert!(info.to_string().contains("Detached                -\n"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_db_info_serde() {
+        let info = init_tests(5, None).db_info().unwrap();
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["database_state"], "CleanShutdown");
+        assert_eq!(json["signature"]["creation_time"], "2021-03-29T08:49:13Z");
+        let round_trip: ese_parser::DbInfo = serde_json::from_value(json).unwrap();
+        assert_eq!(round_trip, info);
+    }
+
+    #[test]
+    fn test_export_page_tags_csv() {
+        let jdb = init_tests(5, None);
+        let page_number = parser::jet::FixedPageNumber::Database as u32;
+        let mut csv = vec![];
+        jdb.export_page_tags_csv(page_number, &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "tag_index,flags,offset,size,is_defunct,data_hex_preview"
+        );
+        // database root page: root page header only
+        assert_eq!(lines.len(), 2);
+        let tag = jdb.get_page_tag_data(page_number, 0).unwrap();
+        let hex: String = tag.iter().map(|b| format!("{:02x}", b)).collect();
+        assert!(lines[1].starts_with("0,"));
+        assert!(lines[1].ends_with(&format!(",{},false,{}", tag.len(), hex)));
+        assert!(jdb.export_page_tags_csv(100_000, &mut vec![]).is_err());
+
+        // a leaf page of the catalog, every tag has its own data
+        let table_id = jdb.open_table("MSysObjects").unwrap();
+        let (page_number, page_tags) = jdb.iter_pages_with_data(table_id).next().unwrap().unwrap();
+        let mut csv = vec![];
+        jdb.export_page_tags_csv(page_number, &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().skip(1).collect();
+        assert_eq!(lines.len(), page_tags.len());
+        for (tag_index, line) in lines.iter().enumerate() {
+            let tag = jdb.get_page_tag_data(page_number, tag_index).unwrap();
+            let hex: String = tag.iter().take(32).map(|b| format!("{:02x}", b)).collect();
+            assert!(line.starts_with(&format!("{},", tag_index)));
+            assert!(line.ends_with(&format!(",{}", hex)), "{}", line);
+        }
+        jdb.close_table(table_id);
+    }
+
+    #[test]
+    fn test_record_page_location() {
+        let jdb = init_tests(5, None);
+        let table_id = jdb.open_table("MSysObjects").unwrap();
+        let (first_page, _) = jdb.iter_pages_with_data(table_id).next().unwrap().unwrap();
+        assert_eq!(
+            jdb.get_record_page_location(table_id).unwrap(),
+            (first_page, 1)
+        );
+        jdb.move_row(table_id, ESE_MoveNext).unwrap();
+        assert_eq!(
+            jdb.get_record_page_location(table_id).unwrap(),
+            (first_page, 2)
+        );
+        assert!(jdb.get_record_page_location(1000).is_err());
+    }
+
+    #[test]
+    fn test_lv_key_for_column() {
+        let jdb = init_tests(5, None);
+        let columns = jdb.get_columns("TestTable").unwrap();
+        let table_id = jdb.open_table("TestTable").unwrap();
+        let column = |name: &str| columns.iter().find(|x| x.name == name).unwrap().id;
+        let key = jdb
+            .get_lv_key_for_column(table_id, column("LongText"))
+            .unwrap()
+            .unwrap();
+        assert!(jdb.has_lv_key(table_id, key));
+        // the value is still readable after the key lookup
+        assert_eq!(
+            jdb.get_column(table_id, column("LongText"))
+                .unwrap()
+                .unwrap()
+                .len(),
+            8600
+        );
+        for name in &["Long", "Text", "TextDefaultValue"] {
+            assert_eq!(
+                jdb.get_lv_key_for_column(table_id, column(name)).unwrap(),
+                None
+            );
+        }
+    }
+
+    #[test]
+    fn test_get_column_reader() {
+        use std::io::Read;
+        let jdb = init_tests(5, None);
+        let columns = jdb.get_columns("TestTable").unwrap();
+        let table_id = jdb.open_table("TestTable").unwrap();
+        let column = |name: &str| columns.iter().find(|x| x.name == name).unwrap().id;
+        for name in &["LongText", "Text", "GUID"] {
+            let expected = jdb.get_column(table_id, column(name)).unwrap().unwrap();
+            let mut lv_reader = jdb
+                .get_column_reader(table_id, column(name))
+                .unwrap()
+                .unwrap();
+            let mut value = vec![];
+            let mut chunk = [0u8; 100];
+            loop {
+                let n = lv_reader.read(&mut chunk).unwrap();
+                if n == 0 {
+                    break;
+                }
+                value.extend_from_slice(&chunk[..n]);
+            }
+            assert_eq!(value, expected, "{}", name);
+        }
+        assert!(jdb
+            .get_column_reader(table_id, column("Short"))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_column_co_occurrence() {
+        let jdb = init_tests(5, None);
+        let columns = jdb.get_columns("TestTable").unwrap();
+        let id = |name: &str| columns.iter().find(|x| x.name == name).unwrap().id;
+        let table_id = jdb.open_table("TestTable").unwrap();
+        let long_text = jdb.get_column(table_id, id("LongText")).unwrap();
+        assert!(long_text.is_some());
+        let m = jdb
+            .get_column_co_occurrence_matrix("TestTable", id("Bit"), id("Short"))
+            .unwrap();
+        assert_eq!(
+            m,
+            CoOccurrenceMatrix {
+                both_present: 0,
+                only_first: 1,
+                only_second: 0,
+                neither: 0
+            }
+        );
+        let m = jdb
+            .get_column_presence_matrix("TestTable", &[id("Bit"), id("Short"), id("LongText")])
+            .unwrap();
+        assert_eq!(m, vec![vec![1, 0, 1], vec![0, 0, 0], vec![1, 0, 1]]);
+        assert!(jdb
+            .get_column_presence_matrix("TestTable", &[id("Bit"), 1000])
+            .is_err());
+
+        // the table opened above keeps its cursor and its long values
+        assert_eq!(jdb.get_column(table_id, id("LongText")).unwrap(), long_text);
+        jdb.close_table(table_id);
+        assert!(!jdb.is_table_open("TestTable"));
+    }
+
+    #[test]
+    fn test_find_tables_with_column() {
+        let jdb = init_tests(5, None);
+        assert_eq!(
+            jdb.find_tables_with_column_name("longtext").unwrap(),
+            vec!["TestTable"]
+        );
+        assert!(jdb.find_tables_with_column_name("none").unwrap().is_empty());
+        assert_eq!(
+            jdb.find_tables_with_column_type(ESE_coltypGUID).unwrap(),
+            vec![("TestTable".to_string(), vec!["GUID".to_string()])]
+        );
+    }
+
+    #[test]
+    fn test_case_insensitive_names() {
+        let jdb = init_tests(5, None);
+        let names = |table: &str| -> Vec<String> {
+            jdb.get_columns(table)
+                .unwrap()
+                .into_iter()
+                .map(|c| c.name)
+                .collect()
+        };
+        assert_eq!(names("msysobjects"), names("MSysObjects"));
+        assert_eq!(names("MSYSOBJECTS"), names("MSysObjects"));
+        let table_id = jdb.open_table("testTABLE").unwrap();
+        assert!(jdb.move_row(table_id, ESE_MoveFirst).unwrap());
+        let currency = jdb.get_column_id("TESTTABLE", "currency").unwrap();
+        assert_eq!(
+            jdb.get_column_id("TestTable", "Currency").unwrap(),
+            currency
+        );
+        assert_eq!(
+            jdb.get_column(table_id, currency).unwrap(),
+            Some(350050_i64.to_le_bytes().to_vec())
+        );
+        assert!(jdb.get_column_id("TestTable", "Currenc").is_err());
+        assert!(matches!(
+            jdb.get_column_id("Missing", "Currency"),
+            Err(error::EseError::TableNotFound(_))
+        ));
+
+        // TestTable and its GUID column renamed in place, the UTF-8 names keep their length
+        let mut data = std::fs::read("testdata/test.edb").unwrap();
+        for (from, to) in [("TestTable", "ÉstTable"), ("GUID", "GÜD")] {
+            let (from, to) = (from.as_bytes(), to.as_bytes());
+            assert_eq!(from.len(), to.len());
+            let mut i = 0;
+            while let Some(pos) = data[i..].windows(from.len()).position(|w| w == from) {
+                data[i + pos..i + pos + to.len()].copy_from_slice(to);
+                i += pos + to.len();
+            }
+        }
+        let jdb = ese_parser::EseParser::load(5, std::io::Cursor::new(data)).unwrap();
+        assert!(jdb.get_tables().unwrap().contains(&"ÉstTable".to_string()));
+        let table_id = jdb.open_table("éSTtable").unwrap();
+        assert!(jdb.move_row(table_id, ESE_MoveFirst).unwrap());
+        assert_eq!(
+            jdb.get_column_id("éstTABLE", "gÜd").unwrap(),
+            jdb.get_column_id("ÉstTable", "GÜD").unwrap()
+        );
+        assert_eq!(
+            jdb.find_tables_with_column_name("güd").unwrap(),
+            vec!["ÉstTable"]
+        );
+        assert!(jdb.open_table("EstTable").is_err());
+
+        let jdb = init_tests(5, Some("Current.mdb"));
+        for table in jdb.get_tables().unwrap() {
+            assert_eq!(
+                jdb.get_columns(&table.to_lowercase()).unwrap().len(),
+                jdb.get_columns(&table).unwrap().len(),
+                "{}",
+                table
+            );
+        }
+    }
+
+    #[cfg(feature = "ffi")]
+    #[test]
+    fn test_ffi() {
+        use ffi::*;
+        use std::ffi::{CStr, CString};
+        use std::ptr;
+        unsafe {
+            let p = ese_parser_create(5);
+            let path = CString::new("testdata/decompress_test.edb").unwrap();
+            let missing = CString::new("testdata/missing.edb").unwrap();
+            let table = CString::new("test_table").unwrap();
+            assert_eq!(
+                ese_parser_open_table(p, table.as_ptr()),
+                ESE_PARSER_INVALID_TABLE
+            );
+            assert_eq!(ese_parser_load(p, missing.as_ptr()), ESE_PARSER_ERROR);
+            assert_eq!(ese_parser_load(p, ptr::null()), ESE_PARSER_NULL_POINTER);
+            assert_eq!(
+                ese_parser_load(ptr::null_mut(), path.as_ptr()),
+                ESE_PARSER_NULL_POINTER
+            );
+            assert_eq!(ese_parser_move_row(p, 0, 1), ESE_PARSER_NOT_LOADED);
+            assert_eq!(ese_parser_load(p, path.as_ptr()), ESE_PARSER_OK);
+
+            let mut array: *mut *mut std::os::raw::c_char = ptr::null_mut();
+            let mut count = 0;
+            assert_eq!(
+                ese_parser_get_tables(p, ptr::null_mut(), &mut count),
+                ESE_PARSER_NULL_POINTER
+            );
+            assert_eq!(
+                ese_parser_get_tables(p, &mut array, &mut count),
+                ESE_PARSER_OK
+            );
+            let mut names = vec![];
+            while !(*array.add(names.len())).is_null() {
+                let s = CStr::from_ptr(*array.add(names.len()));
+                names.push(s.to_str().unwrap().to_string());
+            }
+            assert_eq!(names.len(), count as usize);
+            ese_parser_free_tables(array);
+            ese_parser_free_tables(ptr::null_mut());
+            let jdb = init_tests(5, Some("decompress_test.edb"));
+            assert_eq!(names, jdb.get_tables().unwrap());
+
+            let table_id = ese_parser_open_table(p, table.as_ptr());
+            assert_ne!(table_id, ESE_PARSER_INVALID_TABLE);
+            let ascii = jdb
+                .get_columns("test_table")
+                .unwrap()
+                .into_iter()
+                .find(|c| c.name == "compressed_ascii")
+                .unwrap();
+            let mut rows = 0;
+            let mut direction = ESE_MoveFirst as u32;
+            while ese_parser_move_row(p, table_id, direction) == 1 {
+                direction = ESE_MoveNext as u32;
+                let mut len = 0;
+                assert_eq!(
+                    ese_parser_get_column(p, table_id, ascii.id, ptr::null_mut(), &mut len),
+                    ESE_PARSER_BUFFER_TOO_SMALL
+                );
+                let mut value = vec![0u8; len as usize];
+                assert_eq!(
+                    ese_parser_get_column(p, table_id, ascii.id, value.as_mut_ptr(), &mut len),
+                    ESE_PARSER_OK
+                );
+                assert_eq!(value, format!("Record {:>10}", rows).as_bytes());
+                rows += 1;
+            }
+            assert_eq!(rows, 10);
+            assert_eq!(
+                ese_parser_get_column(p, table_id, ascii.id, ptr::null_mut(), ptr::null_mut()),
+                ESE_PARSER_NULL_POINTER
+            );
+            ese_parser_destroy(p);
+            ese_parser_destroy(ptr::null_mut());
+        }
+
+        // panics don't unwind into the caller
+        assert_eq!(
+            guard(ESE_PARSER_PANIC, || -> std::os::raw::c_int {
+                panic!("ffi test")
+            }),
+            ESE_PARSER_PANIC
+        );
+        assert_eq!(guard(ESE_PARSER_PANIC, || ESE_PARSER_OK), ESE_PARSER_OK);
+    }
+
+    #[test]
+    fn test_structured_errors() {
+        use error::EseError;
+        assert!(matches!(
+            ese_parser::EseParser::load_from_path(5, "testdata/missing.edb"),
+            Err(EseError::Io(_))
+        ));
+        let jdb = init_tests(5, None);
+        match jdb.open_table("Missing") {
+            Err(EseError::TableNotFound(table)) => assert_eq!(table, "Missing"),
+            r => panic!("unexpected result {:?}", r),
+        }
+        let table_id = jdb.open_table("TestTable").unwrap();
+        assert!(matches!(
+            jdb.get_column(table_id, 1000),
+            Err(EseError::ColumnNotFound(1000))
+        ));
+        assert_eq!(
+            jdb.get_column(table_id, 1000).unwrap_err().to_string(),
+            "column 1000 not found"
+        );
+        match jdb.get_column_id("TestTable", "Missing") {
+            Err(EseError::ColumnNameNotFound(column)) => assert_eq!(column, "Missing"),
+            r => panic!("unexpected result {:?}", r),
+        }
+        assert!(matches!(
+            jdb.get_column_storage_class("TestTable", 1000),
+            Err(EseError::ColumnNotFound(1000))
+        ));
+        let empty_db = init_tests(5, Some("Current.mdb"));
+        let empty_table = empty_db.open_table("VIRTUALMACHINES").unwrap();
+        assert!(matches!(
+            empty_db.get_record_page_location(empty_table),
+            Err(EseError::NoCursorPosition(id)) if id == empty_table
+        ));
+        assert!(matches!(
+            jdb.get_column(1000, 1),
+            Err(EseError::TableNotFound(_))
+        ));
+        assert!(matches!(
+            unsafe { jdb.read_page_as_struct::<u32>(u32::MAX, 0) },
+            Err(EseError::BadPageNumber { page: u32::MAX, .. })
+        ));
+        jdb.close_table(table_id);
+    }
+
+    #[test]
+    fn test_rows() {
+        let jdb = init_tests(5, None);
+        let columns = jdb.get_columns("MSysObjects").unwrap();
+        let name = columns.iter().find(|x| x.name == "Name").unwrap();
+        let table_id = jdb.open_table("MSysObjects").unwrap();
+        let rows = jdb.rows(table_id).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(rows.len(), 92);
+        assert!(rows.iter().all(|row| row.len() == columns.len()));
+
+        jdb.move_row(table_id, ESE_MoveFirst).unwrap();
+        for row in &rows {
+            assert_eq!(row[&name.id], jdb.get_column(table_id, name.id).unwrap());
+            jdb.move_row(table_id, ESE_MoveNext).unwrap();
+        }
+        jdb.close_table(table_id);
+
+        assert!(jdb.rows(100).next().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_iter_rows() {
+        for (db, table, count) in [
+            ("test.edb", "MSysObjects", 92),
+            ("Current.mdb", "MSysObjects", 467),
+            ("Current.mdb", "VIRTUALMACHINES", 0),
+            ("decompress_test.edb", "test_table", 10),
+        ] {
+            let jdb = init_tests(5, Some(db));
+            let table_id = jdb.open_table(table).unwrap();
+            let rows = jdb.rows(table_id).collect::<Result<Vec<_>, _>>().unwrap();
+            assert_eq!(rows.len(), count, "{}", db);
+
+            // interleaved iterators over the same table, the cursor is left on the first row
+            jdb.move_row(table_id, ESE_MoveFirst).unwrap();
+            let mut it1 = jdb.iter_rows(table).unwrap();
+            let mut it2 = jdb.iter_rows(table).unwrap();
+            let first = it2.next();
+            for (n, row) in rows.iter().enumerate() {
+                let r1 = it1.next().unwrap().unwrap();
+                for c in r1.columns() {
+                    assert_eq!(r1.get(&c.name).unwrap(), row[&c.id], "{} {}", db, c.name);
+                }
+                // the second iterator is one row ahead
+                match it2.next() {
+                    Some(r2) => {
+                        let r2 = r2.unwrap();
+                        for c in r2.columns() {
+                            assert_eq!(r2.get(&c.name).unwrap(), rows[n + 1][&c.id]);
+                        }
+                    }
+                    None => assert_eq!(n + 1, rows.len()),
+                }
+            }
+            assert!(it1.next().is_none());
+            assert!(it2.next().is_none());
+            assert_eq!(first.is_some(), count > 0);
+            if let Some(first) = first {
+                let first = first.unwrap();
+                let column = &first.columns()[0];
+                assert_eq!(
+                    first.get(&column.name).unwrap(),
+                    jdb.get_column(table_id, column.id).unwrap()
+                );
+            }
+            jdb.close_table(table_id);
+        }
+
+        let jdb = init_tests(5, None);
+        let row = jdb.iter_rows("TestTable").unwrap().next().unwrap().unwrap();
+        assert_eq!(row.get_as::<u8>("UnsignedByte").unwrap(), Some(255));
+        assert_eq!(row.get_as::<i32>("Long").unwrap(), Some(i32::MIN));
+        assert_eq!(row.get_as::<i16>("Short").unwrap(), None);
+        assert!(row.get_as::<i64>("Long").is_err());
+        assert!(matches!(
+            row.get("NoSuchColumn"),
+            Err(error::EseError::ColumnNameNotFound(name)) if name == "NoSuchColumn"
+        ));
+        assert_eq!(
+            row.get_date("DateTime").unwrap().unwrap().to_string(),
+            "2021-03-29 11:49:47 UTC"
+        );
+        let row = jdb
+            .iter_rows("MSysObjects")
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(row.get_str("Name").unwrap().unwrap(), "MSysObjects");
+        assert_eq!(row.get_str("NAME").unwrap().unwrap(), "MSysObjects");
+        assert!(jdb.iter_rows("NoSuchTable").is_err());
+    }
+
+    #[test]
+    fn test_table_checksum() {
+        let jdb = init_tests(5, None);
+        let other = init_tests(5, Some("decompress_test.edb"));
+        let checksums = |db: &ese_parser::EseParser<FileBackend>, table: &str| {
+            let table_id = db.open_table(table).unwrap();
+            let checksums = (
+                db.compute_table_checksum(table_id).unwrap(),
+                db.compute_table_sha256(table_id).unwrap(),
+            );
+            db.close_table(table_id);
+            checksums
+        };
+        let objects = checksums(&jdb, "MSysObjects");
+        assert_eq!(objects, checksums(&jdb, "MSysObjects"));
+        // the shadow catalog holds a copy of the records
+        assert_eq!(objects, checksums(&jdb, "MSysObjectsShadow"));
+        // same locales in both databases
+        assert_eq!(
+            checksums(&jdb, "MSysLocales"),
+            checksums(&other, "MSysLocales")
+        );
+        assert_ne!(objects, checksums(&other, "MSysObjects"));
+    }
+
+    #[test]
+    fn test_index_entries_for_value() {
+        for db in &["test.edb", "Current.mdb"] {
+            let jdb = init_tests(5, Some(db));
+            // secondary index entries hold the primary keys (Id index) of the rows
+            let primary_keys = jdb
+                .get_index_entries_for_value("MSysObjects", "Name", &[])
+                .unwrap();
+            let stats = jdb
+                .get_index_statistics("MSysObjects", "Name", None)
+                .unwrap();
+            assert_eq!(primary_keys.len() as u64, stats.entry_count);
+            for pk in &primary_keys {
+                assert_eq!(
+                    jdb.get_index_entries_for_value("MSysObjects", "Id", pk)
+                        .unwrap(),
+                    vec![pk.clone()]
+                );
+            }
+            // all the entries of the first table (ObjidTable is the first key column)
+            let first = jdb
+                .get_index_entries_for_value("MSysObjects", "Id", &primary_keys[0][..5])
+                .unwrap();
+            assert!(!first.is_empty() && first.len() < primary_keys.len());
+            assert!(jdb
+                .get_index_entries_for_value("MSysObjects", "Id", &[0xff; 8])
+                .unwrap()
+                .is_empty());
+            assert!(jdb
+                .get_index_entries_for_value("MSysObjects", "Wrong", &[])
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn test_get_column_mv_count() {
+        let jdb = init_tests(5, None);
+        let columns = jdb.get_columns("TestTable").unwrap();
+        let table_id = jdb.open_table("TestTable").unwrap();
+        let count = |name: &str| {
+            let column = columns.iter().find(|c| c.name == name).unwrap();
+            jdb.get_column_mv_count(table_id, column.id).unwrap()
+        };
+        assert_eq!(count("Text"), 2);
+        assert_eq!(count("LongBinary"), 2);
+        assert_eq!(count("LongText"), 1);
+        assert_eq!(count("TextDefaultValue"), 1);
+        assert_eq!(count("Long"), 1);
+        assert_eq!(count("Short"), 0);
+        jdb.close_table(table_id);
+
+        // same as reading the values until the last one
+        for db in ["test.edb", "decompress_test.edb", "Current.mdb"] {
+            let jdb = init_tests(5, Some(db));
+            for table in jdb.get_tables().unwrap() {
+                let columns = jdb.get_columns(&table).unwrap();
+                let table_id = jdb.open_table(&table).unwrap();
+                let mut has_row = jdb.move_row(table_id, ESE_MoveFirst).unwrap();
+                while has_row {
+                    for c in &columns {
+                        let mut values = 0;
+                        while jdb
+                            .get_column_mv(table_id, c.id, values + 1)
+                            .unwrap()
+                            .is_some()
+                        {
+                            values += 1;
+                        }
+                        let count = jdb.get_column_mv_count(table_id, c.id).unwrap();
+                        assert_eq!(count, values, "{} {} {}", db, table, c.name);
+                    }
+                    has_row = jdb.move_row(table_id, ESE_MoveNext).unwrap();
+                }
+                jdb.close_table(table_id);
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_row_count() {
+        let jdb = init_tests(5, None);
+        assert_eq!(jdb.get_row_count("MSysObjects").unwrap(), 92);
+        assert_eq!(jdb.get_row_count("TestTable").unwrap(), 1);
+        assert!(jdb.get_row_count("Missing").is_err());
+
+        // the cursor of an open table stays on its row
+        let columns = jdb.get_columns("MSysObjects").unwrap();
+        let name = columns.iter().find(|x| x.name == "Name").unwrap();
+        let table_id = jdb.open_table("MSysObjects").unwrap();
+        jdb.move_row(table_id, 10).unwrap();
+        let row = jdb.get_column(table_id, name.id).unwrap();
+        assert_eq!(jdb.get_row_count("MSysObjects").unwrap(), 92);
+        assert_eq!(jdb.get_column(table_id, name.id).unwrap(), row);
+        assert!(jdb.move_row(table_id, ESE_MoveNext).unwrap());
+        jdb.close_table(table_id);
+
+        for db in &["Current.mdb", "decompress_test.edb"] {
+            let jdb = init_tests(5, Some(db));
+            for table in jdb.get_tables().unwrap() {
+                let table_id = jdb.open_table(&table).unwrap();
+                let mut rows = 0;
+                let mut has_row = jdb.move_row(table_id, ESE_MoveFirst).unwrap();
+                while has_row {
+                    rows += 1;
+                    has_row = jdb.move_row(table_id, ESE_MoveNext).unwrap();
+                }
+                assert_eq!(jdb.get_row_count(&table).unwrap(), rows, "{}", table);
+            }
+        }
+        // empty table
+        assert_eq!(
+            init_tests(5, Some("Current.mdb"))
+                .get_row_count("VIRTUALMACHINES")
+                .unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_default_get_row_count() {
+        use error::EseError;
+        // an EseDb with the default methods, forwarding the required ones to EseParser
+        struct Defaults(ese_parser::EseParser<FileBackend>);
+        impl EseDb for Defaults {
+            fn error_to_string(&self, err: i32) -> String {
+                self.0.error_to_string(err)
+            }
+            fn open_table(&self, table: &str) -> Result<u64, EseError> {
+                self.0.open_table(table)
+            }
+            fn close_table(&self, table: u64) -> bool {
+                self.0.close_table(table)
+            }
+            fn get_tables(&self) -> Result<Vec<String>, EseError> {
+                self.0.get_tables()
+            }
+            fn get_columns(&self, table: &str) -> Result<Vec<ColumnInfo>, EseError> {
+                self.0.get_columns(table)
+            }
+            fn get_column_type(&self, table: u64, column: u32) -> Result<u32, EseError> {
+                self.0.get_column_type(table, column)
+            }
+            fn get_column(&self, table: u64, column: u32) -> Result<Option<Vec<u8>>, EseError> {
+                self.0.get_column(table, column)
+            }
+            fn get_column_mv(
+                &self,
+                table: u64,
+                column: u32,
+                multi_value_index: u32,
+            ) -> Result<Option<Vec<u8>>, EseError> {
+                self.0.get_column_mv(table, column, multi_value_index)
+            }
+            fn move_row(&self, table: u64, crow: i32) -> Result<bool, EseError> {
+                self.0.move_row(table, crow)
+            }
+            fn get_page_size(&self) -> u32 {
+                self.0.get_page_size()
+            }
+            fn get_database_file_size(&self) -> Result<u64, EseError> {
+                self.0.get_database_file_size()
+            }
+        }
+
+        let jdb = Defaults(init_tests(5, None));
+        assert_eq!(jdb.get_row_count("MSysObjects").unwrap(), 92);
+        assert_eq!(jdb.get_row_count("TestTable").unwrap(), 1);
+        assert!(jdb.get_row_count("Missing").is_err());
+
+        // the cursor of an open table stays on its row
+        let columns = jdb.get_columns("MSysObjects").unwrap();
+        let name = columns.iter().find(|x| x.name == "Name").unwrap();
+        let table_id = jdb.open_table("MSysObjects").unwrap();
+        jdb.move_row(table_id, 10).unwrap();
+        let row = jdb.get_column(table_id, name.id).unwrap();
+        assert_eq!(jdb.get_row_count("MSysObjects").unwrap(), 92);
+        assert_eq!(jdb.get_column(table_id, name.id).unwrap(), row);
+        assert!(jdb.0.is_table_open("MSysObjects"));
+        assert!(jdb.move_row(table_id, ESE_MoveNext).unwrap());
+        jdb.close_table(table_id);
+        assert!(!jdb.0.is_table_open("MSysObjects"));
+    }
+
+    #[test]
+    fn test_estimate_row_count() {
+        let jdb = init_tests(5, None);
+        let columns = jdb.get_columns("MSysObjects").unwrap();
+        let name = columns.iter().find(|x| x.name == "Name").unwrap();
+        let table_id = jdb.open_table("MSysObjects").unwrap();
+        jdb.move_row(table_id, 10).unwrap();
+        let row = jdb.get_column(table_id, name.id).unwrap();
+
+        let estimate = jdb.estimate_row_count("MSysObjects").unwrap();
+        assert!((92 / 4..=92 * 4).contains(&estimate), "{}", estimate);
+        assert_eq!(jdb.exact_row_count("MSysObjects").unwrap(), 92);
+        assert_eq!(jdb.get_column(table_id, name.id).unwrap(), row);
+        assert!(jdb.estimate_row_count("Missing").is_err());
+
+        // tables of a single page are counted exactly
+        for db in &["Current.mdb", "decompress_test.edb"] {
+            let jdb = init_tests(5, Some(db));
+            for table in jdb.get_tables().unwrap() {
+                let exact = jdb.exact_row_count(&table).unwrap();
+                let estimate = jdb.estimate_row_count(&table).unwrap();
+                let table_id = jdb.open_table(&table).unwrap();
+                if jdb.iter_pages_with_data(table_id).count() == 1 {
+                    assert_eq!(estimate, exact, "{}", table);
+                } else {
+                    assert!(estimate > 0, "{}", table);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_indexes() {
+        let jdb = init_tests(5, None);
+        let indexes = jdb.get_indexes("MSysObjects").unwrap();
+        let names: Vec<&str> = indexes.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, vec!["Id", "Name", "RootObjects"]);
+        let id = &indexes[0];
+        assert!(id
+            .flags
+            .contains(parser::jet::IndexFlags::Primary | parser::jet::IndexFlags::Unique));
+        assert_eq!(id.key_column_ids, vec![1, 2, 3]); // ObjidTable, Type, Id
+        assert!(id.conditional_column_ids.is_empty());
+        assert!(indexes
+            .iter()
+            .all(|i| i.father_data_page_object_identifier == 2));
+        assert_eq!(
+            jdb.get_index_statistics("MSysObjects", "Name", None)
+                .unwrap()
+                .entry_count,
+            jdb.get_index_entries_for_value("MSysObjects", "Name", &[])
+                .unwrap()
+                .len() as u64
+        );
+
+        let jdb = init_tests(5, Some("Current.mdb"));
+        let columns = jdb.get_columns("CLIENTS").unwrap();
+        let indexes = jdb.get_indexes("CLIENTS").unwrap();
+        assert_eq!(indexes.len(), 3);
+        let index = &indexes[2];
+        assert_eq!(index.name, "Address_Username_RoleGuid_TenantId_index");
+        let key_columns: Vec<&str> = index
+            .key_column_ids
+            .iter()
+            .map(|id| columns.iter().find(|c| c.id == *id).unwrap().name.as_str())
+            .collect();
+        assert_eq!(
+            key_columns,
+            vec!["Address", "AuthenticatedUserName", "RoleGuid", "TenantId"]
+        );
+        assert!(!index.flags.contains(parser::jet::IndexFlags::Primary));
+        assert!(matches!(
+            jdb.get_indexes("Missing"),
+            Err(error::EseError::TableNotFound(_))
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialize_catalog() {
+        let data = std::fs::read("testdata/Current.mdb").unwrap();
+        let reader = parser::reader::Reader::load_db(std::io::Cursor::new(data), 10).unwrap();
+        let catalog = reader.load_catalog().unwrap();
+        let json = serde_json::to_string(&catalog).unwrap();
+        let catalog: Vec<parser::jet::TableDefinition> = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&catalog).unwrap(), json);
+        let clients = catalog
+            .iter()
+            .find(|t| t.table_catalog_definition.as_ref().unwrap().name == "CLIENTS")
+            .unwrap();
+        assert_eq!(clients.index_catalog_definition_array.len(), 3);
+
+        let jdb = init_tests(5, Some("Current.mdb"));
+        let columns = jdb.get_columns("CLIENTS").unwrap();
+        let json = serde_json::to_value(&columns).unwrap();
+        assert_eq!(json[0]["name"], columns[0].name.as_str());
+        let round_trip: Vec<ColumnInfo> = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&round_trip).unwrap(), json);
+
+        let indexes = jdb.get_indexes("CLIENTS").unwrap();
+        let json = serde_json::to_string(&indexes).unwrap();
+        let round_trip: Vec<ese_parser::IndexDefinition> = serde_json::from_str(&json).unwrap();
+        assert_eq!(round_trip, indexes);
+    }
+
+    #[test]
+    fn test_get_columns_meta() {
+        let jdb = init_tests(5, None);
+        let columns = jdb.get_columns_meta("TestTable").unwrap();
+        let column = |name: &str| columns.iter().find(|c| c.name == name).unwrap();
+        let auto_inc = column("AutoInc");
+        assert_eq!(auto_inc.kind, ColumnStorageClass::Fixed);
+        assert!(auto_inc
+            .flags
+            .contains(parser::jet::ColumnFlags::Autoincrement));
+        assert!(!auto_inc.has_default_value);
+        assert_eq!(column("Binary").kind, ColumnStorageClass::Tagged);
+        let text = column("Text");
+        assert_eq!(text.kind, ColumnStorageClass::Tagged);
+        assert!(text.flags.contains(parser::jet::ColumnFlags::Multivalued));
+        assert!(column("TextDefaultValue").has_default_value);
+
+        let columns = jdb.get_columns_meta("MSysObjects").unwrap();
+        let column = |name: &str| columns.iter().find(|c| c.name == name).unwrap();
+        assert_eq!(column("Name").kind, ColumnStorageClass::Variable);
+        assert!(column("Id")
+            .flags
+            .contains(parser::jet::ColumnFlags::NotNull));
+
+        let table = jdb.get_table_meta("MSysObjects").unwrap();
+        assert_eq!(table.father_data_page_number, 4);
+        assert_eq!(table.parent_fdp, 1);
+        assert!(table.initial_number_of_pages > 0);
+        let table = jdb.get_table_meta("TestTable").unwrap();
+        assert_eq!(table.name, "TestTable");
+        assert_eq!(table.parent_fdp, 1);
+        assert!(matches!(
+            jdb.get_table_meta("NoSuchTable"),
+            Err(error::EseError::TableNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_find_row() {
+        for db in &["test.edb", "Current.mdb"] {
+            let jdb = init_tests(5, Some(db));
+            let keys = jdb
+                .get_index_entries_for_value("MSysObjects", "Id", &[])
+                .unwrap();
+            let columns = jdb.get_columns("MSysObjects").unwrap();
+            let name = columns.iter().find(|x| x.name == "Name").unwrap();
+            let table_id = jdb.open_table("MSysObjects").unwrap();
+            let mut names = vec![];
+            let mut has_row = jdb.move_row(table_id, ESE_MoveFirst).unwrap();
+            while has_row {
+                names.push(jdb.get_column(table_id, name.id).unwrap());
+                has_row = jdb.move_row(table_id, ESE_MoveNext).unwrap();
+            }
+            assert_eq!(keys.len(), names.len());
+            // index of the first key not lower than `key`
+            let nearest = |key: &[u8]| keys.iter().position(|k| k.as_slice() >= key).unwrap();
+
+            for (i, key) in keys.iter().enumerate() {
+                assert!(jdb.find_row(table_id, key).unwrap());
+                assert_eq!(jdb.get_column(table_id, name.id).unwrap(), names[i]);
+                if i + 1 < keys.len() {
+                    assert!(jdb.move_row(table_id, ESE_MoveNext).unwrap());
+                    assert_eq!(jdb.get_column(table_id, name.id).unwrap(), names[i + 1]);
+                }
+            }
+
+            let key = &keys[keys.len() / 2];
+            // longer than the stored key: positioned on the next record
+            let mut longer = key.clone();
+            longer.push(0xff);
+            assert!(!jdb.find_row(table_id, &longer).unwrap());
+            let next = nearest(&longer);
+            assert_eq!(jdb.get_column(table_id, name.id).unwrap(), names[next]);
+            assert!(jdb.move_row(table_id, ESE_MoveNext).unwrap());
+            assert_eq!(jdb.get_column(table_id, name.id).unwrap(), names[next + 1]);
+
+            // shorter than the common key prefix of the leaf page
+            let shorter = &key[..2];
+            assert!(!jdb.find_row(table_id, shorter).unwrap());
+            assert_eq!(
+                jdb.get_column(table_id, name.id).unwrap(),
+                names[nearest(shorter)]
+            );
+
+            // before the first and after the last record
+            assert!(!jdb.find_row(table_id, &[]).unwrap());
+            assert_eq!(jdb.get_column(table_id, name.id).unwrap(), names[0]);
+            assert!(!jdb.find_row(table_id, &[0xff; 16]).unwrap());
+            assert_eq!(
+                jdb.get_column(table_id, name.id).unwrap(),
+                names[names.len() - 1]
+            );
+            assert!(!jdb.move_row(table_id, ESE_MoveNext).unwrap());
+            jdb.close_table(table_id);
+        }
+    }
+
+    #[test]
+    fn test_get_record_at_page() {
+        let jdb = init_tests(5, None);
+        let table_id = jdb.open_table("MSysObjects").unwrap();
+        let columns = jdb.get_columns("MSysObjects").unwrap();
+        let name = columns.iter().find(|x| x.name == "Name").unwrap();
+        jdb.move_row(table_id, ESE_MoveFirst).unwrap();
+        let (page, tag) = jdb.get_record_page_location(table_id).unwrap();
+        let first_name = jdb.get_column(table_id, name.id).unwrap();
+        jdb.move_row(table_id, 5).unwrap();
+        let location = jdb.get_record_page_location(table_id).unwrap();
+        let current_name = jdb.get_column(table_id, name.id).unwrap();
+
+        let row = jdb.get_record_at_page(table_id, page, tag).unwrap();
+        assert_eq!(row.len(), columns.len());
+        assert_eq!(row["Name"], first_name);
+        // the cursor is left where it was
+        assert_eq!(jdb.get_record_page_location(table_id).unwrap(), location);
+        assert_eq!(jdb.get_column(table_id, name.id).unwrap(), current_name);
+
+        let database_page = parser::jet::FixedPageNumber::Database as u32;
+        assert!(jdb.get_record_at_page(table_id, database_page, 1).is_err());
+        jdb.close_table(table_id);
+    }
+
+    #[test]
+    fn test_column_page_coverage() {
+        let jdb = init_tests(5, None);
+        let columns = jdb.get_columns("MSysObjects").unwrap();
+        let id = |name: &str| columns.iter().find(|x| x.name == name).unwrap().id;
+        let table_id = jdb.open_table("MSysObjects").unwrap();
+        assert_eq!(
+            jdb.get_column_page_coverage(table_id, id("Name")).unwrap(),
+            1.0
+        );
+        assert_eq!(
+            jdb.get_column_page_coverage(table_id, id("CallbackData"))
+                .unwrap(),
+            0.0
+        );
+        let histogram = jdb
+            .get_column_page_coverage_histogram(table_id, id("DefaultValue"))
+            .unwrap();
+        assert!(!histogram.is_empty());
+        let pages: Vec<u32> = jdb
+            .iter_pages_with_data(table_id)
+            .map(|p| p.unwrap().0)
+            .collect();
+        assert_eq!(histogram.iter().map(|(p, _)| *p).collect::<Vec<_>>(), pages);
+        assert!(
+            jdb.get_column_page_coverage(table_id, id("DefaultValue"))
+                .unwrap()
+                > 0.0
+        );
+        jdb.close_table(table_id);
+    }
+
+    #[test]
+    fn test_column_entropy() {
+        let jdb = init_tests(5, None);
+        let columns = jdb.get_columns("MSysObjects").unwrap();
+        let id = |name: &str| columns.iter().find(|x| x.name == name).unwrap().id;
+        let table_id = jdb.open_table("MSysObjects").unwrap();
+        // far fewer distinct types than names
+        let names = jdb.get_column_entropy(table_id, id("Name")).unwrap();
+        let types = jdb.get_column_entropy(table_id, id("Type")).unwrap();
+        assert!(types > 0.0 && types < names && names <= (92f64).log2());
+        // no callbacks
+        assert_eq!(
+            jdb.get_column_entropy(table_id, id("CallbackData"))
+                .unwrap(),
+            0.0
+        );
+        jdb.close_table(table_id);
+    }
+
+    #[test]
+    fn test_column_values_sorted() {
+        let jdb = init_tests(5, None);
+        let columns = jdb.get_columns("MSysObjects").unwrap();
+        let name = columns.iter().find(|x| x.name == "Name").unwrap();
+        let table_id = jdb.open_table("MSysObjects").unwrap();
+        assert!(jdb.move_row(table_id, 5).unwrap());
+        let current = jdb.get_column(table_id, name.id).unwrap();
+        let values = jdb
+            .get_column_values_sorted("MSysObjects", name.id, None)
+            .unwrap();
+        assert_eq!(values.len(), 92);
+        assert!(values.windows(2).all(|w| w[0] <= w[1]));
+        assert!(jdb
+            .get_column_values_sorted("MSysObjects", name.id, Some(100))
+            .is_err());
+        // the cursor of the caller is kept, after the error too
+        assert_eq!(jdb.get_column(table_id, name.id).unwrap(), current);
+        jdb.close_table(table_id);
+        assert!(!jdb.is_table_open("MSysObjects"));
+
+        // null first
+        let columns = jdb.get_columns("TestTable").unwrap();
+        let short = columns.iter().find(|x| x.name == "Short").unwrap();
+        assert_eq!(
+            jdb.get_column_values_sorted("TestTable", short.id, None)
+                .unwrap(),
+            vec![None]
+        );
+    }
+
+    #[test]
+    fn test_walk_btree() {
+        use error::EseError;
+        use parser::btree::{BTreeVisitor, VisitDecision};
+        use parser::ese_db::PageTag;
+        use parser::jet::{DbPage, FixedPageNumber, PageFlags};
+
+        #[derive(Default)]
+        struct Counter {
+            branch_pages: usize,
+            leaf_pages: usize,
+            leaf_tags: usize,
+            stop_at_leaf: bool,
+            fail_at_third_tag: bool,
+        }
+        impl BTreeVisitor for Counter {
+            fn visit_branch_page(&mut self, _page: &DbPage) -> Result<VisitDecision, EseError> {
+                self.branch_pages += 1;
+                Ok(VisitDecision::Continue)
+            }
+            fn visit_leaf_page(&mut self, _page: &DbPage) -> Result<VisitDecision, EseError> {
+                self.leaf_pages += 1;
+                if self.stop_at_leaf {
+                    Ok(VisitDecision::Stop)
+                } else {
+                    Ok(VisitDecision::Continue)
+                }
+            }
+            fn visit_tag(
+                &mut self,
+                page: &DbPage,
+                _tag: &PageTag,
+                _i: usize,
+            ) -> Result<VisitDecision, EseError> {
+                if page.flags().contains(PageFlags::IS_LEAF) {
+                    self.leaf_tags += 1;
+                    if self.leaf_tags == 3 && self.fail_at_third_tag {
+                        return Err(EseError::new("third tag"));
+                    }
+                }
+                Ok(VisitDecision::Continue)
+            }
+        }
+
+        let jdb = init_tests(5, None);
+        // every catalog entry is a row of MSysObjects
+        let mut counter = Counter::default();
+        jdb.walk_btree(FixedPageNumber::Catalog as u32, &mut counter)
+            .unwrap();
+        assert!(counter.branch_pages > 0);
+        assert!(counter.leaf_pages > 1);
+        let counts = jdb.get_catalog_entry_count();
+        assert_eq!(
+            counter.leaf_tags,
+            counts.tables + counts.columns + counts.indexes + counts.long_values
+        );
+
+        let mut counter = Counter {
+            stop_at_leaf: true,
+            ..Default::default()
+        };
+        jdb.walk_btree(FixedPageNumber::Catalog as u32, &mut counter)
+            .unwrap();
+        assert_eq!(counter.leaf_pages, 1);
+        assert_eq!(counter.leaf_tags, 0);
+
+        // the error of a visitor ends the walk
+        let mut counter = Counter {
+            fail_at_third_tag: true,
+            ..Default::default()
+        };
+        let e = jdb
+            .walk_btree(FixedPageNumber::Catalog as u32, &mut counter)
+            .unwrap_err();
+        assert_eq!(e.to_string(), "third tag");
+        assert_eq!(counter.leaf_tags, 3);
+    }
+
+    #[test]
+    fn test_column_value_length_distribution() {
+        let jdb = init_tests(5, None);
+        let columns = jdb.get_columns("MSysObjects").unwrap();
+        let table_id = jdb.open_table("MSysObjects").unwrap();
+        let column = |name: &str| columns.iter().find(|x| x.name == name).unwrap().id;
+
+        let dist = jdb
+            .get_column_value_length_distribution(table_id, column("Type"), None)
+            .unwrap();
+        assert_eq!((dist.min, dist.max, dist.p50, dist.p99), (2, 2, 2, 2));
+        assert_eq!(dist.mean, 2.0);
+        assert_eq!((dist.null_count, dist.non_null_count), (0, 92));
+
+        let mut lengths = vec![];
+        let mut has_row = jdb.move_row(table_id, ESE_MoveFirst).unwrap();
+        while has_row {
+            if let Some(v) = jdb.get_column(table_id, column("Name")).unwrap() {
+                lengths.push(v.len());
+            }
+            has_row = jdb.move_row(table_id, ESE_MoveNext).unwrap();
+        }
+        lengths.sort_unstable();
+        let dist = jdb
+            .get_column_value_length_distribution(table_id, column("Name"), None)
+            .unwrap();
+        assert_eq!(dist.non_null_count, lengths.len() as u64);
+        assert_eq!(dist.min, lengths[0]);
+        assert_eq!(dist.max, *lengths.last().unwrap());
+        assert_eq!(dist.p50, lengths[(lengths.len() - 1) / 2]);
+        assert!(dist.p50 <= dist.p90 && dist.p90 <= dist.p99 && dist.p99 <= dist.max);
+        let mean = lengths.iter().sum::<usize>() as f64 / lengths.len() as f64;
+        assert!((dist.mean - mean).abs() < 1e-9);
+
+        let dist = jdb
+            .get_column_value_length_distribution(table_id, column("Stats"), Some(10))
+            .unwrap();
+        assert_eq!((dist.null_count, dist.non_null_count), (10, 0));
+        assert_eq!(dist.max, 0);
+    }
+
+    #[test]
+    fn test_close_table_by_name() {
+        let jdb = init_tests(5, None);
+        assert!(!jdb.is_table_open("TestTable"));
+        jdb.open_table("TestTable").unwrap();
+        assert!(jdb.is_table_open("TestTable"));
+        assert!(!jdb.is_table_open("MSysObjects"));
+        jdb.close_table_by_name("TestTable").unwrap();
+        assert!(!jdb.is_table_open("TestTable"));
+        assert!(jdb.close_table_by_name("NoSuchTable").is_err());
+        assert!(!jdb.is_table_open("NoSuchTable"));
+    }
+
+    #[test]
+    fn test_column_value_frequency() {
+        let jdb = init_tests(5, None);
+        let columns = jdb.get_columns("MSysObjids").unwrap();
+        let table_id = jdb.open_table("MSysObjids").unwrap();
+        let objid_table = columns.iter().find(|x| x.name == "objidTable").unwrap();
+        let count = |value: &[u8], limit| {
+            jdb.get_column_value_frequency(table_id, objid_table.id, value, limit)
+                .unwrap()
+        };
+        assert_eq!(count(&[2, 0, 0, 0], None), 3);
+        assert_eq!(count(&[2, 0, 0, 0], Some(2)), 2);
+        assert_eq!(count(&[8, 0, 0, 0], None), 2);
+        assert_eq!(count(&[1, 0, 0, 0], None), 0);
+        assert_eq!(count(&[], None), 0);
+
+        // multi-value column
+        let columns = jdb.get_columns("TestTable").unwrap();
+        let table_id = jdb.open_table("TestTable").unwrap();
+        let text = columns.iter().find(|x| x.name == "Text").unwrap();
+        let hello = jdb.get_column_mv(table_id, text.id, 2).unwrap().unwrap();
+        assert_eq!(jdb.get_column_mv(table_id, text.id, 3).unwrap(), None);
+        assert_eq!(
+            jdb.get_column_value_frequency(table_id, text.id, &hello, None)
+                .unwrap(),
+            1
+        );
+        let short = columns.iter().find(|x| x.name == "Short").unwrap();
+        assert_eq!(
+            jdb.get_column_value_frequency(table_id, short.id, &[], None)
+                .unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_page_and_file_size() {
+        for db in &["test.edb", "decompress_test.edb", "Current.mdb"] {
+            let jdb = init_tests(5, Some(db));
+            let page_size = jdb.get_page_size();
+            assert!([2048, 4096, 8192, 16384, 32768].contains(&page_size));
+            let file_size = jdb.get_database_file_size().unwrap();
+            let path = ["testdata", db].join("/");
+            assert_eq!(file_size, std::fs::metadata(path).unwrap().len());
+            assert_eq!(file_size % page_size as u64, 0);
+        }
+    }
 }
@@ -0,0 +1,269 @@
+//parquet.rs
+use crate::error::EseError;
+use crate::ese_trait::*;
+use crate::value::Value;
+use ::parquet::basic::{LogicalType, Repetition, TimeUnit, Type as PhysicalType};
+use ::parquet::data_type::{
+    BoolType, ByteArray, ByteArrayType, DoubleType, FixedLenByteArray, FixedLenByteArrayType,
+    FloatType, Int32Type, Int64Type,
+};
+use ::parquet::file::properties::WriterProperties;
+use ::parquet::file::writer::SerializedFileWriter;
+use ::parquet::format::MicroSeconds;
+use ::parquet::schema::types::Type;
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+// rows buffered in memory before a row group is written
+const ROW_GROUP_SIZE: usize = 64 * 1024;
+
+fn parquet_err(e: ::parquet::errors::ParquetError) -> EseError {
+    EseError::new(format!("parquet: {}", e))
+}
+
+fn int(bit_width: i8, is_signed: bool) -> Option<LogicalType> {
+    Some(LogicalType::Integer {
+        bit_width,
+        is_signed,
+    })
+}
+
+fn parquet_type(column: &ColumnInfo, repeated: bool) -> Result<Type, EseError> {
+    let (physical_type, logical_type) = match column.typ {
+        ESE_coltypBit => (PhysicalType::BOOLEAN, None),
+        ESE_coltypUnsignedByte => (PhysicalType::INT32, int(8, false)),
+        ESE_coltypShort => (PhysicalType::INT32, int(16, true)),
+        ESE_coltypUnsignedShort => (PhysicalType::INT32, int(16, false)),
+        ESE_coltypLong => (PhysicalType::INT32, int(32, true)),
+        ESE_coltypUnsignedLong => (PhysicalType::INT32, int(32, false)),
+        ESE_coltypCurrency | ESE_coltypLongLong => (PhysicalType::INT64, int(64, true)),
+        ESE_coltypUnsignedLongLong => (PhysicalType::INT64, int(64, false)),
+        ESE_coltypIEEESingle => (PhysicalType::FLOAT, None),
+        ESE_coltypIEEEDouble => (PhysicalType::DOUBLE, None),
+        ESE_coltypDateTime => (
+            PhysicalType::INT64,
+            Some(LogicalType::Timestamp {
+                is_adjusted_to_u_t_c: true,
+                unit: TimeUnit::MICROS(MicroSeconds {}),
+            }),
+        ),
+        ESE_coltypText | ESE_coltypLongText => {
+            (PhysicalType::BYTE_ARRAY, Some(LogicalType::String))
+        }
+        ESE_coltypGUID => (PhysicalType::FIXED_LEN_BYTE_ARRAY, None),
+        _ => (PhysicalType::BYTE_ARRAY, None),
+    };
+    let repetition = if repeated {
+        Repetition::REPEATED
+    } else {
+        Repetition::OPTIONAL
+    };
+    let mut builder = Type::primitive_type_builder(&column.name, physical_type)
+        .with_repetition(repetition)
+        .with_logical_type(logical_type);
+    if column.typ == ESE_coltypGUID {
+        builder = builder.with_length(16);
+    }
+    builder.build().map_err(parquet_err)
+}
+
+// values of a column chunk
+enum Values {
+    Bool(Vec<bool>),
+    Int32(Vec<i32>),
+    Int64(Vec<i64>),
+    Float(Vec<f32>),
+    Double(Vec<f64>),
+    Bytes(Vec<ByteArray>),
+    Fixed(Vec<FixedLenByteArray>),
+}
+
+struct ColumnChunk {
+    values: Values,
+    def_levels: Vec<i16>,
+    rep_levels: Vec<i16>,
+}
+
+impl ColumnChunk {
+    fn new(column: &ColumnInfo) -> Self {
+        let values = match column.typ {
+            ESE_coltypBit => Values::Bool(vec![]),
+            ESE_coltypUnsignedByte
+            | ESE_coltypShort
+            | ESE_coltypUnsignedShort
+            | ESE_coltypLong
+            | ESE_coltypUnsignedLong => Values::Int32(vec![]),
+            ESE_coltypCurrency
+            | ESE_coltypLongLong
+            | ESE_coltypUnsignedLongLong
+            | ESE_coltypDateTime => Values::Int64(vec![]),
+            ESE_coltypIEEESingle => Values::Float(vec![]),
+            ESE_coltypIEEEDouble => Values::Double(vec![]),
+            ESE_coltypGUID => Values::Fixed(vec![]),
+            _ => Values::Bytes(vec![]),
+        };
+        ColumnChunk {
+            values,
+            def_levels: vec![],
+            rep_levels: vec![],
+        }
+    }
+
+    fn push(&mut self, column: &ColumnInfo, v: &[u8]) -> Result<(), EseError> {
+        // invalid characters of text values are replaced, the export goes on
+        let value = Value::from_column(column, v, true)?;
+        match (&mut self.values, value) {
+            (Values::Bool(values), Value::Bool(v)) => values.push(v),
+            (Values::Int32(values), Value::U8(v)) => values.push(v as i32),
+            (Values::Int32(values), Value::I16(v)) => values.push(v as i32),
+            (Values::Int32(values), Value::U16(v)) => values.push(v as i32),
+            (Values::Int32(values), Value::I32(v)) => values.push(v),
+            // the unsigned 32-bit integer logical type keeps the bits
+            (Values::Int32(values), Value::U32(v)) => values.push(v as i32),
+            (Values::Int64(values), Value::I64(v) | Value::Currency(v)) => values.push(v),
+            (Values::Int64(values), Value::U64(v)) => values.push(v as i64),
+            (Values::Int64(values), Value::DateTime(dt)) => values.push(dt.timestamp_micros()),
+            (Values::Float(values), Value::F32(v)) => values.push(v),
+            (Values::Double(values), Value::F64(v)) => values.push(v),
+            (Values::Bytes(values), Value::Text(s)) => values.push(ByteArray::from(s.as_str())),
+            (Values::Bytes(values), Value::Binary(v)) => values.push(ByteArray::from(v)),
+            (Values::Fixed(values), Value::Guid(g)) => {
+                values.push(FixedLenByteArray::from(g.0.to_vec()))
+            }
+            (_, value) => {
+                return Err(EseError::new(format!(
+                    "column {}: wrong value {:?}",
+                    column.name, value
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    fn write(
+        &mut self,
+        writer: &mut ::parquet::file::writer::SerializedColumnWriter,
+        repeated: bool,
+    ) -> Result<(), EseError> {
+        let def = Some(&self.def_levels[..]);
+        let rep = if repeated {
+            Some(&self.rep_levels[..])
+        } else {
+            None
+        };
+        match &self.values {
+            Values::Bool(v) => writer.typed::<BoolType>().write_batch(v, def, rep),
+            Values::Int32(v) => writer.typed::<Int32Type>().write_batch(v, def, rep),
+            Values::Int64(v) => writer.typed::<Int64Type>().write_batch(v, def, rep),
+            Values::Float(v) => writer.typed::<FloatType>().write_batch(v, def, rep),
+            Values::Double(v) => writer.typed::<DoubleType>().write_batch(v, def, rep),
+            Values::Bytes(v) => writer.typed::<ByteArrayType>().write_batch(v, def, rep),
+            Values::Fixed(v) => writer
+                .typed::<FixedLenByteArrayType>()
+                .write_batch(v, def, rep),
+        }
+        .map_err(parquet_err)?;
+        Ok(())
+    }
+}
+
+// tagged columns holding more than one value in any row
+fn find_multi_valued_columns<D: EseDb + ?Sized>(
+    db: &D,
+    table_id: u64,
+    columns: &[ColumnInfo],
+) -> Result<Vec<bool>, EseError> {
+    let mut repeated = vec![false; columns.len()];
+    let mut has_row = db.move_row(table_id, ESE_MoveFirst)?;
+    while has_row {
+        for (i, c) in columns.iter().enumerate() {
+            if !repeated[i] && c.storage_class() == ColumnStorageClass::Tagged {
+                repeated[i] = db.get_column_mv(table_id, c.id, 2)?.is_some();
+            }
+        }
+        has_row = db.move_row(table_id, ESE_MoveNext)?;
+    }
+    Ok(repeated)
+}
+
+/// Writes `table` into a Parquet file at `path`.
+/// Multi-valued columns are written as repeated fields. Returns the number of written rows.
+/// The table is closed on errors too.
+pub fn export_table<D: EseDb + ?Sized>(db: &D, table: &str, path: &Path) -> Result<u64, EseError> {
+    let columns = db.get_columns(table)?;
+    let table_id = db.open_table(table)?;
+    let rows = write_table(db, table, table_id, &columns, path);
+    db.close_table(table_id);
+    rows
+}
+
+fn write_table<D: EseDb + ?Sized>(
+    db: &D,
+    table: &str,
+    table_id: u64,
+    columns: &[ColumnInfo],
+    path: &Path,
+) -> Result<u64, EseError> {
+    let repeated = find_multi_valued_columns(db, table_id, columns)?;
+
+    let fields = columns
+        .iter()
+        .zip(&repeated)
+        .map(|(c, &r)| parquet_type(c, r).map(Arc::new))
+        .collect::<Result<Vec<_>, _>>()?;
+    let schema = Type::group_type_builder(table)
+        .with_fields(fields)
+        .build()
+        .map_err(parquet_err)?;
+    let file = File::create(path)
+        .map_err(|e| EseError::new(format!("can't create {}: {}", path.display(), e)))?;
+    let mut writer = SerializedFileWriter::new(
+        file,
+        Arc::new(schema),
+        Arc::new(WriterProperties::builder().build()),
+    )
+    .map_err(parquet_err)?;
+
+    let mut rows = 0u64;
+    let mut has_row = db.move_row(table_id, ESE_MoveFirst)?;
+    while has_row {
+        let mut chunks: Vec<ColumnChunk> = columns.iter().map(ColumnChunk::new).collect();
+        let mut group_rows = 0;
+        while has_row && group_rows < ROW_GROUP_SIZE {
+            for ((c, chunk), &r) in columns.iter().zip(&mut chunks).zip(&repeated) {
+                let mut count = 0;
+                while let Some(v) = db.get_column_mv(table_id, c.id, count + 1)? {
+                    chunk.push(c, &v)?;
+                    chunk.def_levels.push(1);
+                    chunk.rep_levels.push(if count == 0 { 0 } else { 1 });
+                    count += 1;
+                    if !r {
+                        break;
+                    }
+                }
+                if count == 0 {
+                    // null
+                    chunk.def_levels.push(0);
+                    chunk.rep_levels.push(0);
+                }
+            }
+            group_rows += 1;
+            has_row = db.move_row(table_id, ESE_MoveNext)?;
+        }
+
+        let mut row_group = writer.next_row_group().map_err(parquet_err)?;
+        for (chunk, &r) in chunks.iter_mut().zip(&repeated) {
+            let mut column_writer = row_group
+                .next_column()
+                .map_err(parquet_err)?
+                .ok_or_else(|| EseError::new("parquet: missing column writer"))?;
+            chunk.write(&mut column_writer, r)?;
+            column_writer.close().map_err(parquet_err)?;
+        }
+        row_group.close().map_err(parquet_err)?;
+        rows += group_rows as u64;
+    }
+    writer.close().map_err(parquet_err)?;
+    Ok(rows)
+}
@@ -0,0 +1,79 @@
+//backend.rs
+use crate::error::EseError;
+use std::fs::File;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Random access storage of a database: a file, a buffer, a remote blob...
+/// `Reader` reads the pages with `read_at`, `size` is called once when the database is loaded.
+pub trait ReaderBackend: Send {
+    /// Fills `buf` with the bytes at `offset`, reading past the end is an error.
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<(), EseError>;
+    fn size(&self) -> Result<u64, EseError>;
+}
+
+impl ReaderBackend for Box<dyn ReaderBackend> {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<(), EseError> {
+        (**self).read_at(offset, buf)
+    }
+
+    fn size(&self) -> Result<u64, EseError> {
+        (**self).size()
+    }
+}
+
+/// Any `Read + Seek` source, e.g. `Cursor<Vec<u8>>` or `Cursor<&[u8]>`.
+pub struct CursorBackend<T: Read + Seek + Send> {
+    source: Mutex<T>,
+}
+
+impl<T: Read + Seek + Send> CursorBackend<T> {
+    pub fn new(source: T) -> Self {
+        CursorBackend {
+            source: Mutex::new(source),
+        }
+    }
+}
+
+impl<T: Read + Seek + Send> ReaderBackend for CursorBackend<T> {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<(), EseError> {
+        let mut source = self
+            .source
+            .lock()
+            .map_err(|_| EseError::new("backend source lock poisoned"))?;
+        source.seek(SeekFrom::Start(offset))?;
+        source.read_exact(buf)?;
+        Ok(())
+    }
+
+    fn size(&self) -> Result<u64, EseError> {
+        let mut source = self
+            .source
+            .lock()
+            .map_err(|_| EseError::new("backend source lock poisoned"))?;
+        Ok(source.seek(SeekFrom::End(0))?)
+    }
+}
+
+/// Database held in memory, see `Reader::from_memory`.
+pub type MemoryBackend<D> = CursorBackend<Cursor<D>>;
+
+/// Database file, read the same way `Reader::load_db` reads a `File`.
+pub struct FileBackend(CursorBackend<File>);
+
+impl FileBackend {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, EseError> {
+        Ok(FileBackend(CursorBackend::new(File::open(path)?)))
+    }
+}
+
+impl ReaderBackend for FileBackend {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<(), EseError> {
+        self.0.read_at(offset, buf)
+    }
+
+    fn size(&self) -> Result<u64, EseError> {
+        self.0.size()
+    }
+}
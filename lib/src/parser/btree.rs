@@ -0,0 +1,150 @@
+//btree.rs
+use crate::error::EseError;
+use std::collections::BTreeSet;
+
+use crate::parser::backend::ReaderBackend;
+use crate::parser::ese_db::PageTag;
+use crate::parser::jet::{self, DbPage};
+use crate::parser::reader::Reader;
+
+/// Levels of a B-tree at most, deeper trees are corrupt (or crafted).
+pub const MAX_BTREE_DEPTH: usize = 64;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum VisitDecision {
+    Continue,
+    // on a page: don't visit its tags (and children), on a branch tag: don't descend into the child,
+    // on a leaf tag: don't visit the rest of the page
+    SkipSubtree,
+    Stop,
+}
+
+/// Callbacks of `walk_btree`, called in key order (depth first).
+/// Tag 0 (root header or common key) and defunct tags aren't visited.
+/// The walk stops at the first error returned by a callback and returns it.
+pub trait BTreeVisitor {
+    fn visit_branch_page(&mut self, _page: &DbPage) -> Result<VisitDecision, EseError> {
+        Ok(VisitDecision::Continue)
+    }
+
+    fn visit_leaf_page(&mut self, _page: &DbPage) -> Result<VisitDecision, EseError> {
+        Ok(VisitDecision::Continue)
+    }
+
+    fn visit_tag(
+        &mut self,
+        _page: &DbPage,
+        _tag: &PageTag,
+        _tag_index: usize,
+    ) -> Result<VisitDecision, EseError> {
+        Ok(VisitDecision::Continue)
+    }
+}
+
+// what the walk reports to its callback, along with the depth of the page (0 on the root page)
+enum Visit<'a> {
+    Page(&'a DbPage),
+    Tag(&'a DbPage, &'a PageTag, usize),
+}
+
+/// Walks the B-tree rooted at `page_number`.
+pub fn walk_btree<T: ReaderBackend>(
+    reader: &Reader<T>,
+    page_number: u32,
+    visitor: &mut dyn BTreeVisitor,
+) -> Result<(), EseError> {
+    walk(reader, page_number, &mut |visit, _depth| match visit {
+        Visit::Page(page) if page.flags().contains(jet::PageFlags::IS_LEAF) => {
+            visitor.visit_leaf_page(page)
+        }
+        Visit::Page(page) => visitor.visit_branch_page(page),
+        Visit::Tag(page, tag, tag_index) => visitor.visit_tag(page, tag, tag_index),
+    })
+}
+
+/// Calls `visitor` with the page, the tag and the depth (0 on the root page) of every leaf entry
+/// of the B-tree rooted at `page_number`, in key order. Defunct tags aren't visited,
+/// the walk stops at the first error returned by `visitor`.
+pub fn walk_btree_leaf_tags<T, F>(
+    reader: &Reader<T>,
+    page_number: u32,
+    visitor: &mut F,
+) -> Result<(), EseError>
+where
+    T: ReaderBackend,
+    F: FnMut(&DbPage, &PageTag, usize) -> Result<(), EseError>,
+{
+    walk(reader, page_number, &mut |visit, depth| {
+        if let Visit::Tag(page, tag, _) = visit {
+            if page.flags().contains(jet::PageFlags::IS_LEAF) {
+                visitor(page, tag, depth)?;
+            }
+        }
+        Ok(VisitDecision::Continue)
+    })
+}
+
+// The walker of all the B-trees: depth first, without recursion, failing on child page loops
+// and trees deeper than MAX_BTREE_DEPTH. The stack holds the pages being walked with their next tag index.
+fn walk<T: ReaderBackend>(
+    reader: &Reader<T>,
+    page_number: u32,
+    callback: &mut dyn FnMut(Visit, usize) -> Result<VisitDecision, EseError>,
+) -> Result<(), EseError> {
+    let mut visited_pages = BTreeSet::new();
+    let mut stack: Vec<(DbPage, usize)> = vec![];
+    let mut child_page_number = Some(page_number);
+    loop {
+        if let Some(page_number) = child_page_number.take() {
+            if !visited_pages.insert(page_number) {
+                return Err(EseError::new(format!(
+                    "Child page loop detected at page number {}, visited pages: {:?}",
+                    page_number, visited_pages
+                )));
+            }
+            if stack.len() == MAX_BTREE_DEPTH {
+                return Err(EseError::new(format!(
+                    "page {} is deeper than {} levels in the B-tree",
+                    page_number, MAX_BTREE_DEPTH
+                )));
+            }
+            let db_page = DbPage::new(reader, page_number)?;
+            match callback(Visit::Page(&db_page), stack.len())? {
+                VisitDecision::Continue => stack.push((db_page, 1)),
+                VisitDecision::SkipSubtree => {}
+                VisitDecision::Stop => return Ok(()),
+            }
+        }
+
+        let depth = stack.len().saturating_sub(1);
+        let (db_page, next_tag_index) = match stack.last_mut() {
+            Some(top) => top,
+            None => return Ok(()),
+        };
+        let tag_index = *next_tag_index;
+        *next_tag_index += 1;
+        let tag = match db_page.page_tags.get(tag_index) {
+            Some(tag) => tag,
+            None => {
+                stack.pop();
+                continue;
+            }
+        };
+        if tag.flags().intersects(jet::PageTagFlags::FLAG_IS_DEFUNCT) {
+            continue;
+        }
+        let is_leaf = db_page.flags().contains(jet::PageFlags::IS_LEAF);
+        match callback(Visit::Tag(db_page, tag, tag_index), depth)? {
+            VisitDecision::Continue => {}
+            VisitDecision::SkipSubtree if is_leaf => {
+                stack.pop();
+                continue;
+            }
+            VisitDecision::SkipSubtree => continue,
+            VisitDecision::Stop => return Ok(()),
+        }
+        if !is_leaf {
+            child_page_number = Some(reader.page_tag_get_branch_child_page_number(db_page, tag)?);
+        }
+    }
+}
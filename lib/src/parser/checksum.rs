@@ -0,0 +1,99 @@
+//checksum.rs
+use crate::error::EseError;
+use byteorder::*;
+use std::mem;
+
+use crate::parser::ese_db::*;
+
+// larger pages keep a checksum per quarter of the page
+const MAX_SINGLE_CHECKSUM_PAGE_SIZE: usize = 8 * 1024;
+
+#[derive(Debug)]
+pub enum ChecksumResult {
+    Ok(u32),
+    Mismatch {
+        page_number: u32,
+        // checksum block of the page, pages larger than 8K have 4 blocks
+        block: usize,
+        stored: u64,
+        computed: u64,
+    },
+    Unreadable {
+        page_number: u32,
+        error: EseError,
+    },
+}
+
+/// What to do with a page failing its checksum when it is loaded
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ChecksumMode {
+    #[default]
+    Ignore,
+    Warn,
+    Strict,
+}
+
+/// XOR checksum used by the file header and the pages before the new record format
+pub fn xor_checksum(data: &[u8], seed: u32) -> u32 {
+    data.chunks_exact(mem::size_of::<u32>())
+        .fold(seed, |crc, val| crc ^ LittleEndian::read_u32(val))
+}
+
+/// ECC and XOR checksums of a page block, as stored in the page header:
+/// ECC in the upper 32 bits, XOR (seeded with the page number) in the lower 32 bits.
+/// The first `skip` bytes of the block (the stored checksum) aren't covered.
+pub fn ecc_checksum(block: &[u8], skip: usize, page_number: u32) -> u64 {
+    let mut xor = page_number;
+    // XOR of the bit numbers of all set bits, locates a single flipped bit
+    let mut set_bits = 0u32;
+    let mut odd = false;
+    for (i, val) in block
+        .chunks_exact(mem::size_of::<u32>())
+        .enumerate()
+        .skip(skip / mem::size_of::<u32>())
+    {
+        let mut val = LittleEndian::read_u32(val);
+        xor ^= val;
+        while val != 0 {
+            set_bits ^= i as u32 * 32 + val.trailing_zeros();
+            odd = !odd;
+            val &= val - 1;
+        }
+    }
+    let mask = (block.len() * 8 - 1) as u32;
+    let inverted_bits = if odd { set_bits ^ mask } else { set_bits };
+    let ecc = (inverted_bits << 16) | set_bits;
+    ((ecc as u64) << 32) | xor as u64
+}
+
+/// Returns (stored, computed) checksum pairs of the page, one per checksum block
+pub fn page_checksums(page: &[u8], page_number: u32, format_revision: u32) -> Vec<(u64, u64)> {
+    if format_revision < ESEDB_FORMAT_REVISION_NEW_RECORD_FORMAT {
+        let stored = LittleEndian::read_u32(page);
+        let computed = xor_checksum(&page[4..], ESEDB_FILE_SIGNATURE);
+        return vec![(stored as u64, computed as u64)];
+    }
+    let stored = LittleEndian::read_u64(page);
+    if format_revision < ESEDB_FORMAT_REVISION_EXTENDED_PAGE_HEADER
+        || page.len() <= MAX_SINGLE_CHECKSUM_PAGE_SIZE
+    {
+        return vec![(stored, ecc_checksum(page, 8, page_number))];
+    }
+
+    // checksums of the blocks following the first one are in the extended header
+    let ext_offset = mem::size_of::<PageHeader0x11>() + mem::size_of::<PageHeaderCommon>();
+    page.chunks_exact(page.len() / 4)
+        .enumerate()
+        .map(|(i, block)| {
+            if i == 0 {
+                (stored, ecc_checksum(block, 8, page_number))
+            } else {
+                let offset = ext_offset + (i - 1) * mem::size_of::<u64>();
+                (
+                    LittleEndian::read_u64(&page[offset..]),
+                    ecc_checksum(block, 0, page_number),
+                )
+            }
+        })
+        .collect()
+}
@@ -1,7 +1,36 @@
-use simple_error::SimpleError;
+use crate::error::EseError;
+
+/// Compression scheme of a column value, stored in the upper 5 bits of its first byte
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CompressionScheme {
+    None,
+    SevenBitAscii,
+    SevenBitUnicode,
+    Xpress,
+    Scrub,
+    Xpress9,
+    Xpress10,
+    Unknown(u8),
+}
+
+impl CompressionScheme {
+    pub fn from_data(compressed_data: &[u8]) -> Option<Self> {
+        let identifier = compressed_data.first()? >> 3;
+        Some(match identifier {
+            0 => CompressionScheme::None,
+            1 => CompressionScheme::SevenBitAscii,
+            2 => CompressionScheme::SevenBitUnicode,
+            3 => CompressionScheme::Xpress,
+            4 => CompressionScheme::Scrub,
+            5 => CompressionScheme::Xpress9,
+            6 => CompressionScheme::Xpress10,
+            _ => CompressionScheme::Unknown(identifier),
+        })
+    }
+}
 
 fn seven_bit_decompress_get_size(compressed_data: &[u8]) -> usize {
-    if compressed_data.is_empty() || compressed_data[0] >> 3 > 2
+    if compressed_data.len() < 2 || compressed_data[0] >> 3 > 2
     /* NOT 7BITASCII and NOT 7BITUNICODE */
     {
         return 0;
@@ -11,14 +40,14 @@ fn seven_bit_decompress_get_size(compressed_data: &[u8]) -> usize {
     cbit_total / 7
 }
 
-fn seven_bit_decompress_buf(compressed_data: &[u8]) -> Result<Vec<u8>, SimpleError> {
+fn seven_bit_decompress_buf(compressed_data: &[u8]) -> Result<Vec<u8>, EseError> {
     if compressed_data.is_empty() || compressed_data[0] == 0x18 {
-        return Err(SimpleError::new("compressed data is too short"));
+        return Err(EseError::new("compressed data is too short"));
     }
 
     let decompressed_size = seven_bit_decompress_get_size(compressed_data);
     if decompressed_size == 0 {
-        return Err(SimpleError::new("compressed data size is 0"));
+        return Err(EseError::new("compressed data size is 0"));
     }
 
     let mut uncompressed_data = Vec::<u8>::with_capacity(decompressed_size as usize);
@@ -92,25 +121,16 @@ fn test_7bit_decompression() {
 }
 
 pub fn decompress_size(compressed_data: &[u8]) -> usize {
-    if compressed_data.is_empty() {
-        return 0;
-    }
-    let identifier = compressed_data[0] >> 3;
-    match identifier {
-        1 => {
-            // 7bit ASCII
-            seven_bit_decompress_get_size(compressed_data)
-        }
-        2 => {
-            // 7bit UNICODE
+    match CompressionScheme::from_data(compressed_data) {
+        Some(CompressionScheme::SevenBitAscii) => seven_bit_decompress_get_size(compressed_data),
+        Some(CompressionScheme::SevenBitUnicode) => {
             seven_bit_decompress_get_size(compressed_data) * 2
         }
-        3 => {
-            // LZXPRESS
+        Some(CompressionScheme::Xpress) => {
             if compressed_data.len() < 3 {
                 return 0;
             }
-            u16::from_ne_bytes([compressed_data[1], compressed_data[2]]) as usize
+            u16::from_le_bytes([compressed_data[1], compressed_data[2]]) as usize
         }
         _ => 0,
     }
@@ -119,18 +139,12 @@ pub fn decompress_size(compressed_data: &[u8]) -> usize {
 pub fn decompress_buf(
     compressed_data: &[u8],
     decompressed_size: usize,
-) -> Result<Vec<u8>, SimpleError> {
-    if compressed_data.is_empty() {
-        return Err(SimpleError::new("compressed data is too short"));
-    }
-    let identifier = compressed_data[0] >> 3;
-    match identifier {
-        1 => {
-            // 7bit ASCII
-            seven_bit_decompress_buf(compressed_data)
-        }
-        2 => {
-            // 7bit UNICODE
+) -> Result<Vec<u8>, EseError> {
+    let scheme = CompressionScheme::from_data(compressed_data)
+        .ok_or_else(|| EseError::new("compressed data is too short"))?;
+    match scheme {
+        CompressionScheme::SevenBitAscii => seven_bit_decompress_buf(compressed_data),
+        CompressionScheme::SevenBitUnicode => {
             let decompressed_buf = seven_bit_decompress_buf(compressed_data)?;
             let mut buf = Vec::<u8>::with_capacity(decompressed_buf.len() * 2);
             for c in decompressed_buf {
@@ -139,21 +153,27 @@ pub fn decompress_buf(
             }
             Ok(buf)
         }
-        3 => {
-            // LZXPRESS
+        CompressionScheme::Xpress => {
             if compressed_data.len() < 3 {
-                return Err(SimpleError::new("compressed data is too short"));
-            }
-            match lz77_decompress(&compressed_data[3..], decompressed_size) {
-                Ok(unc) => Ok(unc),
-                Err(e) => {
-                    let s = format!("{:?}", e);
-                    Err(SimpleError::new(s))
-                }
+                return Err(EseError::new("compressed data is too short"));
             }
+            lz77_decompress(&compressed_data[3..], decompressed_size)
         }
-        _ => {
-            return Err(SimpleError::new(format!("bad identifier: {}", identifier)));
+        _ => Err(EseError::new(format!(
+            "unsupported compression scheme: {:?}",
+            scheme
+        ))),
+    }
+}
+
+/// Decompresses a value stored with the compressed flag.
+/// Values of the `None` scheme are returned as they are.
+pub fn decompress_data(data: Vec<u8>) -> Result<Vec<u8>, EseError> {
+    match CompressionScheme::from_data(&data) {
+        None | Some(CompressionScheme::None) => Ok(data),
+        Some(_) => {
+            let decompressed_size = decompress_size(&data);
+            decompress_buf(&data, decompressed_size)
         }
     }
 }
@@ -193,7 +213,7 @@ pub fn ms_impl_decompress_size(v: &[u8]) -> usize {
 
 #[allow(dead_code)]
 #[cfg(all(feature = "nt_comparison", target_os = "windows"))]
-pub fn ms_impl_decompress_buf(v: &[u8], decompressed_size: usize) -> Result<Vec<u8>, SimpleError> {
+pub fn ms_impl_decompress_buf(v: &[u8], decompressed_size: usize) -> Result<Vec<u8>, EseError> {
     const JET_errSuccess: u32 = 0;
     let mut buf = Vec::<u8>::with_capacity(decompressed_size);
     unsafe {
@@ -211,7 +231,7 @@ pub fn ms_impl_decompress_buf(v: &[u8], decompressed_size: usize) -> Result<Vec<
     };
     debug_assert!(decompressed_size == decompressed as usize && decompressed as usize == buf.len());
     if res != JET_errSuccess {
-        return Err(SimpleError::new(format!("Decompress failed. Err {}", res)));
+        return Err(EseError::new(format!("Decompress failed. Err {}", res)));
     }
     Ok(buf)
 }
@@ -231,7 +251,7 @@ fn test_lzxpress_decompression() {
 
 // https://docs.microsoft.com/en-us/openspecs/windows_protocols/ms-xca/a8b7cb0a-92a6-4187-a23b-5e14273b96f8
 // 2.4.4
-fn lz77_decompress(in_buf: &[u8], decompress_size: usize) -> Result<Vec<u8>, SimpleError> {
+fn lz77_decompress(in_buf: &[u8], decompress_size: usize) -> Result<Vec<u8>, EseError> {
     let mut in_pos: usize = 0;
     let mut last_len: usize = 0;
     let mut flags: u32 = 0;
@@ -242,7 +262,7 @@ fn lz77_decompress(in_buf: &[u8], decompress_size: usize) -> Result<Vec<u8>, Sim
     while in_pos < in_buf.len() {
         if flag_count == 0 {
             if (in_pos + 3) >= in_buf.len() {
-                return Err(SimpleError::new("index out of bounds"));
+                return Err(EseError::new("index out of bounds"));
             }
 
             flags = u32::from_le_bytes([
@@ -259,7 +279,10 @@ fn lz77_decompress(in_buf: &[u8], decompress_size: usize) -> Result<Vec<u8>, Sim
 
         if (flags & (1 << flag_count)) == 0 {
             if in_pos >= in_buf.len() {
-                return Err(SimpleError::new("index out of bounds"));
+                return Err(EseError::new("index out of bounds"));
+            }
+            if out_buf.len() >= decompress_size {
+                return Err(EseError::new("decompressed data is bigger than expected"));
             }
             out_buf.push(in_buf[in_pos]);
 
@@ -267,8 +290,8 @@ fn lz77_decompress(in_buf: &[u8], decompress_size: usize) -> Result<Vec<u8>, Sim
         } else {
             if in_pos == in_buf.len() {
                 break;
-            } else if (in_pos + 1) > in_buf.len() {
-                return Err(SimpleError::new("index out of bounds"));
+            } else if (in_pos + 1) >= in_buf.len() {
+                return Err(EseError::new("index out of bounds"));
             }
 
             let mut length = u16::from_le_bytes([in_buf[in_pos], in_buf[in_pos + 1]]) as usize;
@@ -280,7 +303,7 @@ fn lz77_decompress(in_buf: &[u8], decompress_size: usize) -> Result<Vec<u8>, Sim
             if length == 7 {
                 if last_len == 0 {
                     if in_pos >= in_buf.len() {
-                        return Err(SimpleError::new("index out of bounds"));
+                        return Err(EseError::new("index out of bounds"));
                     }
 
                     length = (in_buf[in_pos] % 16).into();
@@ -288,7 +311,7 @@ fn lz77_decompress(in_buf: &[u8], decompress_size: usize) -> Result<Vec<u8>, Sim
                     in_pos += 1;
                 } else {
                     if last_len >= in_buf.len() {
-                        return Err(SimpleError::new("index out of bounds"));
+                        return Err(EseError::new("index out of bounds"));
                     }
 
                     length = (in_buf[last_len] / 16).into();
@@ -297,7 +320,7 @@ fn lz77_decompress(in_buf: &[u8], decompress_size: usize) -> Result<Vec<u8>, Sim
 
                 if length == 15 {
                     if in_pos >= in_buf.len() {
-                        return Err(SimpleError::new("index out of bounds"));
+                        return Err(EseError::new("index out of bounds"));
                     }
 
                     length = in_buf[in_pos].into();
@@ -305,13 +328,16 @@ fn lz77_decompress(in_buf: &[u8], decompress_size: usize) -> Result<Vec<u8>, Sim
 
                     if length == 255 {
                         if (in_pos + 1) >= in_buf.len() {
-                            return Err(SimpleError::new("index out of bounds"));
+                            return Err(EseError::new("index out of bounds"));
                         }
 
                         length = u16::from_le_bytes([in_buf[in_pos], in_buf[in_pos + 1]]) as usize;
                         in_pos += 2;
 
                         if length == 0 {
+                            if (in_pos + 3) >= in_buf.len() {
+                                return Err(EseError::new("index out of bounds"));
+                            }
                             length = u32::from_le_bytes([
                                 in_buf[in_pos],
                                 in_buf[in_pos + 1],
@@ -322,7 +348,7 @@ fn lz77_decompress(in_buf: &[u8], decompress_size: usize) -> Result<Vec<u8>, Sim
                         }
 
                         if length < 15 + 7 {
-                            return Err(SimpleError::new("corrupted data"));
+                            return Err(EseError::new("corrupted data"));
                         }
                         length -= 15 + 7;
                     }
@@ -332,9 +358,12 @@ fn lz77_decompress(in_buf: &[u8], decompress_size: usize) -> Result<Vec<u8>, Sim
             }
             length += 3;
 
+            if out_buf.len() + length > decompress_size {
+                return Err(EseError::new("decompressed data is bigger than expected"));
+            }
             for _ in 0..length {
                 if offset > out_buf.len() {
-                    return Err(SimpleError::new("corrupted data"));
+                    return Err(EseError::new("corrupted data"));
                 }
 
                 out_buf.push(out_buf[out_buf.len() - offset]);
@@ -345,6 +374,186 @@ fn lz77_decompress(in_buf: &[u8], decompress_size: usize) -> Result<Vec<u8>, Sim
     Ok(out_buf)
 }
 
+#[cfg(test)]
+fn seven_bit_compress(data: &[u8], scheme: u8) -> Vec<u8> {
+    let mut out = vec![0u8];
+    let mut bit = 0;
+    for &c in data {
+        for b in 0..7 {
+            if bit % 8 == 0 {
+                out.push(0);
+            }
+            let last = out.len() - 1;
+            out[last] |= ((c >> b) & 1) << (bit % 8);
+            bit += 1;
+        }
+    }
+    let cbit_final = bit - (out.len() - 2) * 8;
+    out[0] = (scheme << 3) | (cbit_final - 1) as u8;
+    out
+}
+
+// greedy MS-XCA plain LZ77 compression
+#[cfg(test)]
+fn lz77_compress(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x18, data.len() as u8, (data.len() >> 8) as u8];
+    // flags of the next 32 literals or matches (1 for a match) precede them
+    let mut flags_pos = out.len();
+    out.extend_from_slice(&[0; 4]);
+    let mut flags: u32 = 0;
+    let mut flag_count = 0;
+    let mut state = (flags_pos, flags, flag_count);
+    let mut push_flag = |out: &mut Vec<u8>, flag: u32| {
+        flags = (flags << 1) | flag;
+        flag_count += 1;
+        if flag_count == 32 {
+            out[flags_pos..flags_pos + 4].copy_from_slice(&flags.to_le_bytes());
+            flags_pos = out.len();
+            out.extend_from_slice(&[0; 4]);
+            flag_count = 0;
+        }
+        (flags_pos, flags, flag_count)
+    };
+
+    let mut last_half_byte = 0;
+    let mut pos = 0;
+    while pos < data.len() {
+        let (mut best_len, mut best_offset) = (0, 0);
+        for offset in 1..=pos.min(8192) {
+            let len = (0..data.len() - pos)
+                .take_while(|&i| data[pos + i] == data[pos + i - offset])
+                .count();
+            if len > best_len {
+                best_len = len;
+                best_offset = offset;
+            }
+        }
+        if best_len < 3 {
+            out.push(data[pos]);
+            state = push_flag(&mut out, 0);
+            pos += 1;
+            continue;
+        }
+        let mut length = best_len - 3;
+        let token = ((best_offset - 1) << 3) as u16;
+        if length < 7 {
+            out.extend_from_slice(&(token | length as u16).to_le_bytes());
+        } else {
+            out.extend_from_slice(&(token | 7).to_le_bytes());
+            length -= 7;
+            if last_half_byte == 0 {
+                last_half_byte = out.len();
+                out.push(length.min(15) as u8);
+            } else {
+                out[last_half_byte] |= (length.min(15) as u8) << 4;
+                last_half_byte = 0;
+            }
+            if length >= 15 {
+                length -= 15;
+                if length < 255 {
+                    out.push(length as u8);
+                } else {
+                    out.push(255);
+                    out.extend_from_slice(&((length + 15 + 7) as u16).to_le_bytes());
+                }
+            }
+        }
+        state = push_flag(&mut out, 1);
+        pos += best_len;
+    }
+    // the rest of the flags are set, the decompression stops on a match past the end
+    let (flags_pos, flags, flag_count) = state;
+    let flags = ((flags as u64) << (32 - flag_count)) as u32 | (u32::MAX >> flag_count);
+    out[flags_pos..flags_pos + 4].copy_from_slice(&flags.to_le_bytes());
+    out
+}
+
+#[cfg(test)]
+fn test_data(size: usize) -> Vec<u8> {
+    let text = b"The quick brown fox jumps over the lazy dog. ";
+    (0..size)
+        .map(|i| {
+            if i % 97 < 50 {
+                text[i % text.len()]
+            } else {
+                (i * 7 % 128) as u8
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn test_decompression_round_trip() {
+    for size in [1, 7, 8, 31, 32, 33, 100, 1000, 4000, 20000] {
+        let data = test_data(size);
+
+        let compressed = seven_bit_compress(&data, 1);
+        assert_eq!(
+            CompressionScheme::from_data(&compressed),
+            Some(CompressionScheme::SevenBitAscii)
+        );
+        assert_eq!(decompress_size(&compressed), size);
+        assert_eq!(decompress_data(compressed).unwrap(), data);
+
+        let compressed = seven_bit_compress(&data, 2);
+        let unicode: Vec<u8> = data.iter().flat_map(|&c| [c, 0]).collect();
+        assert_eq!(decompress_data(compressed).unwrap(), unicode);
+
+        let compressed = lz77_compress(&data);
+        assert_eq!(
+            CompressionScheme::from_data(&compressed),
+            Some(CompressionScheme::Xpress)
+        );
+        assert_eq!(decompress_size(&compressed), size);
+        assert_eq!(decompress_data(compressed).unwrap(), data);
+    }
+    // long matches, with extra length bytes
+    for size in [300, 5000, 60000] {
+        let data = vec![b'a'; size];
+        assert_eq!(decompress_data(lz77_compress(&data)).unwrap(), data);
+    }
+}
+
+#[test]
+fn test_malformed_compressed_data() {
+    let samples = vec![
+        seven_bit_compress(&test_data(100), 1),
+        seven_bit_compress(&test_data(100), 2),
+        lz77_compress(&test_data(1000)),
+        lz77_compress(&[b'a'; 5000]),
+    ];
+    // truncated or corrupted data fail without panicking
+    for sample in samples {
+        for len in 0..sample.len() {
+            let _ = decompress_data(sample[..len].to_vec());
+        }
+        for i in 0..sample.len() {
+            for x in [0x01, 0x80, 0xff] {
+                let mut corrupted = sample.clone();
+                corrupted[i] ^= x;
+                let _ = decompress_data(corrupted);
+            }
+        }
+    }
+    assert!(decompress_data(vec![0x18, 0x10, 0x00]).unwrap().is_empty());
+    assert!(decompress_buf(&[0x18, 0xff, 0xff, 0, 0, 0, 0x80, 0, 0], 0xffff).is_err());
+    assert!(decompress_buf(&[], 0).is_err());
+
+    // schemes without a decoder
+    for first_byte in [0x20, 0x28, 0x30, 0xf8] {
+        assert!(decompress_data(vec![first_byte, 1, 2, 3]).is_err());
+    }
+    assert_eq!(
+        CompressionScheme::from_data(&[0x28]),
+        Some(CompressionScheme::Xpress9)
+    );
+    assert_eq!(
+        CompressionScheme::from_data(&[0xf8]),
+        Some(CompressionScheme::Unknown(0x1f))
+    );
+    assert_eq!(decompress_data(vec![0x00, 1, 2]).unwrap(), vec![0x00, 1, 2]);
+}
+
 use std::convert::TryInto;
 
 pub trait FromBytes {
@@ -7,25 +7,24 @@
     clippy::cast_ptr_alignment
 )]
 
+use crate::error::EseError;
 use crate::ese_parser::*;
 use crate::ese_trait::*;
 use crate::esent::ese_api::*;
-use simple_error::SimpleError;
+use crate::parser::backend::FileBackend;
 use std::cell::RefCell;
-use std::fs::File;
-use std::io::BufReader;
 use std::path::Path;
 
 const CACHE_SIZE_ENTRIES: usize = 10;
 
 pub struct EseBoth {
     api: EseAPI,
-    parser: EseParser<BufReader<File>>,
+    parser: EseParser<FileBackend>,
     opened_tables: RefCell<Vec<(u64, u64)>>,
 }
 
 impl EseBoth {
-    pub fn load_from_path(dbpath: impl AsRef<Path> + Clone) -> Result<Self, SimpleError> {
+    pub fn load_from_path(dbpath: impl AsRef<Path> + Clone) -> Result<Self, EseError> {
         let api = EseAPI::load_from_path(dbpath.clone())?;
         let parser = EseParser::load_from_path(CACHE_SIZE_ENTRIES, dbpath)?;
         Ok(EseBoth {
@@ -41,15 +40,15 @@ impl EseDb for EseBoth {
         "unused".to_string()
     }
 
-    fn open_table(&self, table: &str) -> Result<u64, SimpleError> {
+    fn open_table(&self, table: &str) -> Result<u64, EseError> {
         let api_table = self
             .api
             .open_table(table)
-            .map_err(|e| SimpleError::new(format!("EseAPI::open_table failed: {}", e)))?;
+            .map_err(|e| EseError::new(format!("EseAPI::open_table failed: {}", e)))?;
         let parser_table = self
             .parser
             .open_table(table)
-            .map_err(|e| SimpleError::new(format!("EseParser::open_table failed: {}", e)))?;
+            .map_err(|e| EseError::new(format!("EseParser::open_table failed: {}", e)))?;
         let mut v = self.opened_tables.borrow_mut();
         v.push((api_table, parser_table));
         Ok((v.len() - 1) as u64)
@@ -70,37 +69,37 @@ impl EseDb for EseBoth {
         true
     }
 
-    fn get_tables(&self) -> Result<Vec<String>, SimpleError> {
+    fn get_tables(&self) -> Result<Vec<String>, EseError> {
         let api_tables = self
             .api
             .get_tables()
-            .map_err(|e| SimpleError::new(format!("EseAPI::get_tables failed: {}", e)))?;
+            .map_err(|e| EseError::new(format!("EseAPI::get_tables failed: {}", e)))?;
         let parser_tables = self
             .parser
             .get_tables()
-            .map_err(|e| SimpleError::new(format!("EseParser::get_tables failed: {}", e)))?;
+            .map_err(|e| EseError::new(format!("EseParser::get_tables failed: {}", e)))?;
         if api_tables.len() != parser_tables.len() {
-            return Err(SimpleError::new(format!("get_tables() have a different number of tables: EseAPI tables:\n{:?}\n not equal to EseParser:\n{:?}\n",
+            return Err(EseError::new(format!("get_tables() have a different number of tables: EseAPI tables:\n{:?}\n not equal to EseParser:\n{:?}\n",
                 api_tables, parser_tables)));
         }
         for i in 0..api_tables.len() {
             if api_tables[i] != parser_tables[i] {
-                return Err(SimpleError::new(format!("get_tables() have a difference: EseAPI table:\n{:?}\n not equal to EseParser:\n{:?}\n",
+                return Err(EseError::new(format!("get_tables() have a difference: EseAPI table:\n{:?}\n not equal to EseParser:\n{:?}\n",
                     api_tables[i], parser_tables[i])));
             }
         }
         Ok(api_tables)
     }
 
-    fn get_columns(&self, table: &str) -> Result<Vec<ColumnInfo>, SimpleError> {
+    fn get_columns(&self, table: &str) -> Result<Vec<ColumnInfo>, EseError> {
         let api_columns = self
             .api
             .get_columns(table)
-            .map_err(|e| SimpleError::new(format!("EseAPI::get_columns failed: {}", e)))?;
+            .map_err(|e| EseError::new(format!("EseAPI::get_columns failed: {}", e)))?;
         let parser_columns = self
             .parser
             .get_columns(table)
-            .map_err(|e| SimpleError::new(format!("EseParser::get_columns failed: {}", e)))?;
+            .map_err(|e| EseError::new(format!("EseParser::get_columns failed: {}", e)))?;
         if api_columns.len() != parser_columns.len() {
             if api_columns.len() > parser_columns.len()
                 && (table == "MSysObjects" || table == "MSysObjectsShadow")
@@ -110,7 +109,7 @@ impl EseDb for EseBoth {
                 // https://github.com/libyal/libesedb/blob/main/documentation/Extensible%20Storage%20Engine%20(ESE)%20Database%20File%20(EDB)%20format.asciidoc#catalog
                 return Ok(parser_columns);
             }
-            return Err(SimpleError::new(format!("get_columns({}) have a different number of columns: EseAPI columns:\n{:?}\n not equal to EseParser:\n{:?}\n",
+            return Err(EseError::new(format!("get_columns({}) have a different number of columns: EseAPI columns:\n{:?}\n not equal to EseParser:\n{:?}\n",
                 table, api_columns, parser_columns)));
         }
         for i in 0..api_columns.len() {
@@ -119,7 +118,7 @@ impl EseDb for EseBoth {
                 let c2 = &parser_columns[i];
                 if c1.name != c2.name || c1.typ != c2.typ || c1.cbmax != c2.cbmax || c1.cp != c2.cp
                 {
-                    return Err(SimpleError::new(format!("get_columns({}) have a difference: EseAPI table:\n{:?}\n not equal to EseParser:\n{:?}\n",
+                    return Err(EseError::new(format!("get_columns({}) have a difference: EseAPI table:\n{:?}\n not equal to EseParser:\n{:?}\n",
                         table, api_columns[i], parser_columns[i])));
                 }
             }
@@ -128,12 +127,12 @@ impl EseDb for EseBoth {
         Ok(parser_columns)
     }
 
-    fn move_row(&self, table: u64, crow: i32) -> Result<bool, SimpleError> {
+    fn move_row(&self, table: u64, crow: i32) -> Result<bool, EseError> {
         let (api_table, parser_table) = self.opened_tables.borrow()[table as usize];
         let r1 = self.api.move_row(api_table, crow)?;
         let r2 = self.parser.move_row(parser_table, crow)?;
         if r1 != r2 {
-            Err(SimpleError::new(format!(
+            Err(EseError::new(format!(
                 "move_row return result different: EseAPI {} != EseParser {}",
                 r1, r2
             )))
@@ -142,25 +141,80 @@ impl EseDb for EseBoth {
         }
     }
 
-    fn get_column_str(
-        &self,
-        table: u64,
-        column: u32,
-        cp: u16,
-    ) -> Result<Option<String>, SimpleError> {
+    fn get_page_size(&self) -> u32 {
+        let s1 = self.api.get_page_size();
+        let s2 = self.parser.get_page_size();
+        debug_assert_eq!(
+            s1, s2,
+            "get_page_size return result different: EseAPI != EseParser"
+        );
+        s2
+    }
+
+    fn get_database_file_size(&self) -> Result<u64, EseError> {
+        let s1 = self.api.get_database_file_size()?;
+        let s2 = self.parser.get_database_file_size()?;
+        if s1 != s2 {
+            return Err(EseError::new(format!(
+                "get_database_file_size return result different: EseAPI {} != EseParser {}",
+                s1, s2
+            )));
+        }
+        Ok(s1)
+    }
+
+    fn get_row_count(&self, table: &str) -> Result<u64, EseError> {
+        let r1 = self.api.get_row_count(table)?;
+        let r2 = self.parser.get_row_count(table)?;
+        if r1 != r2 {
+            return Err(EseError::new(format!(
+                "get_row_count return result different: EseAPI {} != EseParser {}",
+                r1, r2
+            )));
+        }
+        Ok(r1)
+    }
+
+    fn get_column_mv_count(&self, table: u64, column: u32) -> Result<u32, EseError> {
+        let (api_table, parser_table) = self.opened_tables.borrow()[table as usize];
+        let c1 = self.api.get_column_mv_count(api_table, column)?;
+        let c2 = self.parser.get_column_mv_count(parser_table, column)?;
+        if c1 != c2 {
+            return Err(EseError::new(format!(
+                "get_column_mv_count return result different: EseAPI {} != EseParser {}",
+                c1, c2
+            )));
+        }
+        Ok(c1)
+    }
+
+    fn get_column_type(&self, table: u64, column: u32) -> Result<u32, EseError> {
+        let (api_table, parser_table) = self.opened_tables.borrow()[table as usize];
+        let t1 = self.api.get_column_type(api_table, column)?;
+        let t2 = self.parser.get_column_type(parser_table, column)?;
+        if t1 != t2 {
+            return Err(EseError::new(format!(
+                "get_column_type return result different: EseAPI {} != EseParser {}",
+                t1, t2
+            )));
+        }
+        Ok(t1)
+    }
+
+    fn get_column_str(&self, table: u64, column: u32, cp: u16) -> Result<Option<String>, EseError> {
         let (api_table, parser_table) = self.opened_tables.borrow()[table as usize];
         let s1 = self.api.get_column_str(api_table, column, cp)?;
         match self.parser.get_column_str(parser_table, column, cp) {
             Ok(s2) => {
                 if s1 != s2 {
-                    return Err(SimpleError::new(format!(
+                    return Err(EseError::new(format!(
                         r"table {}, column({}) EseAPI column '{:?}' not equal to EseParser '{:?}'",
                         table, column, s1, s2
                     )));
                 }
             },
             Err(e) => {
-                return Err(SimpleError::new(format!(
+                return Err(EseError::new(format!(
                     r"table {}, column({}) EseParser failed with error '{:?}', but EseAPI returned '{:?}'",
                     table, column, e, s1
                 )));
@@ -169,20 +223,20 @@ impl EseDb for EseBoth {
         Ok(s1)
     }
 
-    fn get_column(&self, table: u64, column: u32) -> Result<Option<Vec<u8>>, SimpleError> {
+    fn get_column(&self, table: u64, column: u32) -> Result<Option<Vec<u8>>, EseError> {
         let (api_table, parser_table) = self.opened_tables.borrow()[table as usize];
         let s1 = self.api.get_column(api_table, column)?;
         match self.parser.get_column(parser_table, column) {
             Ok(s2) => {
                 if s1 != s2 {
-                    return Err(SimpleError::new(format!(
+                    return Err(EseError::new(format!(
                         r"table {}, column({}) EseAPI column '{:?}' not equal to EseParser '{:?}'",
                         table, column, s1, s2
                     )));
                 }
             },
             Err(e) => {
-                return Err(SimpleError::new(format!(
+                return Err(EseError::new(format!(
                     r"table {}, column({}) EseParser failed with error '{:?}', but EseAPI returned '{:?}'",
                     table, column, e, s1
                 )));
@@ -196,20 +250,20 @@ impl EseDb for EseBoth {
         table: u64,
         column: u32,
         multi_value_index: u32,
-    ) -> Result<Option<Vec<u8>>, SimpleError> {
+    ) -> Result<Option<Vec<u8>>, EseError> {
         let (api_table, parser_table) = self.opened_tables.borrow()[table as usize];
         let s1 = self.api.get_column_mv(api_table, column, multi_value_index)?;
         match self.parser.get_column_mv(parser_table, column, multi_value_index) {
             Ok(s2) => {
                 if s1 != s2 {
-                    return Err(SimpleError::new(format!(
+                    return Err(EseError::new(format!(
                         r"table {}, column({}) EseAPI column '{:?}' not equal to EseParser '{:?}'",
                         table, column, s1, s2
                     )));
                 }
             },
             Err(e) => {
-                return Err(SimpleError::new(format!(
+                return Err(EseError::new(format!(
                     r"table {}, column({}) EseParser failed with error '{:?}', but EseAPI returned '{:?}'",
                     table, column, e, s1
                 )));
@@ -2,9 +2,9 @@
 
 use crate::impl_read_struct;
 use crate::impl_read_struct_buffer;
+use crate::parser::backend::ReaderBackend;
 use crate::parser::jet;
 use crate::parser::jet::*;
-use crate::parser::reader::ReadSeek;
 use nom::number::complete::le_u32;
 use nom_derive::*;
 
@@ -1,13 +1,15 @@
 //jet.rs
 #![allow(non_camel_case_types, dead_code)]
+use crate::error::EseError;
 use crate::impl_read_struct;
+use crate::parser::backend::ReaderBackend;
 use crate::parser::ese_db;
 use crate::parser::ese_db::*;
-use crate::parser::reader::{ReadSeek, Reader};
+use crate::parser::reader::Reader;
 use bitflags::bitflags;
-use chrono::naive::NaiveTime;
+use chrono::naive::{NaiveDate, NaiveTime};
+use chrono::{TimeZone, Utc};
 use nom_derive::*;
-use simple_error::SimpleError;
 use std::{fmt, mem};
 use strum::Display;
 
@@ -143,7 +145,31 @@ bitflags! {
     }
 }
 
-#[derive(Copy, Clone, Display, Debug, Nom)]
+bitflags! {
+    // DataDefinition::flags of the indexes
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct IndexFlags : u32 {
+        const Unique                  = 0x0001;
+        const AllowAllNulls           = 0x0002;
+        const AllowFirstNull          = 0x0004;
+        const AllowSomeNulls          = 0x0008;
+        const NoNullSeg               = 0x0010;
+        const Primary                 = 0x0020;
+        const LocaleSet               = 0x0040;
+        const Multivalued             = 0x0080;
+        const TemplateIndex           = 0x0100;
+        const DerivedIndex            = 0x0200;
+        const LocalizedText           = 0x0400;
+        const SortNullsHigh           = 0x0800;
+        const UnicodeFixupOn          = 0x1000;
+        const CrossProduct            = 0x2000;
+        const DisallowTruncation      = 0x4000;
+        const NestedTable             = 0x8000;
+    }
+}
+
+#[derive(Copy, Clone, Display, Debug, PartialEq, Eq, Nom)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u32)]
 pub enum DbState {
     impossible = 0,
@@ -211,6 +237,18 @@ pub struct DateTime {
 }
 impl_read_struct!(DateTime);
 
+impl DateTime {
+    /// The year is stored as an offset from 1900, `None` for an unset (zeroed) or invalid time.
+    /// Times which are not flagged as UTC are local times of the machine which wrote them,
+    /// they are returned as is.
+    pub fn to_utc(&self) -> Option<chrono::DateTime<Utc>> {
+        let date =
+            NaiveDate::from_ymd_opt(1900 + self.year as i32, self.month as u32, self.day as u32)?;
+        let time = date.and_hms_opt(self.hours as u32, self.minutes as u32, self.seconds as u32)?;
+        Some(Utc.from_utc_datetime(&time))
+    }
+}
+
 #[derive(Copy, Clone, Default, Debug, Nom)]
 #[repr(C)]
 pub struct Signature {
@@ -253,10 +291,10 @@ pub struct DbPage {
 }
 
 impl DbPage {
-    pub fn new<T: ReadSeek>(
+    pub fn new<T: ReaderBackend>(
         reader: &Reader<T>,
         page_number: uint32_t,
-    ) -> Result<DbPage, SimpleError> {
+    ) -> Result<DbPage, EseError> {
         let page_header = reader.load_page_header(page_number)?;
         let mut db_page = DbPage {
             page_number,
@@ -352,6 +390,16 @@ impl RootPageHeader {
     }
 }
 
+impl CatalogDefinition {
+    pub fn column_flags(&self) -> ColumnFlags {
+        ColumnFlags::from_bits_truncate(self.flags)
+    }
+
+    pub fn index_flags(&self) -> IndexFlags {
+        IndexFlags::from_bits_truncate(self.flags)
+    }
+}
+
 impl PageTag {
     pub fn flags(&self) -> PageTagFlags {
         PageTagFlags::from_bits_truncate(self.flags)
@@ -362,7 +410,16 @@ impl PageTag {
     }
 }
 
+// KeyFldIDs item of an index
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IndexKeySegment {
+    pub column_identifier: uint32_t,
+    pub descending: bool,
+}
+
 #[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct CatalogDefinition {
     pub father_data_page_object_identifier: uint32_t,
@@ -379,16 +436,26 @@ pub struct CatalogDefinition {
 
     pub name: String,
 
-    pub template_name: Vec<u8>,
+    pub stats: Vec<u8>,
+    // name of the template table a derived table inherits its columns from
+    pub template_name: Option<String>,
     pub default_value: Vec<u8>,
+    pub key_segments: Vec<IndexKeySegment>,
+    // ConditionalColumns of an index
+    pub conditional_columns: Vec<uint32_t>,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct TableDefinition {
     pub table_catalog_definition: Option<CatalogDefinition>,
     pub column_catalog_definition_array: Vec<CatalogDefinition>,
     pub long_value_catalog_definition: Option<CatalogDefinition>,
+    pub index_catalog_definition_array: Vec<CatalogDefinition>,
+    pub callback_catalog_definition_array: Vec<CatalogDefinition>,
+    // template table the columns are inherited from
+    pub template_table: Option<String>,
 }
 
 pub struct PageTree {
@@ -453,4 +520,18 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_date_time_to_utc() {
+        let dt = DateTime::parse_le(&[5, 10, 5, 10, 5, 121, 1, 0]).unwrap().1;
+        assert_eq!(
+            dt.to_utc().unwrap().to_rfc3339(),
+            "2021-05-10T05:10:05+00:00"
+        );
+        assert_eq!(DateTime::default().to_utc(), None);
+        let bad_month = DateTime::parse_le(&[5, 10, 5, 10, 13, 121, 1, 0])
+            .unwrap()
+            .1;
+        assert_eq!(bad_month.to_utc(), None);
+    }
 }
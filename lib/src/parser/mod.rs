@@ -1,3 +1,6 @@
+pub mod backend;
+pub mod btree;
+pub mod checksum;
 pub mod decomp;
 pub mod ese_both;
 pub mod ese_db;
@@ -1,21 +1,26 @@
 //reader.rs
+use crate::error::EseError;
 use byteorder::*;
 use cache_2q::Cache;
-use simple_error::SimpleError;
 use std::array::TryFromSliceError;
-use std::collections::{hash_map::Entry, BTreeSet, HashMap};
+use std::collections::{hash_map::Entry, BTreeMap, HashMap, HashSet};
 use std::{
     cell::RefCell,
     convert::TryInto,
     io,
     io::{Read, Seek, SeekFrom},
     mem,
+    path::{Path, PathBuf},
 };
 
+use crate::parser::backend::{CursorBackend, MemoryBackend, ReaderBackend};
+use crate::parser::btree::*;
+use crate::parser::checksum::{self, ChecksumMode, ChecksumResult};
 use crate::parser::decomp::*;
 use crate::parser::ese_db;
 use crate::parser::ese_db::*;
 use crate::parser::jet;
+use nom_derive::Parse;
 
 #[cfg(all(feature = "nt_comparison", target_os = "windows"))]
 mod gen_db;
@@ -30,35 +35,41 @@ pub trait ReadSeek: Read + Seek {
 
 impl<T: Read + Seek> ReadSeek for T {}
 
-pub struct Reader<T: ReadSeek> {
-    file: RefCell<T>,
+/// Number of pages loaded by one read when a table scan moves to the next leaf page.
+pub const DEFAULT_READ_AHEAD_PAGES: u32 = 16;
+
+pub struct Reader<T: ReaderBackend> {
+    backend: T,
     cache: RefCell<Cache<u32, Vec<u8>>>,
+    cache_size: usize,
+    read_ahead_pages: u32,
     format_version: jet::FormatVersion,
     format_revision: jet::FormatRevision,
     page_size: u32,
+    file_header: ese_db::FileHeader,
+    path: Option<PathBuf>,
+    checksum_mode: ChecksumMode,
+    // size of the database file, read once from the backend
+    file_size: u64,
+    // pages verified by checksum_mode, not verified again when loaded again
+    verified_pages: RefCell<HashSet<u32>>,
 }
 
-impl<T: ReadSeek> Reader<T> {
-    fn load_db_file_header(&mut self) -> Result<ese_db::FileHeader, SimpleError> {
-        fn calc_crc32(buffer: &[u8]) -> u32 {
-            let mut buf32: Vec<u32> = vec![0; buffer.len() / mem::size_of::<u32>()];
-            LittleEndian::read_u32_into(buffer, &mut buf32);
-            buf32.iter().skip(1).fold(0x89abcdef, |crc, &val| crc ^ val)
-        }
-
+impl<T: ReaderBackend> Reader<T> {
+    fn load_db_file_header(&mut self) -> Result<ese_db::FileHeader, EseError> {
         let (mut db_file_header, buffer) = ese_db::FileHeader::read(self, 0)?;
 
         if db_file_header.signature != ESEDB_FILE_SIGNATURE {
-            return Err(SimpleError::new("bad file_header.signature"));
+            return Err(EseError::BadSignature);
         }
 
         let stored_checksum = db_file_header.checksum;
-        let checksum = calc_crc32(&buffer);
+        let checksum = checksum::xor_checksum(&buffer[4..], ESEDB_FILE_SIGNATURE);
         if stored_checksum != checksum {
-            return Err(SimpleError::new(format!(
-                "wrong checksum: {}, calculated {}",
-                stored_checksum, checksum
-            )));
+            return Err(EseError::ChecksumMismatch {
+                stored: stored_checksum,
+                calculated: checksum,
+            });
         }
         let (backup_file_header, _) =
             ese_db::FileHeader::read(self, db_file_header.page_size as u64)?;
@@ -68,7 +79,7 @@ impl<T: ReadSeek> Reader<T> {
         }
 
         if db_file_header.format_revision != backup_file_header.format_revision {
-            return Err(SimpleError::new(format!(
+            return Err(EseError::new(format!(
                 "mismatch in format revision: {} not equal to backup value {}",
                 db_file_header.format_revision, backup_file_header.format_revision
             )));
@@ -79,68 +90,73 @@ impl<T: ReadSeek> Reader<T> {
         }
 
         if db_file_header.page_size != backup_file_header.page_size {
-            return Err(SimpleError::new(format!(
+            return Err(EseError::new(format!(
                 "mismatch in page size: {} not equal to backup value {}",
                 db_file_header.page_size, backup_file_header.page_size
             )));
         }
         if db_file_header.format_version != 0x620 {
-            return Err(SimpleError::new(format!(
-                "unsupported format version: {}",
-                db_file_header.format_version
-            )));
+            return Err(EseError::BadFormatVersion(db_file_header.format_version));
         }
 
         Ok(db_file_header)
     }
 
-    fn new(read_seek: T, cache_size: usize) -> Result<Reader<T>, SimpleError> {
+    fn new(backend: T, cache_size: usize) -> Result<Reader<T>, EseError> {
+        let file_size = backend.size()?;
         let mut reader = Reader {
-            file: RefCell::new(read_seek),
+            backend,
             cache: RefCell::new(Cache::new(cache_size)),
+            cache_size,
+            read_ahead_pages: DEFAULT_READ_AHEAD_PAGES,
             page_size: 2 * 1024, //just to read header
             format_version: 0,
             format_revision: 0,
+            file_header: Default::default(),
+            path: None,
+            checksum_mode: ChecksumMode::Ignore,
+            file_size,
+            verified_pages: RefCell::new(HashSet::new()),
         };
 
         let db_fh = reader.load_db_file_header()?;
         reader.format_version = db_fh.format_version;
         reader.format_revision = db_fh.format_revision;
         reader.page_size = db_fh.page_size;
+        reader.file_header = db_fh;
 
         reader.cache.get_mut().clear();
 
         Ok(reader)
     }
 
-    fn read(&self, offset: u64, buf: &mut [u8]) -> Result<(), SimpleError> {
+    fn read(&self, offset: u64, buf: &mut [u8]) -> Result<(), EseError> {
         let pg_no = (offset / self.page_size as u64) as u32;
         let mut c = self.cache.borrow_mut();
         if !c.contains_key(&pg_no) {
             let mut page_buf = vec![0u8; self.page_size as usize];
-            let f = &mut self.file.borrow_mut();
-            match f.seek(io::SeekFrom::Start(pg_no as u64 * self.page_size as u64)) {
-                Ok(_) => match f.read_exact(&mut page_buf) {
-                    Ok(_) => {
-                        c.insert(pg_no, page_buf);
-                    }
-                    Err(e) => {
-                        return Err(SimpleError::new(format!("read_exact failed: {:?}", e)));
-                    }
-                },
-                Err(e) => {
-                    return Err(SimpleError::new(format!("seek failed: {:?}", e)));
-                }
-            }
+            self.backend
+                .read_at(pg_no as u64 * self.page_size as u64, &mut page_buf)?;
+            c.insert(pg_no, page_buf);
         }
 
         match c.get(&pg_no) {
             Some(page_buf) => {
                 let page_offset = (offset % self.page_size as u64) as usize;
-                buf.copy_from_slice(&page_buf[page_offset..page_offset + buf.len()]);
+                match page_buf.get(page_offset..page_offset + buf.len()) {
+                    Some(data) => buf.copy_from_slice(data),
+                    None => {
+                        return Err(EseError::new(format!(
+                            "read of {} bytes at offset 0x{:x} is past the end of page {}",
+                            buf.len(),
+                            offset,
+                            pg_no
+                        )));
+                    }
+                }
             }
             None => {
-                return Err(SimpleError::new(format!(
+                return Err(EseError::new(format!(
                     "Cache failed, page number not found: {}",
                     pg_no
                 )));
@@ -150,55 +166,252 @@ impl<T: ReadSeek> Reader<T> {
         Ok(())
     }
 
-    pub fn read_bytes(&self, offset: u64, size: usize) -> Result<Vec<u8>, SimpleError> {
+    /// Loads the pages from `page_number` into the cache with one read, up to `read_ahead_pages`
+    /// pages (and no more than the cache holds), stopping before the first page already cached.
+    pub(crate) fn read_ahead(&self, page_number: u32) -> Result<(), EseError> {
+        let count = self.read_ahead_pages.min(self.cache_size as u32);
+        // the pages are cached by their position in the file, after the database header
+        let first = page_number + 1;
+        let mut c = self.cache.borrow_mut();
+        let uncached = (first..first.saturating_add(count))
+            .take_while(|pg_no| !c.contains_key(pg_no))
+            .count();
+        if uncached < 2 {
+            // nothing to gain over the read of the page itself
+            return Ok(());
+        }
+
+        let page_size = self.page_size as usize;
+        let offset = first as u64 * page_size as u64;
+        // the range may go past the end of the file
+        let in_file = (self.file_size.saturating_sub(offset) / page_size as u64) as usize;
+        let count = uncached.min(in_file);
+        if count == 0 {
+            return Ok(());
+        }
+        let mut buf = vec![0u8; count * page_size];
+        self.backend.read_at(offset, &mut buf)?;
+        for (i, page_buf) in buf.chunks_exact(page_size).enumerate() {
+            c.insert(first + i as u32, page_buf.to_vec());
+        }
+        Ok(())
+    }
+
+    pub fn read_bytes(&self, offset: u64, size: usize) -> Result<Vec<u8>, EseError> {
         let mut buf = vec![0u8; size];
         self.read(offset, &mut buf)?;
         Ok(buf)
     }
 
-    pub fn read_string(&self, offset: u64, size: usize) -> Result<String, SimpleError> {
+    /// Reads a `S` stored at `offset`, the structure must fit into one page.
+    ///
+    /// # Safety
+    ///
+    /// `S` must be a plain `Copy` structure without padding bytes,
+    /// any bit pattern read from the file has to be a valid `S`.
+    pub unsafe fn read_struct<S: Copy>(&self, offset: u64) -> Result<S, EseError> {
+        let buf = self.read_bytes(offset, mem::size_of::<S>())?;
+        Ok(std::ptr::read_unaligned(buf.as_ptr() as *const S))
+    }
+
+    pub fn read_string(&self, offset: u64, size: usize) -> Result<String, EseError> {
         let v = self.read_bytes(offset, size)?;
         match std::str::from_utf8(&v) {
             Ok(s) => Ok(s.to_string()),
 
-            Err(e) => Err(SimpleError::new(format!(
+            Err(e) => Err(EseError::new(format!(
                 "from_utf8 failed: error_len() is {:?}",
                 e.error_len()
             ))),
         }
     }
 
-    pub fn load_db(read_seek: T, cache_size: usize) -> Result<Reader<T>, SimpleError> {
-        Reader::new(read_seek, cache_size)
+    /// Reads the database from a `ReaderBackend`, e.g. `FileBackend` or a `Box<dyn ReaderBackend>`.
+    pub fn from_backend(backend: T, cache_size: usize) -> Result<Reader<T>, EseError> {
+        Reader::new(backend, cache_size)
+    }
+
+    /// Same as `from_backend`, the pages are verified by `checksum_mode` when they are loaded.
+    pub fn from_backend_opts(
+        backend: T,
+        cache_size: usize,
+        checksum_mode: ChecksumMode,
+    ) -> Result<Reader<T>, EseError> {
+        let mut reader = Reader::new(backend, cache_size)?;
+        reader.checksum_mode = checksum_mode;
+        Ok(reader)
+    }
+
+    pub fn checksum_mode(&self) -> ChecksumMode {
+        self.checksum_mode
+    }
+
+    pub fn read_ahead_pages(&self) -> u32 {
+        self.read_ahead_pages
+    }
+
+    /// Sets the number of pages loaded by one read during table scans, 0 or 1 disables read-ahead.
+    /// Random access (seeks, index lookups) always reads single pages.
+    pub fn set_read_ahead_pages(&mut self, pages: u32) {
+        self.read_ahead_pages = pages;
     }
 
     pub fn page_size(&self) -> u32 {
         self.page_size
     }
 
-    pub(crate) fn load_page_header(&self, page_number: u32) -> Result<PageHeader, SimpleError> {
+    /// Pages of 16K and 32K of the extended page header format: the pages have an extended header,
+    /// page tags use 15-bit offsets and sizes, their flags are in the tag data.
+    pub fn is_large_page_format(&self) -> bool {
+        self.format_revision >= ESEDB_FORMAT_REVISION_EXTENDED_PAGE_HEADER
+            && self.page_size >= 16384
+    }
+
+    // mask of the offsets of the tagged data of a record
+    fn tagged_data_type_offset_bitmask(&self) -> u16 {
+        if self.is_large_page_format() {
+            0x7fff
+        } else {
+            0x3fff
+        }
+    }
+
+    pub fn file_header(&self) -> &ese_db::FileHeader {
+        &self.file_header
+    }
+
+    /// Path of the database file, `None` if the reader wasn't opened from a path
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub(crate) fn set_path(&mut self, path: &Path) {
+        self.path = Some(path.to_path_buf());
+    }
+
+    /// Size of the database file when it was loaded.
+    pub fn file_size(&self) -> Result<u64, EseError> {
+        Ok(self.file_size)
+    }
+
+    pub fn verify_page_checksum(&self, page_number: u32) -> ChecksumResult {
+        let page_size = self.page_size as u64;
+        let page = self.check_page_number(page_number).and_then(|_| {
+            self.read_bytes((page_number as u64 + 1) * page_size, page_size as usize)
+        });
+        match page {
+            Ok(page) => self.page_checksum_result(&page, page_number),
+            Err(error) => ChecksumResult::Unreadable { page_number, error },
+        }
+    }
+
+    fn check_page_number(&self, page_number: u32) -> Result<(), EseError> {
+        let page_size = self.page_size as u64;
+        if page_number == 0 || (page_number as u64 + 2) * page_size > self.file_size {
+            return Err(EseError::BadPageNumber {
+                page: page_number,
+                max: (self.file_size / page_size).saturating_sub(2) as u32,
+            });
+        }
+        Ok(())
+    }
+
+    /// Reads the page from the backend, the cache is neither used nor filled:
+    /// a scan of the whole database doesn't evict the pages being used.
+    pub(crate) fn read_page_uncached(&self, page_number: u32) -> Result<Vec<u8>, EseError> {
+        self.check_page_number(page_number)?;
+        let mut page = vec![0u8; self.page_size as usize];
+        self.backend
+            .read_at((page_number as u64 + 1) * self.page_size as u64, &mut page)?;
+        Ok(page)
+    }
+
+    /// Flags of the header of a page read by `read_page_uncached`.
+    pub(crate) fn page_flags_of(&self, page: &[u8]) -> Result<jet::PageFlags, EseError> {
+        // the common part of the header, as read by load_page_header
+        let offset = if self.format_revision < ESEDB_FORMAT_REVISION_NEW_RECORD_FORMAT {
+            0
+        } else if self.format_revision < ESEDB_FORMAT_REVISION_EXTENDED_PAGE_HEADER {
+            mem::size_of::<PageHeader0x0b>()
+        } else {
+            mem::size_of::<PageHeader0x11>()
+        };
+        let (_, common) = PageHeaderCommon::parse_le(&page[offset..])
+            .map_err(|e: nom::Err<nom::error::Error<&[u8]>>| EseError::new(e.to_string()))?;
+        Ok(common.page_flags)
+    }
+
+    /// Checksum verification of a page already read, see `verify_page_checksum`.
+    pub(crate) fn page_checksum_result(&self, page: &[u8], page_number: u32) -> ChecksumResult {
+        // uninitialized page, nothing to check
+        if page.iter().all(|&b| b == 0) {
+            return ChecksumResult::Ok(page_number);
+        }
+        let checksums = checksum::page_checksums(page, page_number, self.format_revision);
+        for (block, (stored, computed)) in checksums.into_iter().enumerate() {
+            if stored != computed {
+                return ChecksumResult::Mismatch {
+                    page_number,
+                    block,
+                    stored,
+                    computed,
+                };
+            }
+        }
+        ChecksumResult::Ok(page_number)
+    }
+
+    /// Verifies the checksum of the page, a mismatch is returned as `EseError::PageChecksumMismatch`.
+    pub fn verify_page(&self, page_number: u32) -> Result<(), EseError> {
+        match self.verify_page_checksum(page_number) {
+            ChecksumResult::Ok(_) => Ok(()),
+            ChecksumResult::Mismatch {
+                page_number,
+                block,
+                stored,
+                computed,
+            } => Err(EseError::PageChecksumMismatch {
+                page: page_number,
+                block,
+                stored,
+                computed,
+            }),
+            ChecksumResult::Unreadable { error, .. } => Err(error),
+        }
+    }
+
+    pub(crate) fn load_page_header(&self, page_number: u32) -> Result<PageHeader, EseError> {
         let page_offset = (page_number + 1) as u64 * (self.page_size) as u64;
 
+        if self.checksum_mode != ChecksumMode::Ignore
+            && !self.verified_pages.borrow().contains(&page_number)
+        {
+            match self.verify_page(page_number) {
+                Ok(()) => {}
+                Err(e) if self.checksum_mode == ChecksumMode::Warn => log::warn!("{}", e),
+                Err(e) => return Err(e),
+            }
+            // a failing page is logged once in Warn mode
+            self.verified_pages.borrow_mut().insert(page_number);
+        }
+
         if self.format_revision < ESEDB_FORMAT_REVISION_NEW_RECORD_FORMAT {
             let header = PageHeaderOld::read(self, page_offset)?;
             let common = PageHeaderCommon::read(self, page_offset)?;
 
-            //let TODO_checksum = 0;
             Ok(PageHeader::old(header, common))
         } else if self.format_revision < ESEDB_FORMAT_REVISION_EXTENDED_PAGE_HEADER {
             let header = PageHeader0x0b::read(self, page_offset)?;
             let common =
                 PageHeaderCommon::read(self, page_offset + mem::size_of_val(&header) as u64)?;
 
-            //TODO: verify checksum
             Ok(PageHeader::x0b(header, common))
         } else {
             let header = PageHeader0x11::read(self, page_offset)?;
             let common =
                 PageHeaderCommon::read(self, page_offset + mem::size_of_val(&header) as u64)?;
 
-            //TODO: verify checksum
-            if self.page_size > 8 * 1024 {
+            if self.is_large_page_format() {
                 let offs = mem::size_of_val(&header) + mem::size_of_val(&common);
                 let ext = PageHeaderExt0x11::read(self, page_offset + offs as u64)?;
 
@@ -209,40 +422,53 @@ impl<T: ReadSeek> Reader<T> {
         }
     }
 
-    pub fn load_page_tags(&self, db_page: &jet::DbPage) -> Result<Vec<PageTag>, SimpleError> {
+    pub fn load_page_tags(&self, db_page: &jet::DbPage) -> Result<Vec<PageTag>, EseError> {
         let page_offset = db_page.offset();
         let mut tags_offset = (page_offset + self.page_size as u64) as u64;
         let tags_cnt = db_page.get_available_page_tag();
+        // the tag data is located between the page header and the tags array at the end of the page
+        let data_size = (self.page_size as usize)
+            .checked_sub(db_page.size() + tags_cnt * 4)
+            .ok_or_else(|| {
+                EseError::new(format!(
+                    "page {}: {} page tags don't fit into the page",
+                    db_page.page_number, tags_cnt
+                ))
+            })?;
         let mut tags = Vec::<PageTag>::with_capacity(tags_cnt);
 
-        for _i in 0..tags_cnt {
+        for i in 0..tags_cnt {
             tags_offset -= 2;
             let page_tag_offset = read_u16(self, tags_offset)?;
             tags_offset -= 2;
             let page_tag_size = read_u16(self, tags_offset)?;
 
-            let flags: u8;
-            let offset: u16;
-            let size: u16;
-
-            if self.format_revision >= ESEDB_FORMAT_REVISION_EXTENDED_PAGE_HEADER
-                && self.page_size >= 16384
-            {
-                offset = page_tag_offset & 0x7fff;
-                size = page_tag_size & 0x7fff;
+            let large_page = self.is_large_page_format();
+            let (offset, size) = if large_page {
+                (page_tag_offset & 0x7fff, page_tag_size & 0x7fff)
+            } else {
+                (page_tag_offset & 0x1fff, page_tag_size & 0x1fff)
+            };
+            if offset as usize + size as usize > data_size {
+                return Err(EseError::new(format!(
+                    "page {} tag {}: offset {} and size {} are past the page data of {} bytes",
+                    db_page.page_number, i, offset, size, data_size
+                )));
+            }
 
-                // The upper 3-bits of the first 16-bit-value in the leaf page entry contain the page tag flags
-                //if db_page.flags().contains(jet::PageFlags::IS_LEAF)
-                {
+            let flags = if large_page {
+                // The upper 3-bits of the first 16-bit-value of the page entry contain the page tag flags,
+                // on leaf and branch pages alike (see clean_pgtag_flag)
+                if size < 2 {
+                    0
+                } else {
                     let flags_offset = page_offset + db_page.size() as u64 + offset as u64;
                     let f: u16 = read_u16(self, flags_offset)?;
-                    flags = (f >> 13) as u8;
+                    (f >> 13) as u8
                 }
             } else {
-                flags = (page_tag_offset >> 13) as u8;
-                offset = page_tag_offset & 0x1fff;
-                size = page_tag_size & 0x1fff;
-            }
+                (page_tag_offset >> 13) as u8
+            };
             tags.push(PageTag {
                 flags,
                 offset,
@@ -257,7 +483,7 @@ impl<T: ReadSeek> Reader<T> {
         &self,
         db_page: &jet::DbPage,
         page_tag: &PageTag,
-    ) -> Result<RootPageHeader, SimpleError> {
+    ) -> Result<RootPageHeader, EseError> {
         let root_page_offset = page_tag.offset(db_page);
 
         // TODO Seen in format version 0x620 revision 0x14
@@ -270,7 +496,7 @@ impl<T: ReadSeek> Reader<T> {
             return Ok(RootPageHeader::x19(root_page_header));
         }
 
-        Err(SimpleError::new(format!(
+        Err(EseError::new(format!(
             "wrong size of page tag: {:?}",
             page_tag
         )))
@@ -280,17 +506,20 @@ impl<T: ReadSeek> Reader<T> {
         &self,
         db_page: &jet::DbPage,
         page_tag: &PageTag,
-    ) -> Result<u32, SimpleError> {
+    ) -> Result<u32, EseError> {
         let mut offset = page_tag.offset(db_page);
 
-        if page_tag
+        let local_page_key_size = if page_tag
             .flags()
             .intersects(jet::PageTagFlags::FLAG_HAS_COMMON_KEY_SIZE)
         {
             // Why is this intersect vs contains?
             offset += 2;
-        }
-        let local_page_key_size: u16 = read_u16(self, offset)?;
+            read_u16(self, offset)?
+        } else {
+            // the first 16-bit-value holds the page tag flags of large pages
+            self.clean_pgtag_flag(db_page, read_u16(self, offset)?)
+        };
         offset += 2;
         offset += local_page_key_size as u64;
 
@@ -298,8 +527,153 @@ impl<T: ReadSeek> Reader<T> {
         Ok(child_page_number)
     }
 
-    pub fn load_catalog(&self) -> Result<Vec<jet::TableDefinition>, SimpleError> {
-        let db_page = jet::DbPage::new(self, jet::FixedPageNumber::Catalog as u32)?;
+    // full key of the page tag: common prefix (stored in page tag 0) + local key
+    pub fn load_page_tag_key(
+        &self,
+        db_page: &jet::DbPage,
+        page_tag: &PageTag,
+    ) -> Result<Vec<u8>, EseError> {
+        let mut offset = page_tag.offset(db_page);
+        let mut key = vec![];
+
+        let mut first_word_read = false;
+        if page_tag
+            .flags()
+            .intersects(jet::PageTagFlags::FLAG_HAS_COMMON_KEY_SIZE)
+        {
+            let common_page_key_size = self.clean_pgtag_flag(db_page, read_u16(self, offset)?);
+            first_word_read = true;
+            offset += 2;
+            if common_page_key_size > 0 {
+                let offset0 = db_page.page_tags[0].offset(db_page);
+                key = self.read_bytes(offset0, common_page_key_size as usize)?;
+            }
+        }
+        let mut local_page_key_size: u16 = read_u16(self, offset)?;
+        if !first_word_read {
+            local_page_key_size = self.clean_pgtag_flag(db_page, local_page_key_size);
+        }
+        offset += 2;
+        key.append(&mut self.read_bytes(offset, local_page_key_size as usize)?);
+        Ok(key)
+    }
+
+    /// Returns the data of the page tag, without page tag flags
+    pub fn load_page_tag_data(
+        &self,
+        db_page: &jet::DbPage,
+        page_tag: &PageTag,
+    ) -> Result<Vec<u8>, EseError> {
+        let mut data = self.read_bytes(page_tag.offset(db_page), page_tag.size as usize)?;
+        if data.len() >= 2 {
+            let first_word = self.clean_pgtag_flag(db_page, LittleEndian::read_u16(&data));
+            LittleEndian::write_u16(&mut data, first_word);
+        }
+        Ok(data)
+    }
+
+    /// Returns the data of the page tag following its key
+    pub fn load_page_tag_value(
+        &self,
+        db_page: &jet::DbPage,
+        page_tag: &PageTag,
+    ) -> Result<Vec<u8>, EseError> {
+        let mut data = self.load_page_tag_data(db_page, page_tag)?;
+        let key_fields_size = if page_tag
+            .flags()
+            .intersects(jet::PageTagFlags::FLAG_HAS_COMMON_KEY_SIZE)
+        {
+            4
+        } else {
+            2
+        };
+        if data.len() < key_fields_size {
+            return Err(EseError::new(format!(
+                "wrong page tag at page {}: {:?}",
+                db_page.page_number, page_tag
+            )));
+        }
+        let data_offset =
+            key_fields_size + LittleEndian::read_u16(&data[key_fields_size - 2..]) as usize;
+        if data.len() < data_offset {
+            return Err(EseError::new(format!(
+                "wrong page tag key size at page {}: {:?}",
+                db_page.page_number, page_tag
+            )));
+        }
+        Ok(data.split_off(data_offset))
+    }
+
+    // space tree leaf entry: key is the last page number of the extent (big-endian),
+    // data is the number of pages in the extent
+    // returns (first page number, number of pages) of the extent, None for empty extents
+    fn load_space_tree_entry(
+        &self,
+        db_page: &jet::DbPage,
+        page_tag: &PageTag,
+    ) -> Result<Option<(u32, u32)>, EseError> {
+        let key = self.load_page_tag_key(db_page, page_tag)?;
+        let value = self.load_page_tag_value(db_page, page_tag)?;
+        if key.len() < 4 || value.len() < 4 {
+            return Err(EseError::new(format!(
+                "wrong space tree entry at page {}: {:?}",
+                db_page.page_number, page_tag
+            )));
+        }
+        let last_page = BigEndian::read_u32(&key[key.len() - 4..]);
+        let number_of_pages = LittleEndian::read_u32(&value);
+        if number_of_pages == 0 {
+            return Ok(None);
+        }
+        match last_page.checked_sub(number_of_pages - 1) {
+            Some(first_page) => Ok(Some((first_page, number_of_pages))),
+            None => Err(EseError::new(format!(
+                "wrong space tree extent at page {}: last page {}, {} pages",
+                db_page.page_number, last_page, number_of_pages
+            ))),
+        }
+    }
+
+    // returns (first page number, number of pages) of every extent of the space tree
+    pub fn load_space_tree_extents(&self, page_number: u32) -> Result<Vec<(u32, u32)>, EseError> {
+        let mut visitor = SpaceTreeVisitor {
+            reader: self,
+            extents: vec![],
+        };
+        walk_btree(self, page_number, &mut visitor)?;
+        Ok(visitor.extents)
+    }
+
+    /// Returns (key, value) of the leaf entries of the B-tree whose key starts with `prefix`,
+    /// in key order
+    pub fn load_btree_entries_with_key_prefix(
+        &self,
+        page_number: u32,
+        prefix: &[u8],
+    ) -> Result<Vec<BTreeEntry>, EseError> {
+        let mut visitor = KeyPrefixVisitor {
+            reader: self,
+            prefix,
+            entries: vec![],
+        };
+        walk_btree(self, page_number, &mut visitor)?;
+        Ok(visitor.entries)
+    }
+
+    pub fn load_catalog(&self) -> Result<Vec<jet::TableDefinition>, EseError> {
+        self.load_catalog_from(jet::FixedPageNumber::Catalog as u32)
+    }
+
+    // copy of the catalog (MSysObjectsShadow)
+    pub fn load_shadow_catalog(&self) -> Result<Vec<jet::TableDefinition>, EseError> {
+        self.load_catalog_from(jet::FixedPageNumber::CatalogBackup as u32)
+    }
+
+    fn load_catalog_from(
+        &self,
+        catalog_page_number: u32,
+    ) -> Result<Vec<jet::TableDefinition>, EseError> {
+        let db_page = jet::DbPage::new(self, catalog_page_number)?;
         let pg_tags = &db_page.page_tags;
 
         let is_root = db_page.flags().contains(jet::PageFlags::IS_ROOT);
@@ -308,93 +682,63 @@ impl<T: ReadSeek> Reader<T> {
         }
 
         let mut res: Vec<jet::TableDefinition> = vec![];
-        let mut table_def: jet::TableDefinition = jet::TableDefinition {
-            table_catalog_definition: None,
-            column_catalog_definition_array: vec![],
-            long_value_catalog_definition: None,
-        };
+        let mut table_def = jet::TableDefinition::default();
 
-        let mut page_number;
-        if db_page.flags().contains(jet::PageFlags::IS_PARENT) {
-            page_number = self.page_tag_get_branch_child_page_number(&db_page, &pg_tags[1])?;
-        } else if db_page.flags().contains(jet::PageFlags::IS_LEAF) {
-            page_number = db_page.page_number;
-        } else {
-            return Err(SimpleError::new(format!(
+        if !db_page
+            .flags()
+            .intersects(jet::PageFlags::IS_PARENT | jet::PageFlags::IS_LEAF)
+        {
+            return Err(EseError::new(format!(
                 "pageno {}: neither IS_PARENT nor IS_LEAF is present in {:?}",
                 db_page.page_number,
                 db_page.flags()
             )));
         }
-        let mut prev_page_number = db_page.page_number;
 
-        while page_number != 0 {
-            let db_page = jet::DbPage::new(self, page_number)?;
-            let pg_tags = &db_page.page_tags;
-
-            if db_page.prev_page() != 0 && prev_page_number != db_page.prev_page() {
-                return Err(SimpleError::new(format!(
-                    "pageno {}: wrong previous_page number {}, expected {}",
-                    db_page.page_number,
-                    db_page.prev_page(),
-                    prev_page_number
-                )));
-            }
-            if !db_page.flags().contains(jet::PageFlags::IS_LEAF) {
-                return Err(SimpleError::new(format!(
-                    "pageno {}: IS_LEAF flag should be present",
-                    db_page.page_number
-                )));
-            }
-
-            for i in pg_tags.iter().skip(1) {
-                if jet::PageTagFlags::from_bits_truncate(i.flags)
-                    .intersects(jet::PageTagFlags::FLAG_IS_DEFUNCT)
+        walk_btree_leaf_tags(self, catalog_page_number, &mut |db_page, tag, _depth| {
+            let cat_item = self.load_catalog_item(db_page, tag)?;
+            if cat_item.cat_type == jet::CatalogType::Table as u16 {
+                if table_def.table_catalog_definition.is_some() {
+                    res.push(mem::take(&mut table_def));
+                } else if !table_def.column_catalog_definition_array.is_empty()
+                    || table_def.long_value_catalog_definition.is_some()
+                    || !table_def.index_catalog_definition_array.is_empty()
+                    || !table_def.callback_catalog_definition_array.is_empty()
                 {
-                    continue;
+                    return Err(EseError::CorruptCatalog(
+                        "column/long definition is going before table".to_string(),
+                    ));
                 }
-                let cat_item = self.load_catalog_item(&db_page, i)?;
-                if cat_item.cat_type == jet::CatalogType::Table as u16 {
-                    if table_def.table_catalog_definition.is_some() {
-                        res.push(table_def);
-                        table_def = jet::TableDefinition {
-                            table_catalog_definition: None,
-                            column_catalog_definition_array: vec![],
-                            long_value_catalog_definition: None,
-                        };
-                    } else if !table_def.column_catalog_definition_array.is_empty()
-                        || table_def.long_value_catalog_definition.is_some()
-                    {
-                        return Err(SimpleError::new(
-                            "corrupted table detected: column/long definition is going before table"));
-                    }
-                    table_def.table_catalog_definition = Some(cat_item);
-                } else if cat_item.cat_type == jet::CatalogType::Column as u16 {
-                    table_def.column_catalog_definition_array.push(cat_item);
-                } else if cat_item.cat_type == jet::CatalogType::LongValue as u16 {
-                    if table_def.long_value_catalog_definition.is_some() {
-                        return Err(SimpleError::new("long-value catalog definition duplicate?"));
-                    }
-                    table_def.long_value_catalog_definition = Some(cat_item);
-                }
-                // we knowingly ignore Index and Callback Catalog types
-                else if cat_item.cat_type != jet::CatalogType::Index as u16
-                    && cat_item.cat_type != jet::CatalogType::Callback as u16
-                {
-                    return Err(SimpleError::new(format!(
-                        "TODO: Unhandled cat_item.cat_type {}",
-                        cat_item.cat_type
-                    )));
+                table_def.template_table = cat_item.template_name.clone();
+                table_def.table_catalog_definition = Some(cat_item);
+            } else if cat_item.cat_type == jet::CatalogType::Column as u16 {
+                table_def.column_catalog_definition_array.push(cat_item);
+            } else if cat_item.cat_type == jet::CatalogType::LongValue as u16 {
+                if table_def.long_value_catalog_definition.is_some() {
+                    return Err(EseError::CorruptCatalog(
+                        "long-value catalog definition duplicate".to_string(),
+                    ));
                 }
+                table_def.long_value_catalog_definition = Some(cat_item);
+            } else if cat_item.cat_type == jet::CatalogType::Index as u16 {
+                table_def.index_catalog_definition_array.push(cat_item);
+            } else if cat_item.cat_type == jet::CatalogType::Callback as u16 {
+                table_def.callback_catalog_definition_array.push(cat_item);
+            } else {
+                return Err(EseError::CorruptCatalog(format!(
+                    "unhandled cat_item.cat_type {}",
+                    cat_item.cat_type
+                )));
             }
-            prev_page_number = page_number;
-            page_number = db_page.next_page();
-        }
+            Ok(())
+        })?;
 
         if table_def.table_catalog_definition.is_some() {
             res.push(table_def);
         }
 
+        inherit_template_columns(&mut res)?;
+
         Ok(res)
     }
 
@@ -402,7 +746,7 @@ impl<T: ReadSeek> Reader<T> {
         &self,
         db_page: &jet::DbPage,
         page_tag: &PageTag,
-    ) -> Result<jet::CatalogDefinition, SimpleError> {
+    ) -> Result<jet::CatalogDefinition, EseError> {
         let mut offset = page_tag.offset(db_page);
 
         let mut first_word_read = false;
@@ -467,8 +811,12 @@ impl<T: ReadSeek> Reader<T> {
                 if variable_size_data_type_size & 0x8000 != 0 {
                     data_type_size = 0;
                 } else {
-                    data_type_size =
-                        variable_size_data_type_size - previous_variable_size_data_type_size;
+                    data_type_size = sub_size(
+                        variable_size_data_type_size,
+                        previous_variable_size_data_type_size,
+                        "catalog variable data size",
+                    )
+                    .map_err(|e| EseError::new(format!("page {}: {}", db_page.page_number, e)))?;
                 }
                 if data_type_size > 0 {
                     match data_type_number {
@@ -476,17 +824,37 @@ impl<T: ReadSeek> Reader<T> {
                             let offset_dtn = offset_ddh + variable_size_data_type_value_data_offset as u64 + previous_variable_size_data_type_size as u64;
                             cat_def.name = self.read_string(offset_dtn, data_type_size as usize)?;
                         },
+                        129 => {
+                            // Stats
+                            let offset_stats = offset_ddh + variable_size_data_type_value_data_offset as u64 + previous_variable_size_data_type_size as u64;
+                            cat_def.stats = self.read_bytes(offset_stats, data_type_size as usize)?;
+                        },
                         130 => {
-                            // TODO template_name
+                            // TemplateTable
+                            let offset_tmpl = offset_ddh + variable_size_data_type_value_data_offset as u64 + previous_variable_size_data_type_size as u64;
+                            cat_def.template_name = Some(self.read_string(offset_tmpl, data_type_size as usize)?);
                         },
                         131 => {
                             // TODO default_value
                             let offset_def = offset_ddh + variable_size_data_type_value_data_offset as u64 + previous_variable_size_data_type_size as u64;
                             cat_def.default_value = self.read_bytes(offset_def, data_type_size as usize)?;
                         },
-                        132 | // KeyFldIDs
+                        132 => {
+                            // KeyFldIDs
+                            let offset_key = offset_ddh + variable_size_data_type_value_data_offset as u64 + previous_variable_size_data_type_size as u64;
+                            let key = self.read_bytes(offset_key, data_type_size as usize)?;
+                            cat_def.key_segments = read_index_key_segments(&key, self.format_revision);
+                        },
+                        134 => {
+                            // ConditionalColumns, stored as the KeyFldIDs
+                            let offset_cond = offset_ddh + variable_size_data_type_value_data_offset as u64 + previous_variable_size_data_type_size as u64;
+                            let columns = self.read_bytes(offset_cond, data_type_size as usize)?;
+                            cat_def.conditional_columns = read_index_key_segments(&columns, self.format_revision)
+                                .iter()
+                                .map(|s| s.column_identifier)
+                                .collect();
+                        },
                         133 | // VarSegMac
-                        134 | // ConditionalColumns
                         135 | // TupleLimits
                         136 | // Version
                         137  // iMSO_SortID (?)
@@ -495,7 +863,7 @@ impl<T: ReadSeek> Reader<T> {
                         },
                         _ => {
                             if data_type_size > 0 {
-                                return Err(SimpleError::new(format!("TODO handle data_type_number: {}", data_type_number)));
+                                return Err(EseError::new(format!("TODO handle data_type_number: {}", data_type_number)));
                             }
                         }
                     }
@@ -508,37 +876,74 @@ impl<T: ReadSeek> Reader<T> {
         Ok(cat_def)
     }
 
-    pub fn clean_pgtag_flag(&self, db_page: &jet::DbPage, data: u16) -> u16 {
-        // The upper 3-bits of the first 16-bit-value in the leaf page entry contain the page tag flags
-        if self.format_revision >= ESEDB_FORMAT_REVISION_EXTENDED_PAGE_HEADER
-            && self.page_size >= 16384
-            && db_page.flags().contains(jet::PageFlags::IS_LEAF)
-        {
+    pub fn clean_pgtag_flag(&self, _db_page: &jet::DbPage, data: u16) -> u16 {
+        // The upper 3-bits of the first 16-bit-value in the page entry contain the page tag flags
+        if self.is_large_page_format() {
             return data & 0x1FFF;
         }
         data
     }
 
-    pub fn find_first_leaf_page(&self, mut page_number: u32) -> Result<u32, SimpleError> {
-        let mut visited_pages: BTreeSet<u32> = BTreeSet::new();
-        loop {
-            if visited_pages.contains(&page_number) {
-                return Err(SimpleError::new(format!(
+    /// Follows the first live child of the branch pages from `page_number` down to a leaf page,
+    /// `MAX_BTREE_DEPTH` levels at most.
+    pub fn find_first_leaf_page(&self, page_number: u32) -> Result<u32, EseError> {
+        let mut visitor = FirstLeafVisitor { leaf_page: None };
+        walk_btree(self, page_number, &mut visitor)?;
+        visitor.leaf_page.ok_or_else(|| {
+            EseError::new(format!(
+                "no leaf page in the B-tree of page {}",
+                page_number
+            ))
+        })
+    }
+
+    /// Descends the B-tree from `page_number` to the leaf page where `key` belongs.
+    /// Keys of branch page tags are upper bounds of their child pages, an empty key has no bound.
+    /// `MAX_BTREE_DEPTH` levels at most.
+    pub fn find_leaf_page_for_key(
+        &self,
+        page_number: u32,
+        key: &[u8],
+    ) -> Result<jet::DbPage, EseError> {
+        let mut visited_pages = vec![];
+        let mut db_page = jet::DbPage::new(self, page_number)?;
+        while !db_page.flags().contains(jet::PageFlags::IS_LEAF) {
+            if visited_pages.contains(&db_page.page_number) {
+                return Err(EseError::new(format!(
                     "Child page loop detected at page number {}, visited pages: {:?}",
-                    page_number, visited_pages
+                    db_page.page_number, visited_pages
                 )));
             }
-
-            let db_page = jet::DbPage::new(self, page_number)?;
-            if db_page.flags().contains(jet::PageFlags::IS_LEAF) {
-                return Ok(page_number);
-            } else {
-                visited_pages.insert(page_number);
+            visited_pages.push(db_page.page_number);
+            let mut child = None;
+            for tag in db_page.page_tags.iter().skip(1) {
+                if tag.flags().intersects(jet::PageTagFlags::FLAG_IS_DEFUNCT) {
+                    continue;
+                }
+                child = Some(tag);
+                let tag_key = self.load_page_tag_key(&db_page, tag)?;
+                if tag_key.is_empty() || tag_key.as_slice() >= key {
+                    break;
+                }
             }
-
-            let pg_tags = &db_page.page_tags;
-            page_number = self.page_tag_get_branch_child_page_number(&db_page, &pg_tags[1])?;
+            let child_page_number = match child {
+                Some(tag) => self.page_tag_get_branch_child_page_number(&db_page, tag)?,
+                None => {
+                    return Err(EseError::new(format!(
+                        "branch page {} has no child pages",
+                        db_page.page_number
+                    )))
+                }
+            };
+            if visited_pages.len() == MAX_BTREE_DEPTH {
+                return Err(EseError::new(format!(
+                    "page {} is deeper than {} levels in the B-tree",
+                    child_page_number, MAX_BTREE_DEPTH
+                )));
+            }
+            db_page = jet::DbPage::new(self, child_page_number)?;
         }
+        Ok(db_page)
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -551,11 +956,14 @@ impl<T: ReadSeek> Reader<T> {
         page_tag_index: usize,
         column_id: u32,
         multi_value_index: usize, // 0 value mean itagSequence = 1
-    ) -> Result<Option<Vec<u8>>, SimpleError> {
+    ) -> Result<Option<Vec<u8>>, EseError> {
         let pg_tags = &db_page.page_tags;
+        lls.tagged_column.data_type_flags = None;
+        lls.tagged_column.lv_key = None;
+        lls.tagged_column.value_count = None;
 
         if !db_page.flags().contains(jet::PageFlags::IS_LEAF) {
-            return Err(SimpleError::new(format!(
+            return Err(EseError::new(format!(
                 "expected leaf page, page_flags 0x{:?}",
                 db_page.flags()
             )));
@@ -567,7 +975,7 @@ impl<T: ReadSeek> Reader<T> {
         }
 
         if page_tag_index >= pg_tags.len() {
-            return Err(SimpleError::new(format!(
+            return Err(EseError::new(format!(
                 "wrong page tag index: {}",
                 page_tag_index
             )));
@@ -600,14 +1008,43 @@ impl<T: ReadSeek> Reader<T> {
             lls.offset += 2;
             lls.offset += local_page_key_size as u64;
 
-            lls.record_data_size = page_tag.size as u64 - (lls.offset - offset_start);
+            lls.record_data_size = (page_tag.size as u64)
+                .checked_sub(lls.offset - offset_start)
+                .ok_or_else(|| {
+                    EseError::new(format!(
+                        "page {} tag {}: key size {} is past the tag size {}",
+                        lls.page_number, page_tag_index, local_page_key_size, page_tag.size
+                    ))
+                })?;
 
             lls.offset_ddh = lls.offset;
+            lls.check_in_record(
+                lls.offset_ddh,
+                mem::size_of::<ese_db::DataDefinitionHeader>() as u64,
+            )?;
             lls.ddh = ese_db::DataDefinitionHeader::read(self, lls.offset_ddh)?;
             lls.offset += mem::size_of::<ese_db::DataDefinitionHeader>() as u64;
 
+            // the fixed size data (and its bits mask) is followed by the variable size data
+            let variable_size_data_types_offset = lls.ddh.variable_size_data_types_offset;
+            let fixed_data_size = (variable_size_data_types_offset as u64)
+                .checked_sub(mem::size_of::<ese_db::DataDefinitionHeader>() as u64)
+                .ok_or_else(|| {
+                    EseError::new(format!(
+                        "page {} tag {}: wrong variable size data offset {}",
+                        lls.page_number, page_tag_index, variable_size_data_types_offset
+                    ))
+                })?;
+            lls.check_in_record(lls.offset, fixed_data_size)?;
+
             // read fixed data bits mask, located at the end of fixed columns
             lls.fixed_data_bits_mask_size = (lls.ddh.last_fixed_size_data_type as usize + 7) / 8;
+            if lls.fixed_data_bits_mask_size as u64 > fixed_data_size {
+                return Err(EseError::new(format!(
+                    "page {} tag {}: fixed data bits mask of {} bytes is past the fixed data",
+                    lls.page_number, page_tag_index, lls.fixed_data_bits_mask_size
+                )));
+            }
             if lls.fixed_data_bits_mask_size > 0 {
                 lls.fixed_data_bits_mask = self.read_bytes(
                     lls.offset_ddh + lls.ddh.variable_size_data_types_offset as u64
@@ -626,8 +1063,16 @@ impl<T: ReadSeek> Reader<T> {
 
             lls.var_state.current_type = 127;
             lls.var_state.type_offset = lls.ddh.variable_size_data_types_offset;
-            lls.var_state.value_offset =
-                lls.ddh.variable_size_data_types_offset + (number_of_variable_size_data_types * 2);
+            lls.var_state.value_offset = lls
+                .ddh
+                .variable_size_data_types_offset
+                .checked_add(number_of_variable_size_data_types * 2)
+                .ok_or_else(|| {
+                    EseError::new(format!(
+                        "page {} tag {}: wrong variable size data offset {}",
+                        lls.page_number, page_tag_index, variable_size_data_types_offset
+                    ))
+                })?;
         } else {
             for j in 0..tbl_def.column_catalog_definition_array.len() {
                 let col = &tbl_def.column_catalog_definition_array[j];
@@ -650,6 +1095,7 @@ impl<T: ReadSeek> Reader<T> {
                             // empty value
                             return Ok(None);
                         }
+                        lls.check_in_record(lls.offset, col.size as u64)?;
                         let v = self.read_bytes(lls.offset, col.size as usize)?;
                         return Ok(Some(v));
                     }
@@ -661,6 +1107,7 @@ impl<T: ReadSeek> Reader<T> {
             } else if lls.var_state.current_type < lls.ddh.last_variable_size_data_type as u32 {
                 // variable size
                 while lls.var_state.current_type < col.identifier {
+                    lls.check_in_record(lls.offset_ddh + lls.var_state.type_offset as u64, 2)?;
                     let variable_size_data_type_size: u16 =
                         read_u16(self, lls.offset_ddh + lls.var_state.type_offset as u64)?;
                     lls.var_state.type_offset += 2;
@@ -670,12 +1117,30 @@ impl<T: ReadSeek> Reader<T> {
                     {
                         let var_offset = lls.offset_ddh + lls.var_state.value_offset as u64;
                         let var_size = variable_size_data_type_size
-                            - lls.previous_variable_size_data_type_size;
-
-                        lls.var_state.value_offset += var_size;
+                            .checked_sub(lls.previous_variable_size_data_type_size)
+                            .ok_or_else(|| {
+                                EseError::new(format!(
+                                    "column {}: variable data end {} is before the previous one {}",
+                                    col.identifier,
+                                    variable_size_data_type_size,
+                                    lls.previous_variable_size_data_type_size
+                                ))
+                            })?;
+
+                        lls.var_state.value_offset = lls
+                            .var_state
+                            .value_offset
+                            .checked_add(var_size)
+                            .ok_or_else(|| {
+                                lls.locate_error(EseError::new(format!(
+                                    "column {}: variable data size {} is out of the record",
+                                    col.identifier, var_size
+                                )))
+                            })?;
                         lls.previous_variable_size_data_type_size = variable_size_data_type_size;
 
                         if col.identifier == column_id {
+                            lls.check_in_record(var_offset, var_size as u64)?;
                             let v = self.read_bytes(var_offset, var_size as usize)?;
                             return Ok(Some(v));
                         }
@@ -687,10 +1152,17 @@ impl<T: ReadSeek> Reader<T> {
             } else {
                 // tagged
                 if tagged_data_types_format == jet::TaggedDataTypesFormats::Linear {
-                    // TODO
-                    println!(
-                        "TODO tagged_data_types_format ==-- jet::TaggedDataTypesFormats::Linear"
-                    );
+                    if col.identifier == column_id {
+                        let v = self.load_tagged_data_linear_format(
+                            lls,
+                            lv_tags,
+                            col,
+                            multi_value_index,
+                        )?;
+                        if v.is_some() {
+                            return Ok(v);
+                        }
+                    }
                 } else if tagged_data_types_format == jet::TaggedDataTypesFormats::Index {
                     match self.load_tagged_data_linear(
                         lv_tags,
@@ -702,8 +1174,9 @@ impl<T: ReadSeek> Reader<T> {
                         lls.offset_ddh,
                         lls.record_data_size,
                         multi_value_index,
+                        &mut lls.tagged_column,
                     ) {
-                        Err(e) => return Err(e),
+                        Err(e) => return Err(lls.locate_error(e)),
                         Ok(r) => {
                             if r.is_some() {
                                 return Ok(r);
@@ -715,7 +1188,7 @@ impl<T: ReadSeek> Reader<T> {
             // column not found?
             if col.identifier == column_id {
                 // default present?
-                if !col.default_value.is_empty() {
+                if !col.default_value.is_empty() && multi_value_index <= 1 {
                     return Ok(Some(col.default_value.clone()));
                 }
                 // empty
@@ -723,7 +1196,65 @@ impl<T: ReadSeek> Reader<T> {
             }
         }
 
-        Err(SimpleError::new(format!("column {} not found", column_id)))
+        Err(EseError::ColumnNotFound(column_id))
+    }
+
+    // tagged data of format revision 0x620/2 and older, following the variable size data
+    fn load_tagged_data_linear_format(
+        &self,
+        lls: &LastLoadState,
+        lv_tags: &LV_tags,
+        col: &jet::CatalogDefinition,
+        multi_value_index: usize,
+    ) -> Result<Option<Vec<u8>>, EseError> {
+        let types_offset = lls.ddh.variable_size_data_types_offset as u64;
+        let number_of_variable_size_data_types =
+            (lls.ddh.last_variable_size_data_type as u64).saturating_sub(127);
+        let mut tagged_data_offset = types_offset + number_of_variable_size_data_types * 2;
+        if number_of_variable_size_data_types > 0 {
+            // the sizes are cumulative, the last one is the size of all the variable size data
+            let last_size_offset = types_offset + (number_of_variable_size_data_types - 1) * 2;
+            tagged_data_offset +=
+                (read_u16(self, lls.offset_ddh + last_size_offset)? & 0x7fff) as u64;
+        }
+        if tagged_data_offset >= lls.record_data_size {
+            return Ok(None);
+        }
+        let data = self.read_bytes(
+            lls.offset_ddh + tagged_data_offset,
+            (lls.record_data_size - tagged_data_offset) as usize,
+        )?;
+        self.linear_tagged_value(&data, lv_tags, col, multi_value_index)
+    }
+
+    // value of the column in linear tagged data: long values are read from the long value tree,
+    // multi-values are split
+    pub(crate) fn linear_tagged_value(
+        &self,
+        data: &[u8],
+        lv_tags: &LV_tags,
+        col: &jet::CatalogDefinition,
+        multi_value_index: usize,
+    ) -> Result<Option<Vec<u8>>, EseError> {
+        use jet::TaggedDataTypeFlag;
+        let found = find_linear_tagged_value(data, col.identifier, multi_value_index)?;
+        let (dtf, value) = match found {
+            Some(found) => found,
+            None => return Ok(None),
+        };
+        let compressed = jet::ColumnFlags::from_bits_truncate(col.flags)
+            .intersects(jet::ColumnFlags::Compressed);
+        let multi_value = TaggedDataTypeFlag::MULTI_VALUE | TaggedDataTypeFlag::MULTI_VALUE_OFFSET;
+        if dtf.intersects(multi_value) {
+            self.multi_value(value, &dtf, multi_value_index, lv_tags, compressed)
+        } else if dtf.intersects(TaggedDataTypeFlag::LONG_VALUE) {
+            let key = lv_key_from_bytes(value)?;
+            Ok(Some(self.load_lv_data(lv_tags, key, compressed)?))
+        } else if dtf.intersects(TaggedDataTypeFlag::COMPRESSED) {
+            Ok(Some(decompress_data(value.to_vec())?))
+        } else {
+            Ok(Some(value.to_vec()))
+        }
     }
 
     fn init_tag_state(
@@ -733,13 +1264,19 @@ impl<T: ReadSeek> Reader<T> {
         offset: &mut u64,
         offset_ddh: u64,
         record_data_size: u64,
-    ) -> Result<Option<Vec<u8>>, SimpleError> {
+    ) -> Result<Option<Vec<u8>>, EseError> {
         tag_state.types_offset = var_state.value_offset;
 
-        tag_state.remaining_definition_data_size = (record_data_size
-            - tag_state.types_offset as u64)
+        tag_state.remaining_definition_data_size = record_data_size
+            .checked_sub(tag_state.types_offset as u64)
+            .ok_or_else(|| {
+                EseError::new(format!(
+                    "tagged data offset {} is past the record size {}",
+                    tag_state.types_offset, record_data_size
+                ))
+            })?
             .try_into()
-            .map_err(|e: std::num::TryFromIntError| SimpleError::new(e.to_string()))?;
+            .map_err(|e: std::num::TryFromIntError| EseError::new(e.to_string()))?;
 
         *offset = offset_ddh + tag_state.types_offset as u64;
 
@@ -751,13 +1288,69 @@ impl<T: ReadSeek> Reader<T> {
             *offset += 2;
 
             if tag_state.type_offset == 0 {
-                return Err(SimpleError::new("tag_state.type_offset == 0"));
+                return Err(EseError::new("tag_state.type_offset == 0"));
             }
-            tag_state.offset_data_size = (tag_state.type_offset & 0x3fff) - 4;
-            tag_state.remaining_definition_data_size -= 4;
+            tag_state.offset_data_size = sub_size(
+                tag_state.type_offset & self.tagged_data_type_offset_bitmask(),
+                4,
+                "tagged data offsets size",
+            )?;
+            tag_state.remaining_definition_data_size = sub_size(
+                tag_state.remaining_definition_data_size,
+                4,
+                "tagged data size",
+            )?;
         }
         Ok(None)
     }
+
+    // reads the size and the data type flags of the current tagged data,
+    // returns the flags and the offset of the value (relative to the data definition header)
+    fn read_tagged_data_type_flags(
+        &self,
+        tag_state: &mut TaggedDataState,
+        previous_tagged_data_type_offset: u16,
+        offset_ddh: u64,
+    ) -> Result<(u8, u16), EseError> {
+        let tagged_data_type_offset_bitmask = self.tagged_data_type_offset_bitmask();
+        let masked_previous_tagged_data_type_offset: u16 =
+            previous_tagged_data_type_offset & tagged_data_type_offset_bitmask;
+        let masked_tagged_data_type_offset =
+            tag_state.type_offset & tagged_data_type_offset_bitmask;
+
+        if masked_tagged_data_type_offset > masked_previous_tagged_data_type_offset {
+            tag_state.tagged_data_type_size =
+                masked_tagged_data_type_offset - masked_previous_tagged_data_type_offset;
+        } else {
+            tag_state.tagged_data_type_size = tag_state.remaining_definition_data_size;
+        }
+        let mut tagged_data_type_value_offset = tag_state
+            .types_offset
+            .checked_add(masked_previous_tagged_data_type_offset)
+            .ok_or_else(|| {
+                EseError::new(format!(
+                    "tagged data offset {} is out of the record",
+                    masked_previous_tagged_data_type_offset
+                ))
+            })?;
+        let mut data_type_flags: u8 = 0;
+        if tag_state.tagged_data_type_size > 0 {
+            tag_state.remaining_definition_data_size = sub_size(
+                tag_state.remaining_definition_data_size,
+                tag_state.tagged_data_type_size,
+                "tagged data size",
+            )?;
+            if self.is_large_page_format() || (previous_tagged_data_type_offset & 0x4000) != 0
+            {
+                data_type_flags = read_u8(self, offset_ddh + tagged_data_type_value_offset as u64)?;
+
+                tagged_data_type_value_offset += 1;
+                tag_state.tagged_data_type_size -= 1;
+            }
+        }
+        Ok((data_type_flags, tagged_data_type_value_offset))
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn load_tagged_data_linear(
         &self,
@@ -770,7 +1363,8 @@ impl<T: ReadSeek> Reader<T> {
         offset_ddh: u64,
         record_data_size: u64,
         multi_value_index: usize,
-    ) -> Result<Option<Vec<u8>>, SimpleError> {
+        tagged_column: &mut TaggedColumnLoad,
+    ) -> Result<Option<Vec<u8>>, EseError> {
         if tag_state.types_offset == 0 {
             self.init_tag_state(tag_state, *var_state, offset, offset_ddh, record_data_size)?;
         }
@@ -785,47 +1379,46 @@ impl<T: ReadSeek> Reader<T> {
                 tag_state.type_offset = read_u16(self, *offset)?;
                 *offset += 2;
 
-                tag_state.offset_data_size -= 4;
-                tag_state.remaining_definition_data_size -= 4;
+                tag_state.offset_data_size =
+                    sub_size(tag_state.offset_data_size, 4, "tagged data offsets size")?;
+                tag_state.remaining_definition_data_size = sub_size(
+                    tag_state.remaining_definition_data_size,
+                    4,
+                    "tagged data size",
+                )?;
             }
 
-            let tagged_data_type_offset_bitmask: u16;
-            if self.format_revision >= ESEDB_FORMAT_REVISION_EXTENDED_PAGE_HEADER
-                && self.page_size >= 16384
-            {
-                tagged_data_type_offset_bitmask = 0x7fff;
-            } else {
-                tagged_data_type_offset_bitmask = 0x3fff;
-            }
-            let masked_previous_tagged_data_type_offset: u16 =
-                previous_tagged_data_type_offset & tagged_data_type_offset_bitmask;
-            let masked_tagged_data_type_offset =
-                tag_state.type_offset & tagged_data_type_offset_bitmask;
-
-            if masked_tagged_data_type_offset > masked_previous_tagged_data_type_offset {
-                tag_state.tagged_data_type_size =
-                    masked_tagged_data_type_offset - masked_previous_tagged_data_type_offset;
-            } else {
-                tag_state.tagged_data_type_size = tag_state.remaining_definition_data_size;
+            let (data_type_flags, tagged_data_type_value_offset) = self
+                .read_tagged_data_type_flags(
+                    tag_state,
+                    previous_tagged_data_type_offset,
+                    offset_ddh,
+                )?;
+            let dtf = jet::TaggedDataTypeFlag::from_bits_truncate(data_type_flags as u16);
+            if col.identifier == column_id {
+                tagged_column.data_type_flags = Some(dtf);
             }
-            let mut tagged_data_type_value_offset =
-                tag_state.types_offset + masked_previous_tagged_data_type_offset;
-            let mut data_type_flags: u8 = 0;
-            if tag_state.tagged_data_type_size > 0 {
-                tag_state.remaining_definition_data_size -= tag_state.tagged_data_type_size;
-                if (self.format_revision >= ESEDB_FORMAT_REVISION_EXTENDED_PAGE_HEADER
-                    && self.page_size >= 16384)
-                    || (previous_tagged_data_type_offset & 0x4000) != 0
-                {
-                    data_type_flags =
-                        read_u8(self, offset_ddh + tagged_data_type_value_offset as u64)?;
-
-                    tagged_data_type_value_offset += 1;
-                    tag_state.tagged_data_type_size -= 1;
-                }
+            if col.identifier == column_id && tagged_column.mode == TaggedLoadMode::ValueCount {
+                tagged_column.value_count = Some(self.count_tagged_values(
+                    offset_ddh + tagged_data_type_value_offset as u64,
+                    tag_state.tagged_data_type_size,
+                    &dtf,
+                )?);
+                return Ok(None);
             }
             if tag_state.tagged_data_type_size > 0 && col.identifier == column_id {
                 let value_offset = offset_ddh + tagged_data_type_value_offset as u64;
+                if dtf.intersects(jet::TaggedDataTypeFlag::LONG_VALUE)
+                    && !dtf.intersects(
+                        jet::TaggedDataTypeFlag::MULTI_VALUE
+                            | jet::TaggedDataTypeFlag::MULTI_VALUE_OFFSET,
+                    )
+                {
+                    tagged_column.lv_key = Some(self.read_lv_key(value_offset)?);
+                    if tagged_column.mode == TaggedLoadMode::LvKey {
+                        return Ok(None);
+                    }
+                }
                 match self.load_tagged_column(
                     lv_tags,
                     col,
@@ -849,7 +1442,7 @@ impl<T: ReadSeek> Reader<T> {
     fn read_lv_key(
         &self,
         offset: u64)
-    -> Result<u64, SimpleError> {
+    -> Result<u64, EseError> {
         let key;
         let mut bytes = self.read_bytes(offset, 4)?;
         // if fLID64 is set, this is LVKEY64
@@ -870,7 +1463,7 @@ impl<T: ReadSeek> Reader<T> {
         tagged_data_type_size: u16,
         data_type_flags: u8,
         multi_value_index: usize,
-    ) -> Result<Option<Vec<u8>>, SimpleError> {
+    ) -> Result<Option<Vec<u8>>, EseError> {
         let mut v = Vec::new();
 
         use jet::ColumnFlags;
@@ -879,6 +1472,14 @@ impl<T: ReadSeek> Reader<T> {
         let col_flag = ColumnFlags::from_bits_truncate(col.flags);
         let compressed = col_flag.intersects(ColumnFlags::Compressed);
         let dtf = TaggedDataTypeFlag::from_bits_truncate(data_type_flags as u16);
+        if multi_value_index > 1
+            && !dtf.intersects(
+                TaggedDataTypeFlag::MULTI_VALUE | TaggedDataTypeFlag::MULTI_VALUE_OFFSET,
+            )
+        {
+            // not a multi-value, there is only the first value
+            return Ok(None);
+        }
         if dtf.intersects(TaggedDataTypeFlag::LONG_VALUE) {
             v = self.load_lv_data(lv_tags, self.read_lv_key(offset)?, compressed)?;
         } else if dtf
@@ -896,11 +1497,7 @@ impl<T: ReadSeek> Reader<T> {
                 v = mv_data;
             }
         } else if dtf.intersects(jet::TaggedDataTypeFlag::COMPRESSED) {
-            v = self.read_bytes(offset, tagged_data_type_size as usize)?;
-            let dsize = decompress_size(&v);
-            if dsize > 0 {
-                v = decompress_buf(&v, dsize)?;
-            }
+            v = decompress_data(self.read_bytes(offset, tagged_data_type_size as usize)?)?;
         } else {
             v = self.read_bytes(offset, tagged_data_type_size as usize)?;
         }
@@ -911,6 +1508,26 @@ impl<T: ReadSeek> Reader<T> {
         Ok(None)
     }
 
+    // number of values of a tagged column, the values themselves aren't read
+    fn count_tagged_values(
+        &self,
+        offset: u64,
+        tagged_data_type_size: u16,
+        dtf: &jet::TaggedDataTypeFlag,
+    ) -> Result<u32, EseError> {
+        if tagged_data_type_size == 0 {
+            Ok(0)
+        } else if dtf.intersects(jet::TaggedDataTypeFlag::MULTI_VALUE_OFFSET) {
+            // the first value, and the second one after it
+            Ok(2)
+        } else if dtf.intersects(jet::TaggedDataTypeFlag::MULTI_VALUE) {
+            // the offset to the first value follows the offsets of all the values
+            Ok(((read_u16(self, offset)? & 0x7fff) / 2) as u32)
+        } else {
+            Ok(1)
+        }
+    }
+
     fn read_multi_value(
         &self,
         offset: u64,
@@ -919,73 +1536,31 @@ impl<T: ReadSeek> Reader<T> {
         multi_value_index: usize,
         lv_tags: &LV_tags,
         compressed: bool,
-    ) -> Result<Option<Vec<u8>>, SimpleError> {
-        let mut mv_indexes: Vec<(u16 /*shift*/, (bool /*lv*/, u16 /*size*/))> = Vec::new();
-        if dtf.intersects(jet::TaggedDataTypeFlag::MULTI_VALUE_OFFSET) {
-            // The first byte contain the offset
-            // [13, ...]
-            let offset_mv_list = offset;
-            let value: u16 = read_u8(self, offset_mv_list)? as u16;
-
-            mv_indexes.push((1, (false, value)));
-            mv_indexes.push((value + 1, (false, tagged_data_type_size - value - 1)));
-        } else if dtf.intersects(jet::TaggedDataTypeFlag::MULTI_VALUE) {
-            // The first 2 bytes contain the offset to the first value
-            // there is an offset for every value
-            // therefore first offset / 2 = the number of value entries
-            // [8, 0, 7, 130, 11, 2, 10, 131, ...]
-            let mut offset_mv_list = offset;
-            let mut value = read_u16(self, offset_mv_list)?;
-            offset_mv_list += 2;
-
-            let mut value_entry_size: u16;
-            let mut value_entry_offset = value & 0x7fff;
-            let mut entry_lvbit: bool = (value & 0x8000) > 0;
-            let number_of_value_entries = value_entry_offset / 2;
-
-            for _ in 1..number_of_value_entries {
-                value = read_u16(self, offset_mv_list)?;
-                offset_mv_list += 2;
-                value_entry_size = (value & 0x7fff) - value_entry_offset;
-                mv_indexes.push((value_entry_offset, (entry_lvbit, value_entry_size)));
-                entry_lvbit = (value & 0x8000) > 0;
-                value_entry_offset = value & 0x7fff;
-            }
-            value_entry_size = tagged_data_type_size - value_entry_offset;
-            mv_indexes.push((value_entry_offset, (entry_lvbit, value_entry_size)));
-        } else {
-            return Err(SimpleError::new(format!(
-                "Unknown TaggedDataTypeFlag: {}",
-                dtf.bits()
-            )));
-        }
-        let mut mv_index = 0;
-        if multi_value_index > 0 && multi_value_index - 1 < mv_indexes.len() {
-            mv_index = multi_value_index - 1;
-        }
+    ) -> Result<Option<Vec<u8>>, EseError> {
+        let data = self.read_bytes(offset, tagged_data_type_size as usize)?;
+        self.multi_value(&data, dtf, multi_value_index, lv_tags, compressed)
+    }
 
-        if mv_index < mv_indexes.len() {
-            let (shift, (lv, size)) = mv_indexes[mv_index];
-            let v;
-            if lv {
-                v = self.load_lv_data(
-                        lv_tags,
-                        self.read_lv_key(offset + shift as u64)?,
-                        compressed
-                    )?;
-            } else {
-                v = self.read_bytes(offset + shift as u64, size as usize)?;
-                if compressed {
-                    let dsize = decompress_size(&v);
-                    if dsize > 0 {
-                        let dv = decompress_buf(&v, dsize)?;
-                        return Ok(Some(dv));
-                    }
-                }
+    // value `multi_value_index` (0 and 1 are the first one) of the multi-value `data`,
+    // long values are read from the long value tree
+    fn multi_value(
+        &self,
+        data: &[u8],
+        dtf: &jet::TaggedDataTypeFlag,
+        multi_value_index: usize,
+        lv_tags: &LV_tags,
+        compressed: bool,
+    ) -> Result<Option<Vec<u8>>, EseError> {
+        let values = split_multi_value(data, dtf)?;
+        match values.get(multi_value_index.saturating_sub(1)) {
+            Some(&(true, key)) => {
+                let key = lv_key_from_bytes(key)?;
+                Ok(Some(self.load_lv_data(lv_tags, key, compressed)?))
             }
-            return Ok(Some(v));
+            Some(&(false, v)) if compressed => Ok(Some(decompress_data(v.to_vec())?)),
+            Some(&(false, v)) => Ok(Some(v.to_vec())),
+            None => Ok(None),
         }
-        Ok(None)
     }
 
     pub fn load_lv_tag(
@@ -993,7 +1568,7 @@ impl<T: ReadSeek> Reader<T> {
         db_page: &jet::DbPage,
         page_tag: &PageTag,
         page_tag_0: &PageTag,
-    ) -> Result<Option<LV_tags>, SimpleError> {
+    ) -> Result<Option<LV_tags>, EseError> {
         let mut offset = page_tag.offset(db_page);
         let page_tag_offset: u64 = offset;
 
@@ -1033,7 +1608,17 @@ impl<T: ReadSeek> Reader<T> {
             offset += local_page_key_size as u64;
         }
 
-        if (page_tag.size as u64) - (offset - page_tag_offset) == 8 {
+        let data_size = (page_tag.size as u64)
+            .checked_sub(offset - page_tag_offset)
+            .ok_or_else(|| {
+                EseError::new(format!(
+                    "page {}: long value key size {} is past the tag size {}",
+                    db_page.page_number,
+                    offset - page_tag_offset,
+                    page_tag.size
+                ))
+            })?;
+        if data_size == 8 {
             //let _skey: u32 = reader.read_struct(offset)?;
             //offset += 4;
             //let _total_size : u32 = reader.read_struct(offset)?;
@@ -1053,11 +1638,17 @@ impl<T: ReadSeek> Reader<T> {
 
             let skey : u64;
             let mut seg_offset : u32 = 0;
+            if page_key.len() < 4 {
+                return Err(EseError::new(format!(
+                    "page {}: wrong long value key {:?}",
+                    db_page.page_number, page_key
+                )));
+            }
             // LVKEY64 (LID64, ULONG offset)
             if page_key.len() == 12 {
                 skey =
                     u64::from_le_bytes(page_key[0..8].try_into().map_err(|e: TryFromSliceError| {
-                        SimpleError::new(format!(
+                        EseError::new(format!(
                             "can't convert page_key {:?} into slice [0..8], error: {}",
                             page_key, e
                         ))
@@ -1067,14 +1658,14 @@ impl<T: ReadSeek> Reader<T> {
                 seg_offset = u32::from_le_bytes(
                     page_key[8..12]
                         .try_into()
-                        .map_err(|e: TryFromSliceError| SimpleError::new(e.to_string()))?,
+                        .map_err(|e: TryFromSliceError| EseError::new(e.to_string()))?,
                 )
                 .to_be();
             } else {
                 // LVKEY32 (LID32, ULONG offset)
                 skey =
                     u32::from_le_bytes(page_key[0..4].try_into().map_err(|e: TryFromSliceError| {
-                        SimpleError::new(format!(
+                        EseError::new(format!(
                             "can't convert page_key {:?} into slice [0..4], error: {}",
                             page_key, e
                         ))
@@ -1085,18 +1676,18 @@ impl<T: ReadSeek> Reader<T> {
                     seg_offset = u32::from_le_bytes(
                         page_key[4..8]
                             .try_into()
-                            .map_err(|e: TryFromSliceError| SimpleError::new(e.to_string()))?,
+                            .map_err(|e: TryFromSliceError| EseError::new(e.to_string()))?,
                     )
                     .to_be();
                 }
             }
 
             res.offset = offset;
-            res.size = (page_tag.size as u64 - (offset - page_tag_offset))
+            res.size = data_size
                 .try_into()
-                .map_err(|e: std::num::TryFromIntError| SimpleError::new(e.to_string()))?;
+                .map_err(|e: std::num::TryFromIntError| EseError::new(e.to_string()))?;
 
-            let mut t: HashMap<u32, LV_tag> = HashMap::new();
+            let mut t: BTreeMap<u32, LV_tag> = BTreeMap::new();
             t.insert(seg_offset, res);
             let mut new_tag: LV_tags = HashMap::new();
             new_tag.insert(skey, t);
@@ -1105,84 +1696,23 @@ impl<T: ReadSeek> Reader<T> {
         }
     }
 
-    pub fn load_lv_metadata(&self, page_number: u32) -> Result<LV_tags, SimpleError> {
+    pub fn load_lv_metadata(&self, page_number: u32) -> Result<LV_tags, EseError> {
         let db_page = jet::DbPage::new(self, page_number)?;
-        let pg_tags = &db_page.page_tags;
 
         if !db_page.flags().contains(jet::PageFlags::IS_LONG_VALUE) {
-            return Err(SimpleError::new(format!(
+            return Err(EseError::new(format!(
                 "pageno {}: IS_LONG_VALUE flag should be present",
                 db_page.page_number
             )));
         }
 
-        let mut tags: LV_tags = HashMap::new();
-
-        if !db_page.flags().contains(jet::PageFlags::IS_LEAF) {
-            let mut prev_page_number = page_number;
-            let mut page_number =
-                self.page_tag_get_branch_child_page_number(&db_page, &pg_tags[1])?;
-            while page_number != 0 {
-                let db_page = jet::DbPage::new(self, page_number)?;
-                let pg_tags = &db_page.page_tags;
-
-                if db_page.prev_page() != 0 && prev_page_number != db_page.prev_page() {
-                    return Err(SimpleError::new(format!(
-                        "pageno {}: wrong previous_page number {}, expected {}",
-                        db_page.page_number,
-                        db_page.prev_page(),
-                        prev_page_number
-                    )));
-                }
-                if !db_page
-                    .flags()
-                    .contains(jet::PageFlags::IS_LEAF | jet::PageFlags::IS_LONG_VALUE)
-                {
-                    // maybe it's "Parent of leaf" page
-                    let r = self.load_lv_metadata(page_number);
-                    match r {
-                        Ok(new_tags) => {
-                            merge_lv_tags(&mut tags, new_tags);
-                        }
-                        Err(e) => {
-                            return Err(e);
-                        }
-                    }
-                } else {
-                    for i in 1..pg_tags.len() {
-                        if jet::PageTagFlags::from_bits_truncate(pg_tags[i].flags)
-                            .intersects(jet::PageTagFlags::FLAG_IS_DEFUNCT)
-                        {
-                            continue;
-                        }
-
-                        match self.load_lv_tag(&db_page, &pg_tags[i], &pg_tags[0]) {
-                            Ok(r) => {
-                                if let Some(new_tag) = r {
-                                    merge_lv_tags(&mut tags, new_tag);
-                                }
-                            }
-                            Err(e) => return Err(e),
-                        }
-                    }
-                }
-                prev_page_number = page_number;
-                page_number = db_page.next_page();
-            }
-        } else {
-            for i in 1..pg_tags.len() {
-                match self.load_lv_tag(&db_page, &pg_tags[i], &pg_tags[0]) {
-                    Ok(r) => {
-                        if let Some(new_tag) = r {
-                            merge_lv_tags(&mut tags, new_tag);
-                        }
-                    }
-                    Err(e) => return Err(e),
-                }
-            }
-        }
-
-        Ok(tags)
+        let mut visitor = LvTreeVisitor {
+            reader: self,
+            tags: HashMap::new(),
+            previous_leaf: None,
+        };
+        walk_btree(self, page_number, &mut visitor)?;
+        Ok(visitor.tags)
     }
 
     pub fn load_lv_data(
@@ -1190,38 +1720,104 @@ impl<T: ReadSeek> Reader<T> {
         lv_tags: &LV_tags,
         long_value_key: u64,
         compressed: bool,
-    ) -> Result<Vec<u8>, SimpleError> {
+    ) -> Result<Vec<u8>, EseError> {
         let mut res: Vec<u8> = vec![];
-        if lv_tags.contains_key(&long_value_key) {
-            let seg_offsets = lv_tags.get(&long_value_key).expect("No long value key");
-            loop {
-                let offset = res.len() as u32;
-                if seg_offsets.contains_key(&offset) {
-                    let tag = seg_offsets.get(&offset).expect("No offset");
-                    let mut v = self.read_bytes(tag.offset, tag.size as usize)?;
-                    if compressed {
-                        let dsize = decompress_size(&v);
-                        if dsize > 0 {
-                            v = decompress_buf(&v, dsize)?;
-                        }
-                    }
-                    res.append(&mut v);
-                    // search next offset
-                } else {
-                    break;
+        if let Some(segments) = lv_tags.get(&long_value_key) {
+            // segments are sorted by offset, the value ends at the first gap
+            for (&seg_offset, tag) in segments {
+                match (seg_offset as usize).cmp(&res.len()) {
+                    // overlapping segment
+                    std::cmp::Ordering::Less => continue,
+                    std::cmp::Ordering::Greater => break,
+                    std::cmp::Ordering::Equal => {}
+                }
+                let mut v = self.read_bytes(tag.offset, tag.size as usize)?;
+                if compressed {
+                    v = decompress_data(v)?;
                 }
+                res.append(&mut v);
             }
         }
 
         if !res.is_empty() {
             Ok(res)
         } else {
-            Err(SimpleError::new(format!(
-                "LV key 0x{:X} not found",
-                long_value_key
-            )))
+            Err(EseError::LvKeyNotFound(long_value_key))
         }
     }
+
+    /// Streaming reader over the segments of a long value, in offset order.
+    /// A segment is read (and decompressed) only when the previous one is consumed.
+    pub fn lv_reader(
+        &self,
+        lv_tags: &LV_tags,
+        long_value_key: u64,
+        compressed: bool,
+    ) -> Result<LvReader<'_, T>, EseError> {
+        let segments = lv_tags
+            .get(&long_value_key)
+            .ok_or(EseError::LvKeyNotFound(long_value_key))?;
+        Ok(LvReader {
+            reader: self,
+            segments: segments
+                .iter()
+                .map(|(&seg_offset, tag)| (seg_offset, tag.offset, tag.size))
+                .collect(),
+            next_segment: 0,
+            compressed,
+            buffer: vec![],
+            buffer_pos: 0,
+            position: 0,
+        })
+    }
+}
+
+impl<T: ReadSeek + Send> Reader<CursorBackend<T>> {
+    /// Reads the database from any `Read + Seek` source, e.g. a `File` or a `Cursor`.
+    pub fn load_db(read_seek: T, cache_size: usize) -> Result<Self, EseError> {
+        Reader::from_backend(CursorBackend::new(read_seek), cache_size)
+    }
+
+    /// Same as `load_db`, the pages are verified by `checksum_mode` when they are loaded.
+    pub fn load_db_opts(
+        read_seek: T,
+        cache_size: usize,
+        checksum_mode: ChecksumMode,
+    ) -> Result<Self, EseError> {
+        Reader::from_backend_opts(CursorBackend::new(read_seek), cache_size, checksum_mode)
+    }
+}
+
+// the data is already in memory, the cache only holds the pages being read
+const IN_MEMORY_CACHE_SIZE: usize = 2;
+
+impl<D: AsRef<[u8]> + Send> Reader<MemoryBackend<D>> {
+    /// Reads a database held in memory (`&[u8]`, `Vec<u8>`...), e.g. from a memory dump
+    /// or a network capture.
+    pub fn from_memory(data: D) -> Result<Self, EseError> {
+        Reader::load_db(io::Cursor::new(data), IN_MEMORY_CACHE_SIZE)
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl Reader<MemoryBackend<memmap2::Mmap>> {
+    /// Maps the database file into memory, the pages missing from the cache are copied
+    /// from the mapping instead of being read from the file.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated while the reader exists.
+    pub unsafe fn load_db_mmap(
+        path: impl AsRef<Path>,
+        cache_size: usize,
+    ) -> Result<Self, EseError> {
+        let path = path.as_ref();
+        let file = std::fs::File::open(path)?;
+        let mmap = memmap2::Mmap::map(&file)?;
+        let mut reader = Reader::load_db(io::Cursor::new(mmap), cache_size)?;
+        reader.set_path(path);
+        Ok(reader)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -1232,7 +1828,327 @@ pub struct LV_tag {
     pub size: u32,
 }
 
-pub type LV_tags = HashMap<u64 /*key*/, HashMap<u32 /*seg_offset*/, LV_tag>>;
+pub type LV_tags = HashMap<u64 /*key*/, BTreeMap<u32 /*seg_offset*/, LV_tag>>;
+
+/// Long value read segment by segment, see `Reader::lv_reader`.
+pub struct LvReader<'a, T: ReaderBackend> {
+    reader: &'a Reader<T>,
+    // seg_offset, file offset and size of the segments
+    segments: Vec<(u32, u64, u32)>,
+    next_segment: usize,
+    compressed: bool,
+    buffer: Vec<u8>,
+    buffer_pos: usize,
+    // size of the value up to the end of the buffer
+    position: u64,
+}
+
+impl<'a, T: ReaderBackend> LvReader<'a, T> {
+    // value stored in the record, there are no segments to read
+    pub(crate) fn from_value(reader: &'a Reader<T>, value: Vec<u8>) -> Self {
+        LvReader {
+            reader,
+            segments: vec![],
+            next_segment: 0,
+            compressed: false,
+            position: value.len() as u64,
+            buffer: value,
+            buffer_pos: 0,
+        }
+    }
+
+    // as load_lv_data, overlapping segments are skipped and the value ends at the first gap
+    fn load_next_segment(&mut self) -> Result<bool, EseError> {
+        while let Some(&(seg_offset, offset, size)) = self.segments.get(self.next_segment) {
+            self.next_segment += 1;
+            match (seg_offset as u64).cmp(&self.position) {
+                std::cmp::Ordering::Less => continue,
+                std::cmp::Ordering::Greater => break,
+                std::cmp::Ordering::Equal => {}
+            }
+            let mut v = self.reader.read_bytes(offset, size as usize)?;
+            if self.compressed {
+                v = decompress_data(v)?;
+            }
+            self.position += v.len() as u64;
+            self.buffer = v;
+            self.buffer_pos = 0;
+            return Ok(true);
+        }
+        self.next_segment = self.segments.len();
+        Ok(false)
+    }
+}
+
+impl<T: ReaderBackend> Read for LvReader<'_, T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.buffer_pos == self.buffer.len() {
+            let loaded = self.load_next_segment().map_err(io::Error::other)?;
+            if !loaded {
+                return Ok(0);
+            }
+        }
+        let n = buf.len().min(self.buffer.len() - self.buffer_pos);
+        buf[..n].copy_from_slice(&self.buffer[self.buffer_pos..self.buffer_pos + n]);
+        self.buffer_pos += n;
+        Ok(n)
+    }
+}
+
+pub type BTreeEntry = (Vec<u8> /*key*/, Vec<u8> /*value*/);
+
+// segments of the leaf pages of a long-value tree, which have to be chained in key order
+struct LvTreeVisitor<'a, T: ReaderBackend> {
+    reader: &'a Reader<T>,
+    tags: LV_tags,
+    // (page number, next page) of the previous leaf page
+    previous_leaf: Option<(u32, u32)>,
+}
+
+impl<'a, T: ReaderBackend> BTreeVisitor for LvTreeVisitor<'a, T> {
+    fn visit_leaf_page(&mut self, page: &jet::DbPage) -> Result<VisitDecision, EseError> {
+        if let Some((previous_page, next_page)) = self.previous_leaf {
+            if page.prev_page() != previous_page {
+                return Err(EseError::new(format!(
+                    "pageno {}: wrong previous_page number {}, expected {}",
+                    page.page_number,
+                    page.prev_page(),
+                    previous_page
+                )));
+            }
+            if next_page != page.page_number {
+                return Err(EseError::new(format!(
+                    "pageno {}: wrong next_page number {}, expected {}",
+                    previous_page, next_page, page.page_number
+                )));
+            }
+        }
+        self.previous_leaf = Some((page.page_number, page.next_page()));
+        Ok(VisitDecision::Continue)
+    }
+
+    fn visit_tag(
+        &mut self,
+        page: &jet::DbPage,
+        tag: &PageTag,
+        _tag_index: usize,
+    ) -> Result<VisitDecision, EseError> {
+        if page.flags().contains(jet::PageFlags::IS_LEAF) {
+            if let Some(new_tag) = self.reader.load_lv_tag(page, tag, &page.page_tags[0])? {
+                merge_lv_tags(&mut self.tags, new_tag);
+            }
+        }
+        Ok(VisitDecision::Continue)
+    }
+}
+
+struct SpaceTreeVisitor<'a, T: ReaderBackend> {
+    reader: &'a Reader<T>,
+    extents: Vec<(u32, u32)>,
+}
+
+impl<'a, T: ReaderBackend> BTreeVisitor for SpaceTreeVisitor<'a, T> {
+    fn visit_tag(
+        &mut self,
+        page: &jet::DbPage,
+        tag: &PageTag,
+        _tag_index: usize,
+    ) -> Result<VisitDecision, EseError> {
+        if page.flags().contains(jet::PageFlags::IS_LEAF) {
+            if let Some(extent) = self.reader.load_space_tree_entry(page, tag)? {
+                self.extents.push(extent);
+            }
+        }
+        Ok(VisitDecision::Continue)
+    }
+}
+
+// branch keys are upper bounds of the keys of their child pages (empty for the last one)
+struct KeyPrefixVisitor<'a, 'p, T: ReaderBackend> {
+    reader: &'a Reader<T>,
+    prefix: &'p [u8],
+    entries: Vec<BTreeEntry>,
+}
+
+impl<'a, 'p, T: ReaderBackend> BTreeVisitor for KeyPrefixVisitor<'a, 'p, T> {
+    fn visit_tag(
+        &mut self,
+        page: &jet::DbPage,
+        tag: &PageTag,
+        _tag_index: usize,
+    ) -> Result<VisitDecision, EseError> {
+        let key = self.reader.load_page_tag_key(page, tag)?;
+        let matches = key.starts_with(self.prefix);
+        if !page.flags().contains(jet::PageFlags::IS_LEAF) {
+            if !key.is_empty() && !matches && key.as_slice() < self.prefix {
+                return Ok(VisitDecision::SkipSubtree);
+            }
+            return Ok(VisitDecision::Continue);
+        }
+        if matches {
+            let value = self.reader.load_page_tag_value(page, tag)?;
+            self.entries.push((key, value));
+        } else if key.as_slice() > self.prefix {
+            return Ok(VisitDecision::Stop);
+        }
+        Ok(VisitDecision::Continue)
+    }
+}
+
+// the walk descends the first live child of each branch page, the first leaf page reached ends it
+struct FirstLeafVisitor {
+    leaf_page: Option<u32>,
+}
+
+impl BTreeVisitor for FirstLeafVisitor {
+    fn visit_leaf_page(&mut self, page: &jet::DbPage) -> Result<VisitDecision, EseError> {
+        self.leaf_page = Some(page.page_number);
+        Ok(VisitDecision::Stop)
+    }
+}
+
+// 4 bytes segments: flags (1 - descending) and column identifier,
+// older formats use 2 bytes signed column identifiers, negative for descending order
+fn read_index_key_segments(
+    key: &[u8],
+    format_revision: jet::FormatRevision,
+) -> Vec<jet::IndexKeySegment> {
+    if format_revision >= ESEDB_FORMAT_REVISION_NEW_RECORD_FORMAT {
+        key.chunks_exact(4)
+            .map(|s| jet::IndexKeySegment {
+                column_identifier: LittleEndian::read_u16(&s[2..]) as u32,
+                descending: LittleEndian::read_u16(s) & 0x1 != 0,
+            })
+            .collect()
+    } else {
+        key.chunks_exact(2)
+            .map(|s| {
+                let id = LittleEndian::read_i16(s);
+                jet::IndexKeySegment {
+                    column_identifier: id.unsigned_abs() as u32,
+                    descending: id < 0,
+                }
+            })
+            .collect()
+    }
+}
+
+// size arithmetic of the record data, which is corrupt when the result would be negative
+fn sub_size(size: u16, sub: u16, what: &str) -> Result<u16, EseError> {
+    size.checked_sub(sub)
+        .ok_or_else(|| EseError::new(format!("wrong {}: {} is less than {}", what, size, sub)))
+}
+
+// Linear tagged data: identifier (u16), size (u16) and data of each value, in identifier order.
+// Like the offsets of the indexed format, the size has flag bits: with 0x4000 the data starts with
+// its data type flags (jet::TaggedDataTypeFlag), 0x8000 is reserved. Returns the flags and the data
+// of the value; the values of a multi-valued column without the multi-value flags repeat its
+// identifier, with them the whole multi-value is returned.
+fn find_linear_tagged_value(
+    data: &[u8],
+    column_id: u32,
+    multi_value_index: usize, // 0 and 1 are the first value
+) -> Result<Option<(jet::TaggedDataTypeFlag, &[u8])>, EseError> {
+    let mut remaining = multi_value_index.max(1);
+    let mut offset = 0;
+    while offset + 4 <= data.len() {
+        let identifier = LittleEndian::read_u16(&data[offset..]) as u32;
+        let size_field = LittleEndian::read_u16(&data[offset + 2..]);
+        let size = (size_field & 0x3fff) as usize;
+        offset += 4;
+        if offset + size > data.len() {
+            return Err(EseError::new(format!(
+                "tagged data of column {} (size {}) is out of the record (size {})",
+                identifier,
+                size,
+                data.len()
+            )));
+        }
+        if identifier == column_id {
+            let mut value = &data[offset..offset + size];
+            let mut dtf = jet::TaggedDataTypeFlag::empty();
+            if size_field & 0x4000 != 0 && !value.is_empty() {
+                dtf = jet::TaggedDataTypeFlag::from_bits_truncate(value[0] as u16);
+                value = &value[1..];
+            }
+            if dtf.intersects(
+                jet::TaggedDataTypeFlag::MULTI_VALUE | jet::TaggedDataTypeFlag::MULTI_VALUE_OFFSET,
+            ) {
+                return Ok(Some((dtf, value)));
+            }
+            remaining -= 1;
+            if remaining == 0 {
+                return Ok(Some((dtf, value)));
+            }
+        } else if identifier > column_id {
+            break;
+        }
+        offset += size;
+    }
+    Ok(None)
+}
+
+// Values of a multi-valued tagged column, with whether each one is a long value key.
+// MULTI_VALUE_OFFSET: two values, the first byte is the size of the first one.
+// MULTI_VALUE: the offsets (u16, 0x8000 for long values) of all the values, then the values,
+// so the offset of the first value is twice the number of values.
+fn split_multi_value<'a>(
+    data: &'a [u8],
+    dtf: &jet::TaggedDataTypeFlag,
+) -> Result<Vec<(bool, &'a [u8])>, EseError> {
+    let out_of_value = |what: &str| {
+        EseError::new(format!(
+            "multi-value {} is out of the value (size {})",
+            what,
+            data.len()
+        ))
+    };
+    if dtf.intersects(jet::TaggedDataTypeFlag::MULTI_VALUE_OFFSET) {
+        let first_size = *data.first().ok_or_else(|| out_of_value("offset"))? as usize;
+        let first = data
+            .get(1..1 + first_size)
+            .ok_or_else(|| out_of_value("entry"))?;
+        Ok(vec![(false, first), (false, &data[1 + first_size..])])
+    } else if dtf.intersects(jet::TaggedDataTypeFlag::MULTI_VALUE) {
+        let entry = |i: usize| {
+            data.get(i * 2..i * 2 + 2)
+                .map(LittleEndian::read_u16)
+                .ok_or_else(|| out_of_value("offset"))
+        };
+        let count = ((entry(0)? & 0x7fff) / 2) as usize;
+        let mut values = Vec::with_capacity(count);
+        for i in 0..count {
+            let value_offset = entry(i)?;
+            let end = if i + 1 < count {
+                (entry(i + 1)? & 0x7fff) as usize
+            } else {
+                data.len()
+            };
+            let value = data
+                .get((value_offset & 0x7fff) as usize..end)
+                .ok_or_else(|| out_of_value("entry"))?;
+            values.push((value_offset & 0x8000 != 0, value));
+        }
+        Ok(values)
+    } else {
+        Err(EseError::new(format!(
+            "Unknown TaggedDataTypeFlag: {}",
+            dtf.bits()
+        )))
+    }
+}
+
+// long value key of a tagged value: 4 bytes, or 8 when fLID64 (0x80 of the 4th byte) is set
+fn lv_key_from_bytes(v: &[u8]) -> Result<u64, EseError> {
+    match v {
+        [_, _, _, b3, ..] if b3 & 0x80 == 0 => Ok(LittleEndian::read_u32(v) as u64),
+        _ if v.len() >= 8 => Ok(LittleEndian::read_u64(v)),
+        _ => Err(EseError::new(format!(
+            "long value key of {} bytes",
+            v.len()
+        ))),
+    }
+}
 
 fn merge_lv_tags(tags: &mut LV_tags, new_tags: LV_tags) {
     for (new_key, new_segs) in new_tags {
@@ -1250,18 +2166,49 @@ fn merge_lv_tags(tags: &mut LV_tags, new_tags: LV_tags) {
     }
 }
 
+// Columns of a derived table are stored in its template table definition,
+// the own columns of the derived table follow them in the records (template columns sort first).
+fn inherit_template_columns(tables: &mut [jet::TableDefinition]) -> Result<(), EseError> {
+    for i in 0..tables.len() {
+        let template_name = match &tables[i].template_table {
+            Some(template_name) => template_name.clone(),
+            None => continue,
+        };
+        let template = tables
+            .iter()
+            .find(|t| {
+                t.table_catalog_definition
+                    .as_ref()
+                    .is_some_and(|c| c.name == template_name)
+            })
+            .ok_or_else(|| {
+                EseError::CorruptCatalog(format!("template table {} not found", template_name))
+            })?;
+        let derived = &tables[i].column_catalog_definition_array;
+        let mut columns: Vec<jet::CatalogDefinition> = template
+            .column_catalog_definition_array
+            .iter()
+            .filter(|c| derived.iter().all(|d| d.identifier != c.identifier))
+            .cloned()
+            .collect();
+        columns.extend(derived.iter().cloned());
+        tables[i].column_catalog_definition_array = columns;
+    }
+    Ok(())
+}
+
 #[macro_export]
 macro_rules! impl_read_struct {
     ($struct_type: ident) => {
         impl $struct_type {
-            pub(crate) fn read<T: ReadSeek>(
+            pub(crate) fn read<T: ReaderBackend>(
                 reader: &crate::parser::reader::Reader<T>,
                 page_offset: u64,
-            ) -> Result<Self, simple_error::SimpleError> {
+            ) -> Result<Self, crate::error::EseError> {
                 let buffer = reader.read_bytes(page_offset, std::mem::size_of::<$struct_type>())?;
                 let (_, ret) = $struct_type::parse_le(&buffer[..]).map_err(
                     |e: nom::Err<nom::error::Error<&[u8]>>| {
-                        simple_error::SimpleError::new(e.to_string())
+                        crate::error::EseError::new(e.to_string())
                     },
                 )?;
                 Ok(ret)
@@ -1274,14 +2221,14 @@ macro_rules! impl_read_struct {
 macro_rules! impl_read_struct_buffer {
     ($struct_type: ident) => {
         impl $struct_type {
-            pub(crate) fn read<T: ReadSeek>(
+            pub(crate) fn read<T: ReaderBackend>(
                 reader: &crate::parser::reader::Reader<T>,
                 page_offset: u64,
-            ) -> Result<(Self, Vec<u8>), simple_error::SimpleError> {
+            ) -> Result<(Self, Vec<u8>), crate::error::EseError> {
                 let buffer = reader.read_bytes(page_offset, std::mem::size_of::<$struct_type>())?;
                 let (_, ret) = $struct_type::parse_le(&buffer[..]).map_err(
                     |e: nom::Err<nom::error::Error<&[u8]>>| {
-                        simple_error::SimpleError::new(e.to_string())
+                        crate::error::EseError::new(e.to_string())
                     },
                 )?;
                 Ok((ret, buffer))
@@ -1294,10 +2241,10 @@ macro_rules! impl_read_struct_buffer {
 macro_rules! impl_read_primitive {
     ($primitive_type: ident) => {
         paste::item! {
-            pub(crate) fn [<read_ $primitive_type>]<T: ReadSeek>(reader: &crate::parser::reader::Reader<T>, page_offset: u64) -> Result<$primitive_type, simple_error::SimpleError> {
+            pub(crate) fn [<read_ $primitive_type>]<T: ReaderBackend>(reader: &crate::parser::reader::Reader<T>, page_offset: u64) -> Result<$primitive_type, crate::error::EseError> {
                 let size = std::mem::size_of::<$primitive_type>();
                 let buffer = reader.read_bytes(page_offset, size)?;
-                let arr = buffer[..].try_into().map_err(|e: std::array::TryFromSliceError| simple_error::SimpleError::new(e.to_string()))?;
+                let arr = buffer[..].try_into().map_err(|e: std::array::TryFromSliceError| crate::error::EseError::new(e.to_string()))?;
                 Ok($primitive_type::from_le_bytes(arr))
             }
         }
@@ -1324,10 +2271,35 @@ pub struct VariableSizeDataState {
     pub value_offset: u16,
 }
 
+// the last loaded tagged column
+#[derive(Clone, Debug, Default)]
+pub struct TaggedColumnLoad {
+    // data type flags, None if the column isn't in the record
+    pub data_type_flags: Option<jet::TaggedDataTypeFlag>,
+    // key of a (single valued) long value column
+    pub lv_key: Option<u64>,
+    // number of values of the column, set in TaggedLoadMode::ValueCount
+    pub value_count: Option<u32>,
+    pub mode: TaggedLoadMode,
+}
+
+// how far the value of a tagged column is read
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TaggedLoadMode {
+    #[default]
+    Value,
+    // stop at the long value key, without reading the long value data
+    LvKey,
+    // count the values from the multi-value offsets, without reading them
+    ValueCount,
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct LastLoadState {
     pub page_number: u32,
     pub page_tag_index: usize,
+    // row generation of the table cursor the state was loaded for
+    pub row_generation: u64,
     pub last_column: u32,
     pub offset: u64,
     pub offset_ddh: u64,
@@ -1338,6 +2310,7 @@ pub struct LastLoadState {
     pub tag_state: TaggedDataState,
     pub previous_variable_size_data_type_size: u16,
     pub var_state: VariableSizeDataState,
+    pub tagged_column: TaggedColumnLoad,
 }
 
 impl LastLoadState {
@@ -1348,6 +2321,29 @@ impl LastLoadState {
             ..Default::default()
         }
     }
+
+    // the record data (after the key) holds `size` bytes at `offset`
+    pub fn check_in_record(&self, offset: u64, size: u64) -> Result<(), EseError> {
+        let end = self.offset_ddh + self.record_data_size;
+        if offset < self.offset_ddh || offset.saturating_add(size) > end {
+            return Err(EseError::new(format!(
+                "page {} tag {}: {} bytes at 0x{:x} are out of the record data 0x{:x}..0x{:x}",
+                self.page_number, self.page_tag_index, size, offset, self.offset_ddh, end
+            )));
+        }
+        Ok(())
+    }
+
+    // adds the location of the record to an error of its data
+    pub fn locate_error(&self, e: EseError) -> EseError {
+        match e {
+            EseError::Other(s) => EseError::Other(format!(
+                "page {} tag {}: {}",
+                self.page_number, self.page_tag_index, s
+            )),
+            e => e,
+        }
+    }
 }
 
 pub trait FromBytes {
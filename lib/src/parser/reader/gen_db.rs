@@ -1,7 +1,7 @@
 #![cfg(all(feature = "nt_comparison", target_os = "windows"))]
 #![cfg(test)]
 
-use crate::ese_trait::ESE_CP;
+use crate::ese_trait::{ESE_MoveFirst, ESE_MoveNext, ESE_CP};
 use crate::esent::esent::*;
 use encoding::{
     all::{ASCII, UTF_16LE, UTF_8},
@@ -120,18 +120,31 @@ impl EseAPI {
         self: &mut EseAPI,
         name: &str,
         columns: &mut Vec<JET_COLUMNCREATE_A>,
+    ) -> JET_TABLEID {
+        self.create_table_from_template(name, None, 0, columns)
+    }
+
+    fn create_table_from_template(
+        self: &mut EseAPI,
+        name: &str,
+        template: Option<&str>,
+        grbit: JET_GRBIT,
+        columns: &mut Vec<JET_COLUMNCREATE_A>,
     ) -> JET_TABLEID {
         let mut table_def = JET_TABLECREATE_A {
             cbStruct: size_of::<JET_TABLECREATE_A>() as u32,
             szTableName: CString::new(name).unwrap().into_raw(),
-            szTemplateTableName: ptr::null_mut(),
+            szTemplateTableName: match template {
+                Some(template) => CString::new(template).unwrap().into_raw(),
+                None => ptr::null_mut(),
+            },
             ulPages: 0,
             ulDensity: 0,
             rgcolumncreate: columns.as_mut_ptr(),
             cColumns: columns.len() as raw::c_ulong,
             rgindexcreate: ptr::null_mut(),
             cIndexes: 0,
-            grbit: 0,
+            grbit,
             tableid: 0,
             cCreated: 0,
         };
@@ -265,6 +278,203 @@ pub fn prepare_db_gen(
     dst_path
 }
 
+// derived_table inherits the "template_text" and "template_long" columns
+// of template_table and adds "own_text", records_cnt records set all of them
+pub fn prepare_template_db_gen(
+    filename: &str,
+    template_table: &str,
+    derived_table: &str,
+    pg_size: usize,
+    records_cnt: usize,
+) -> std::path::PathBuf {
+    let mut dst_path = std::env::temp_dir();
+    dst_path.push(filename);
+
+    if dst_path.exists() {
+        let _ = fs::remove_file(&dst_path);
+    }
+
+    println!("creating {}", dst_path.display());
+    let mut db_client = EseAPI::new(filename, pg_size);
+
+    let dbpath = CString::new(dst_path.to_str().unwrap()).unwrap();
+    jettry!(JetCreateDatabaseA(
+        db_client.sesid,
+        dbpath.as_ptr(),
+        ptr::null(),
+        &mut db_client.dbid,
+        0
+    ));
+
+    let mut template_columns = vec![
+        EseAPI::create_text_column("template_text", ESE_CP::ASCII, JET_bitColumnTagged),
+        EseAPI::create_column("template_long", JET_coltypLong, ESE_CP::None, 0),
+    ];
+    let template_tableid = db_client.create_table_from_template(
+        template_table,
+        None,
+        JET_bitTableCreateTemplateTable,
+        &mut template_columns,
+    );
+    jettry!(JetCloseTable(db_client.sesid, template_tableid));
+
+    let mut own_columns = vec![EseAPI::create_text_column(
+        "own_text",
+        ESE_CP::ASCII,
+        JET_bitColumnTagged,
+    )];
+    let tableid = db_client.create_table_from_template(
+        derived_table,
+        Some(template_table),
+        0,
+        &mut own_columns,
+    );
+
+    let column_id = |name: &str| {
+        let name = CString::new(name).unwrap();
+        let mut column_def: JET_COLUMNDEF = unsafe { std::mem::zeroed() };
+        jettry!(JetGetTableColumnInfoA(
+            db_client.sesid,
+            tableid,
+            name.as_ptr(),
+            &mut column_def as *mut JET_COLUMNDEF as *mut raw::c_void,
+            size_of::<JET_COLUMNDEF>() as raw::c_ulong,
+            JET_ColInfo
+        ));
+        column_def.columnid
+    };
+    let template_text = column_id("template_text");
+    let template_long = column_id("template_long");
+    let own_text = column_id("own_text");
+
+    for i in 0..records_cnt {
+        let template_s = format!("Template {}", i);
+        let own_s = format!("Own {}", i);
+        let long = i as i32;
+        let values: [(JET_COLUMNID, &[u8]); 3] = [
+            (template_text, template_s.as_bytes()),
+            (template_long, &long.to_le_bytes()),
+            (own_text, own_s.as_bytes()),
+        ];
+
+        db_client.begin_transaction();
+
+        jettry!(JetPrepareUpdate(db_client.sesid, tableid, JET_prepInsert));
+        for (columnid, data) in values {
+            let mut setColumn = JET_SETCOLUMN {
+                columnid,
+                pvData: data.as_ptr() as *const raw::c_void,
+                cbData: data.len() as raw::c_ulong,
+                grbit: 0,
+                ibLongValue: 0,
+                itagSequence: 0,
+                err: 0,
+            };
+
+            jettry!(JetSetColumns(db_client.sesid, tableid, &mut setColumn, 1));
+        }
+
+        jettry!(JetUpdate(
+            db_client.sesid,
+            tableid,
+            ptr::null_mut(),
+            0,
+            ptr::null_mut()
+        ));
+        db_client.commit_transaction();
+    }
+
+    dst_path
+}
+
+// records_cnt records with "Record {i}" in the "text" column,
+// then the records with an index in `deleted` are removed with JetDelete
+pub fn prepare_deleted_rows_db_gen(
+    filename: &str,
+    table: &str,
+    pg_size: usize,
+    records_cnt: usize,
+    deleted: &[usize],
+) -> std::path::PathBuf {
+    let mut dst_path = std::env::temp_dir();
+    dst_path.push(filename);
+
+    if dst_path.exists() {
+        let _ = fs::remove_file(&dst_path);
+    }
+
+    println!("creating {}", dst_path.display());
+    let mut db_client = EseAPI::new(filename, pg_size);
+
+    let dbpath = CString::new(dst_path.to_str().unwrap()).unwrap();
+    jettry!(JetCreateDatabaseA(
+        db_client.sesid,
+        dbpath.as_ptr(),
+        ptr::null(),
+        &mut db_client.dbid,
+        0
+    ));
+
+    let mut columns = vec![EseAPI::create_text_column(
+        "text",
+        ESE_CP::ASCII,
+        JET_bitColumnTagged,
+    )];
+    let tableid = db_client.create_table(table, &mut columns);
+
+    for i in 0..records_cnt {
+        let s = format!("Record {}", i);
+
+        db_client.begin_transaction();
+
+        jettry!(JetPrepareUpdate(db_client.sesid, tableid, JET_prepInsert));
+        let mut setColumn = JET_SETCOLUMN {
+            columnid: columns[0].columnid,
+            pvData: s.as_ptr() as *const raw::c_void,
+            cbData: s.len() as raw::c_ulong,
+            grbit: 0,
+            ibLongValue: 0,
+            itagSequence: 0,
+            err: 0,
+        };
+        jettry!(JetSetColumns(db_client.sesid, tableid, &mut setColumn, 1));
+
+        jettry!(JetUpdate(
+            db_client.sesid,
+            tableid,
+            ptr::null_mut(),
+            0,
+            ptr::null_mut()
+        ));
+        db_client.commit_transaction();
+    }
+
+    // records are in insertion order in the primary index
+    db_client.begin_transaction();
+    jettry!(JetMove(
+        db_client.sesid,
+        tableid,
+        ESE_MoveFirst as raw::c_long,
+        0
+    ));
+    for i in 0..records_cnt {
+        if deleted.contains(&i) {
+            jettry!(JetDelete(db_client.sesid, tableid));
+        }
+        if i + 1 < records_cnt {
+            jettry!(JetMove(
+                db_client.sesid,
+                tableid,
+                ESE_MoveNext as raw::c_long,
+                0
+            ));
+        }
+    }
+    db_client.commit_transaction();
+
+    dst_path
+}
+
 pub fn clean_db_gen(dst_path: &Path) {
     fs::remove_file(dst_path.with_extension("jfm")).unwrap();
     fs::remove_file(dst_path).unwrap();
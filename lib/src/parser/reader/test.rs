@@ -7,7 +7,7 @@ use std::collections::HashSet;
 use std::fs;
 use std::fs::File;
 use std::io::BufReader;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[cfg(all(feature = "nt_comparison", target_os = "windows"))]
 use crate::parser::reader::gen_db::*;
@@ -25,7 +25,7 @@ pub fn prepare_db(
 }
 
 #[test]
-pub fn caching_test() -> Result<(), SimpleError> {
+pub fn caching_test() -> Result<(), EseError> {
     let cache_size: usize = 10;
     let table = "test_table";
     let test_db = "decompress_test.edb";
@@ -35,7 +35,7 @@ pub fn caching_test() -> Result<(), SimpleError> {
     let file = File::open(path.clone()).unwrap();
     let buf_reader = BufReader::with_capacity(4096, file);
 
-    let mut reader = Reader::new(buf_reader, cache_size as usize)?;
+    let mut reader = Reader::load_db(buf_reader, cache_size as usize)?;
     let page_size = reader.page_size as u64;
     let num_of_pages =
         std::cmp::min(fs::metadata(&path).unwrap().len() / page_size, page_size) as usize;
@@ -74,7 +74,7 @@ pub fn caching_test() -> Result<(), SimpleError> {
 
 #[cfg(all(feature = "nt_comparison", target_os = "windows"))]
 #[test]
-pub fn caching_test_windows() -> Result<(), SimpleError> {
+pub fn caching_test_windows() -> Result<(), EseError> {
     let cache_size: usize = 10;
     let table = "test_table";
     let test_db = "caching_test.edb";
@@ -84,7 +84,7 @@ pub fn caching_test_windows() -> Result<(), SimpleError> {
     let file = File::open(path.clone()).unwrap();
     let buf_reader = BufReader::with_capacity(4096, file);
 
-    let mut reader = Reader::new(buf_reader, cache_size as usize)?;
+    let mut reader = Reader::load_db(buf_reader, cache_size as usize)?;
     let page_size = reader.page_size as u64;
     let num_of_pages =
         std::cmp::min(fs::metadata(&path).unwrap().len() / page_size, page_size) as usize;
@@ -122,14 +122,14 @@ pub fn caching_test_windows() -> Result<(), SimpleError> {
     Ok(())
 }
 
-fn check_row<R: ReadSeek>(
+fn check_row<R: ReaderBackend>(
     jdb: &mut EseParser<R>,
     table_id: u64,
     columns: &[ColumnInfo],
 ) -> HashSet<String> {
     let mut values = HashSet::<String>::new();
     for col in columns {
-        match jdb.get_column_str(table_id, col.id, col.cp) {
+        match jdb.get_column_string(table_id, col, false) {
             Ok(result) => {
                 if let Some(value) = result {
                     values.insert(value);
@@ -144,22 +144,26 @@ fn check_row<R: ReadSeek>(
 }
 
 #[test]
-pub fn decompress_test_7bit() -> Result<(), SimpleError> {
+pub fn decompress_test_7bit() -> Result<(), EseError> {
     // if record size < 1024 - 7 bit compression is used
     run_decompress_test("decompress_test.edb", 10)?;
     Ok(())
 }
 
 #[test]
-pub fn decompress_test_lzxpress() -> Result<(), SimpleError> {
+pub fn decompress_test_lzxpress() -> Result<(), EseError> {
     // if record size > 1024 - lzxpress compression is used
     run_decompress_test("decompress_test2.edb", 2048)?;
     Ok(())
 }
 
-pub fn run_decompress_test(filename: &str, record_size: usize) -> Result<(), SimpleError> {
+pub fn run_decompress_test(filename: &str, record_size: usize) -> Result<(), EseError> {
+    let path = prepare_db(filename, "test_table", 1024 * 8, record_size, 10);
+    check_decompressed_rows(&path, record_size)
+}
+
+fn check_decompressed_rows(path: &Path, record_size: usize) -> Result<(), EseError> {
     let table = "test_table";
-    let path = prepare_db(filename, table, 1024 * 8, record_size, 10);
     //let mut jdb = EseParser::init(5);
     let mut jdb = EseParser::load_from_path(5, path.to_str().unwrap())?;
 
@@ -182,5 +186,948 @@ pub fn run_decompress_test(filename: &str, record_size: usize) -> Result<(), Sim
             break;
         }
     }
+    let rows = jdb.iter_rows(table)?.collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(rows.len(), 10);
+    Ok(())
+}
+
+#[cfg(all(feature = "nt_comparison", target_os = "windows"))]
+#[test]
+pub fn decompress_test_page_sizes_windows() -> Result<(), EseError> {
+    for pg_size in [2048, 4096, 8192, 16384, 32768] {
+        // 7-bit and lzxpress compression
+        for record_size in [10, 2048] {
+            println!("page size {}, record size {}", pg_size, record_size);
+            let path = prepare_db_gen(
+                &format!("decompress_test_{}.edb", pg_size),
+                "test_table",
+                pg_size,
+                record_size,
+                10,
+            );
+            let reader = Reader::load_db(File::open(&path)?, 10)?;
+            assert_eq!(reader.page_size() as usize, pg_size);
+            check_decompressed_rows(&path, record_size)?;
+            clean_db_gen(&path);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+pub fn large_page_tagged_data_offset_test() -> Result<(), EseError> {
+    let path = prepare_db("test.edb", "", 0, 0, 0);
+    let mut data = fs::read(&path).unwrap();
+    // tagged data of column 256, the offset of its value has the 0x4000 bit set
+    let offset_ddh = 4096 * 3 + 100;
+    data[offset_ddh + 4..offset_ddh + 8].copy_from_slice(&[0x00, 0x01, 0x08, 0x40]);
+    let mut reader = Reader::load_db(std::io::Cursor::new(data), 10)?;
+
+    let mut offsets_size = |page_size: u32| -> Result<u16, EseError> {
+        reader.page_size = page_size;
+        reader.format_revision = ESEDB_FORMAT_REVISION_EXTENDED_PAGE_HEADER;
+        reader.cache.get_mut().clear();
+        let mut tag_state = TaggedDataState::default();
+        let var_state = VariableSizeDataState {
+            value_offset: 4,
+            ..Default::default()
+        };
+        let mut offset = 0;
+        reader.init_tag_state(
+            &mut tag_state,
+            var_state,
+            &mut offset,
+            offset_ddh as u64,
+            0x5000,
+        )?;
+        assert_eq!(tag_state.identifier, 256);
+        Ok(tag_state.offset_data_size)
+    };
+    // 0x4000 is a flag with pages up to 8K, a part of the offset with 16K and 32K pages
+    assert_eq!(offsets_size(8192)?, 0x0004);
+    assert_eq!(offsets_size(16384)?, 0x4004);
+    assert_eq!(offsets_size(32768)?, 0x4004);
+    Ok(())
+}
+
+// database of 32K pages: the file header of test.edb and `pages` of (page number, flags, tag data)
+fn large_page_db(pages: &[(u32, jet::PageFlags, Vec<Vec<u8>>)]) -> Vec<u8> {
+    large_page_db_of_size(32768, pages)
+}
+
+// same as `large_page_db`, with pages of 16K or 32K
+fn large_page_db_of_size(
+    page_size: usize,
+    pages: &[(u32, jet::PageFlags, Vec<Vec<u8>>)],
+) -> Vec<u8> {
+    let last_page = pages.iter().map(|p| p.0).max().unwrap_or(0) as usize;
+    let mut data = vec![0u8; (last_page + 2) * page_size];
+
+    let test_db = fs::read(prepare_db("test.edb", "", 0, 0, 0)).unwrap();
+    let header_size = mem::size_of::<ese_db::FileHeader>();
+    let mut header = test_db[..header_size].to_vec();
+    // format revision and page size
+    header[0xe8..0xec].copy_from_slice(&ESEDB_FORMAT_REVISION_EXTENDED_PAGE_HEADER.to_le_bytes());
+    header[0xec..0xf0].copy_from_slice(&(page_size as u32).to_le_bytes());
+    let checksum = checksum::xor_checksum(&header[4..], ESEDB_FILE_SIGNATURE);
+    header[..4].copy_from_slice(&checksum.to_le_bytes());
+    data[..header_size].copy_from_slice(&header);
+    data[page_size..page_size + header_size].copy_from_slice(&header);
+
+    let header_size = mem::size_of::<PageHeader0x11>()
+        + mem::size_of::<PageHeaderCommon>()
+        + mem::size_of::<PageHeaderExt0x11>();
+    for (page_number, flags, tags) in pages {
+        let page = &mut data[(*page_number as usize + 1) * page_size..][..page_size];
+        // PageHeaderCommon follows the 8 bytes checksum
+        page[32..34].copy_from_slice(&0u16.to_le_bytes());
+        page[34..36].copy_from_slice(&(tags.len() as u16).to_le_bytes());
+        page[36..40].copy_from_slice(&flags.bits().to_le_bytes());
+        let mut offset = 0;
+        for (i, tag) in tags.iter().enumerate() {
+            page[header_size + offset..][..tag.len()].copy_from_slice(tag);
+            let tag_entry = page_size - 4 * (i + 1);
+            page[tag_entry..tag_entry + 2].copy_from_slice(&(tag.len() as u16).to_le_bytes());
+            page[tag_entry + 2..tag_entry + 4].copy_from_slice(&(offset as u16).to_le_bytes());
+            offset += tag.len();
+        }
+    }
+    data
+}
+
+// branch entry of `large_page_db`: local key size, key, child page number
+fn branch_entry(key_size: u16, child: u32) -> Vec<u8> {
+    let mut entry = key_size.to_le_bytes().to_vec();
+    entry.resize(2 + key_size as usize, b'k');
+    entry.extend_from_slice(&child.to_le_bytes());
+    entry
+}
+
+// leaf entry of `large_page_db`: page tag flags and local key size, key, record data
+fn leaf_entry(flags: u16, key: &[u8]) -> Vec<u8> {
+    let mut entry = ((flags << 13) | key.len() as u16).to_le_bytes().to_vec();
+    entry.extend_from_slice(key);
+    entry.extend_from_slice(b"data");
+    entry
+}
+
+// sets the page tag `flags` in the upper 3 bits of the first 16-bit value of an entry
+fn with_tag_flags(mut entry: Vec<u8>, flags: jet::PageTagFlags) -> Vec<u8> {
+    let first_word = LittleEndian::read_u16(&entry) | (flags.bits() as u16) << 13;
+    LittleEndian::write_u16(&mut entry, first_word);
+    entry
+}
+
+#[test]
+pub fn large_page_branch_tags_test() -> Result<(), EseError> {
+    // branch entry with a common key: common key size, then the entry without it
+    let mut common_key_entry = 3u16.to_le_bytes().to_vec();
+    common_key_entry.extend_from_slice(&branch_entry(1, 2));
+    for page_size in [16384, 32768] {
+        let data = large_page_db_of_size(
+            page_size,
+            &[
+                (
+                    1,
+                    jet::PageFlags::IS_PARENT,
+                    vec![
+                        // common key of the page
+                        b"pre".to_vec(),
+                        with_tag_flags(
+                            common_key_entry.clone(),
+                            jet::PageTagFlags::FLAG_HAS_COMMON_KEY_SIZE,
+                        ),
+                        with_tag_flags(branch_entry(2, 3), jet::PageTagFlags::FLAG_0x01),
+                        with_tag_flags(branch_entry(0, 4), jet::PageTagFlags::FLAG_IS_DEFUNCT),
+                    ],
+                ),
+                (
+                    2,
+                    jet::PageFlags::IS_LEAF,
+                    vec![vec![], leaf_entry(0, b"abc")],
+                ),
+                (
+                    3,
+                    jet::PageFlags::IS_LEAF,
+                    vec![vec![], leaf_entry(2, b"old"), leaf_entry(0, b"def")],
+                ),
+                (
+                    4,
+                    jet::PageFlags::IS_LEAF,
+                    vec![vec![], leaf_entry(0, b"xyz")],
+                ),
+            ],
+        );
+        let reader = Reader::from_memory(data)?;
+        assert_eq!(reader.page_size() as usize, page_size);
+        assert!(reader.is_large_page_format());
+
+        // the flags of branch entries are read from their data like the flags of leaf entries
+        let branch = jet::DbPage::new(&reader, 1)?;
+        let flags: Vec<jet::PageTagFlags> =
+            branch.page_tags[1..].iter().map(|t| t.flags()).collect();
+        assert_eq!(
+            flags,
+            [
+                jet::PageTagFlags::FLAG_HAS_COMMON_KEY_SIZE,
+                jet::PageTagFlags::FLAG_0x01,
+                jet::PageTagFlags::FLAG_IS_DEFUNCT
+            ]
+        );
+        for (tag, child) in branch.page_tags[1..].iter().zip([2, 3, 4]) {
+            assert_eq!(
+                reader.page_tag_get_branch_child_page_number(&branch, tag)?,
+                child
+            );
+        }
+        assert_eq!(
+            reader.load_page_tag_key(&branch, &branch.page_tags[1])?,
+            b"prek"
+        );
+        assert_eq!(
+            reader.load_page_tag_key(&branch, &branch.page_tags[2])?,
+            b"kk"
+        );
+        assert_eq!(reader.find_first_leaf_page(1)?, 2);
+
+        // the defunct child page and leaf entry are skipped
+        let mut keys = vec![];
+        walk_btree_leaf_tags(&reader, 1, &mut |page, tag, _| {
+            keys.push(reader.load_page_tag_key(page, tag)?);
+            Ok(())
+        })?;
+        assert_eq!(keys, [b"abc", b"def"]);
+        let leaf = jet::DbPage::new(&reader, 3)?;
+        assert!(leaf.page_tags[1]
+            .flags()
+            .contains(jet::PageTagFlags::FLAG_IS_DEFUNCT));
+        assert_eq!(reader.load_page_tag_key(&leaf, &leaf.page_tags[1])?, b"old");
+    }
+    Ok(())
+}
+
+#[test]
+pub fn walk_btree_leaf_tags_test() -> Result<(), EseError> {
+    let data = large_page_db(&[
+        (
+            1,
+            jet::PageFlags::IS_ROOT | jet::PageFlags::IS_PARENT,
+            vec![vec![], branch_entry(0, 2), branch_entry(0, 3)],
+        ),
+        (
+            2,
+            jet::PageFlags::IS_PARENT,
+            vec![vec![], branch_entry(0, 4)],
+        ),
+        (
+            3,
+            jet::PageFlags::IS_LEAF,
+            vec![vec![], leaf_entry(0, b"ghi")],
+        ),
+        (
+            4,
+            jet::PageFlags::IS_LEAF,
+            vec![vec![], leaf_entry(0, b"abc"), leaf_entry(2, b"def")],
+        ),
+        // pages 5 and 6 are children of each other
+        (
+            5,
+            jet::PageFlags::IS_PARENT,
+            vec![vec![], branch_entry(0, 6)],
+        ),
+        (
+            6,
+            jet::PageFlags::IS_PARENT,
+            vec![vec![], branch_entry(0, 5)],
+        ),
+    ]);
+    let reader = Reader::from_memory(data)?;
+
+    // in key order, without the defunct entry
+    let mut entries = vec![];
+    walk_btree_leaf_tags(&reader, 1, &mut |page, tag, depth| {
+        entries.push((
+            page.page_number,
+            reader.load_page_tag_key(page, tag)?,
+            depth,
+        ));
+        Ok(())
+    })?;
+    assert_eq!(
+        entries,
+        vec![(4, b"abc".to_vec(), 2), (3, b"ghi".to_vec(), 1)]
+    );
+
+    // the walk stops at the first error of the visitor
+    let mut visited = 0;
+    let r = walk_btree_leaf_tags(&reader, 1, &mut |_, _, _| {
+        visited += 1;
+        Err(EseError::new("stop"))
+    });
+    assert!(r.is_err());
+    assert_eq!(visited, 1);
+
+    let r = walk_btree_leaf_tags(&reader, 5, &mut |_, _, _| Ok(()));
+    assert!(r.unwrap_err().to_string().contains("loop detected"));
+    Ok(())
+}
+
+#[test]
+pub fn btree_depth_limit_test() -> Result<(), EseError> {
+    // pages 1 to 64 are branch pages, the child of each page is the next one, page 65 is a leaf
+    let mut pages: Vec<(u32, jet::PageFlags, Vec<Vec<u8>>)> = (1..65)
+        .map(|page_number| {
+            let tags = vec![vec![], branch_entry(0, page_number + 1)];
+            (page_number, jet::PageFlags::IS_PARENT, tags)
+        })
+        .collect();
+    pages.push((
+        65,
+        jet::PageFlags::IS_LEAF,
+        vec![vec![], leaf_entry(0, b"abc")],
+    ));
+    let reader = Reader::from_memory(large_page_db(&pages))?;
+    assert_eq!(MAX_BTREE_DEPTH, 64);
+
+    // 64 levels from page 2
+    assert_eq!(reader.find_first_leaf_page(2)?, 65);
+    let mut depths = vec![];
+    walk_btree_leaf_tags(&reader, 2, &mut |_, _, depth| {
+        depths.push(depth);
+        Ok(())
+    })?;
+    assert_eq!(depths, vec![63]);
+    assert_eq!(reader.find_leaf_page_for_key(2, b"abc")?.page_number, 65);
+
+    // 65 levels from page 1
+    let e = reader.find_first_leaf_page(1).unwrap_err();
+    assert!(e.to_string().contains("deeper than 64 levels"), "{}", e);
+    let e = walk_btree_leaf_tags(&reader, 1, &mut |_, _, _| Ok(())).unwrap_err();
+    assert!(e.to_string().contains("deeper than 64 levels"), "{}", e);
+    let e = reader.find_leaf_page_for_key(1, b"abc").unwrap_err();
+    assert!(e.to_string().contains("page 65 is deeper"), "{}", e);
+    Ok(())
+}
+
+// sets the previous and next page numbers of `level`, pages of the same level of a `large_page_db`
+fn link_pages(data: &mut [u8], level: &[u32]) {
+    for (i, &page_number) in level.iter().enumerate() {
+        let previous_page = if i > 0 { level[i - 1] } else { 0 };
+        let next_page = level.get(i + 1).copied().unwrap_or(0);
+        // PageHeaderCommon follows the 8 bytes checksum
+        let page = &mut data[(page_number as usize + 1) * 32768..];
+        page[16..20].copy_from_slice(&previous_page.to_le_bytes());
+        page[20..24].copy_from_slice(&next_page.to_le_bytes());
+    }
+}
+
+#[test]
+pub fn multi_level_lv_tree_test() -> Result<(), EseError> {
+    const SEGMENT_SIZE: usize = 12000;
+    // two values of 20 segments, two segments per leaf page
+    let values: Vec<(u32, Vec<u8>)> = [1u32, 2]
+        .iter()
+        .map(|&lid| {
+            let value = (0..20 * SEGMENT_SIZE)
+                .map(|i| (i * 7 + lid as usize) as u8)
+                .collect();
+            (lid, value)
+        })
+        .collect();
+    // LVKEY32 key: big-endian long value identifier and segment offset
+    let lv_entry = |lid: u32, offset: u32, data: &[u8]| -> Vec<u8> {
+        let mut key = lid.to_be_bytes().to_vec();
+        key.extend_from_slice(&offset.to_be_bytes());
+        let mut entry = (key.len() as u16).to_le_bytes().to_vec();
+        entry.extend_from_slice(&key);
+        entry.extend_from_slice(data);
+        entry
+    };
+
+    let lv_flags = jet::PageFlags::IS_LONG_VALUE;
+    let leaves: Vec<u32> = (10..30).collect();
+    // root page 1, branch pages 2 and 3 of 10 leaf pages each
+    let mut pages = vec![
+        (
+            1,
+            lv_flags | jet::PageFlags::IS_ROOT | jet::PageFlags::IS_PARENT,
+            vec![vec![], branch_entry(4, 2), branch_entry(0, 3)],
+        ),
+        (2, lv_flags | jet::PageFlags::IS_PARENT, vec![vec![]]),
+        (3, lv_flags | jet::PageFlags::IS_PARENT, vec![vec![]]),
+    ];
+    for (i, &leaf) in leaves.iter().enumerate() {
+        let (lid, value) = &values[i / 10];
+        let mut tags = vec![vec![]];
+        if i % 10 == 0 {
+            // root entry of the value, its data is the reference count and the size
+            let mut entry = 4u16.to_le_bytes().to_vec();
+            entry.extend_from_slice(&lid.to_be_bytes());
+            entry.extend_from_slice(&1u32.to_le_bytes());
+            entry.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            tags.push(entry);
+        }
+        for segment in 0..2 {
+            let offset = ((i % 10) * 2 + segment) * SEGMENT_SIZE;
+            tags.push(lv_entry(
+                *lid,
+                offset as u32,
+                &value[offset..offset + SEGMENT_SIZE],
+            ));
+        }
+        let branch = &mut pages[1 + i / 10].2;
+        branch.push(branch_entry(if i % 10 == 9 { 0 } else { 4 }, leaf));
+        pages.push((leaf, lv_flags | jet::PageFlags::IS_LEAF, tags));
+    }
+    let mut data = large_page_db(&pages);
+    link_pages(&mut data, &[2, 3]);
+    link_pages(&mut data, &leaves);
+
+    let check_values = |data: &Vec<u8>| -> Result<(), EseError> {
+        let reader = Reader::from_memory(data.clone())?;
+        let tags = reader.load_lv_metadata(1)?;
+        assert_eq!(tags.len(), 2);
+        for (lid, value) in &values {
+            assert_eq!(tags[&(*lid as u64)].len(), 20);
+            assert!(reader.load_lv_data(&tags, *lid as u64, false)? == *value);
+        }
+        Ok(())
+    };
+    check_values(&data)?;
+    // only the chain of the leaf pages is checked
+    link_pages(&mut data, &[3, 2]);
+    check_values(&data)?;
+
+    // the leaf pages are chained across their parents
+    link_pages(&mut data, &leaves[..10]);
+    let e = Reader::from_memory(data)?.load_lv_metadata(1).unwrap_err();
+    assert!(
+        e.to_string()
+            .contains("pageno 19: wrong next_page number 0, expected 20"),
+        "{}",
+        e
+    );
+    Ok(())
+}
+
+#[test]
+pub fn linear_tagged_data_test() -> Result<(), EseError> {
+    // column 256: "ab", column 257: two values, column 259: empty,
+    // column 260: data type flags (0x4000 of the size) of a compressed value
+    let data: Vec<u8> = vec![
+        0x00, 0x01, 0x02, 0x00, b'a', b'b', //
+        0x01, 0x01, 0x01, 0x00, 1, //
+        0x01, 0x01, 0x02, 0x00, 2, 3, //
+        0x03, 0x01, 0x00, 0x00, //
+        0x04, 0x01, 0x03, 0x40, 0x02, 4, 5,
+    ];
+    let value = |column_id, multi_value_index| {
+        find_linear_tagged_value(&data, column_id, multi_value_index).map(|v| v.map(|(_, v)| v))
+    };
+    assert_eq!(value(256, 0)?, Some(&b"ab"[..]));
+    assert_eq!(value(256, 2)?, None);
+    assert_eq!(value(257, 1)?, Some(&[1u8][..]));
+    assert_eq!(value(257, 2)?, Some(&[2u8, 3][..]));
+    assert_eq!(value(257, 3)?, None);
+    assert_eq!(value(258, 0)?, None);
+    assert_eq!(value(259, 0)?, Some(&[][..]));
+    assert_eq!(
+        find_linear_tagged_value(&data, 260, 0)?,
+        Some((jet::TaggedDataTypeFlag::COMPRESSED, &[4u8, 5][..]))
+    );
+    assert_eq!(value(261, 0)?, None);
+
+    // value size is out of the data
+    assert!(find_linear_tagged_value(&data[..10], 257, 1).is_err());
+    Ok(())
+}
+
+#[test]
+pub fn linear_tagged_data_record_test() -> Result<(), EseError> {
+    // fixed column 1, variable column 128 ("xyz"), then the linear tagged data
+    let mut record: Vec<u8> = vec![1, 128, 8, 0, 0xaa, 0xbb, 0xcc, 0xdd, 3, 0, b'x', b'y', b'z'];
+    let tagged_data_offset = record.len() as u64;
+    record.extend_from_slice(&[
+        0x00, 0x01, 0x02, 0x00, b'a', b'b', //
+        0x01, 0x01, 0x01, 0x00, 1, //
+        0x01, 0x01, 0x01, 0x00, 2,
+    ]);
+
+    let path = prepare_db("test.edb", "", 0, 0, 0);
+    let mut data = fs::read(&path).unwrap();
+    let offset_ddh = 4096 * 3 + 100;
+    data[offset_ddh..offset_ddh + record.len()].copy_from_slice(&record);
+    let reader = Reader::load_db(std::io::Cursor::new(data), 10)?;
+
+    let mut lls = LastLoadState {
+        offset_ddh: offset_ddh as u64,
+        record_data_size: record.len() as u64,
+        ddh: ese_db::DataDefinitionHeader {
+            last_fixed_size_data_type: 1,
+            last_variable_size_data_type: 128,
+            variable_size_data_types_offset: 8,
+        },
+        ..Default::default()
+    };
+    let lv_tags = LV_tags::new();
+    let column = |identifier| jet::CatalogDefinition {
+        identifier,
+        ..Default::default()
+    };
+    assert_eq!(
+        reader.load_tagged_data_linear_format(&lls, &lv_tags, &column(256), 0)?,
+        Some(b"ab".to_vec())
+    );
+    assert_eq!(
+        reader.load_tagged_data_linear_format(&lls, &lv_tags, &column(257), 2)?,
+        Some(vec![2])
+    );
+    assert_eq!(
+        reader.load_tagged_data_linear_format(&lls, &lv_tags, &column(258), 0)?,
+        None
+    );
+
+    // no tagged data after the variable size data
+    lls.record_data_size = tagged_data_offset;
+    assert_eq!(
+        reader.load_tagged_data_linear_format(&lls, &lv_tags, &column(256), 0)?,
+        None
+    );
+    Ok(())
+}
+
+#[test]
+pub fn linear_tagged_long_value_test() -> Result<(), EseError> {
+    // the LongText column of TestTable is a long value, its key is put into linear tagged data
+    let path = prepare_db("test.edb", "", 0, 0, 0);
+    let jdb = EseParser::load_from_path(10, &path)?;
+    let column_id = jdb
+        .get_columns("TestTable")?
+        .into_iter()
+        .find(|c| c.name == "LongText")
+        .unwrap()
+        .id;
+    let table_id = jdb.open_table("TestTable")?;
+    let key = jdb.get_lv_key_for_column(table_id, column_id)?.unwrap();
+    let expected = jdb.get_column(table_id, column_id)?.unwrap();
+    assert!(expected.len() > 4096, "a value of several segments");
+
+    let reader = Reader::load_db(File::open(&path).unwrap(), 10)?;
+    let table = reader
+        .load_catalog()?
+        .into_iter()
+        .find(|t| {
+            t.table_catalog_definition
+                .as_ref()
+                .is_some_and(|c| c.name == "TestTable")
+        })
+        .unwrap();
+    let lv_tags = reader.load_lv_metadata(
+        table
+            .long_value_catalog_definition
+            .as_ref()
+            .unwrap()
+            .father_data_page_number,
+    )?;
+    let column = table
+        .column_catalog_definition_array
+        .iter()
+        .find(|c| c.identifier == column_id)
+        .unwrap();
+    let id = (column_id as u16).to_le_bytes();
+    let key = (key as u32).to_le_bytes();
+
+    // a long value: flags byte (0x4000 of the size) LONG_VALUE, then the key
+    let mut data = id.to_vec();
+    data.extend_from_slice(&(0x4000u16 | 5).to_le_bytes());
+    data.push(jet::TaggedDataTypeFlag::LONG_VALUE.bits() as u8);
+    data.extend_from_slice(&key);
+    assert_eq!(
+        reader.linear_tagged_value(&data, &lv_tags, column, 1)?,
+        Some(expected.clone())
+    );
+    assert_eq!(
+        reader.linear_tagged_value(&data, &lv_tags, column, 2)?,
+        None
+    );
+
+    // a multi-value of [1, 2] and the long value (0x8000 of its offset),
+    // the column is compressed: [1, 2] is of the None compression scheme
+    let mut data = id.to_vec();
+    data.extend_from_slice(&(0x4000u16 | 11).to_le_bytes());
+    data.push(jet::TaggedDataTypeFlag::MULTI_VALUE.bits() as u8);
+    data.extend_from_slice(&[4, 0, 6, 0x80, 1, 2]);
+    data.extend_from_slice(&key);
+    assert_eq!(
+        reader.linear_tagged_value(&data, &lv_tags, column, 1)?,
+        Some(vec![1, 2])
+    );
+    assert_eq!(
+        reader.linear_tagged_value(&data, &lv_tags, column, 2)?,
+        Some(expected)
+    );
+    assert_eq!(
+        reader.linear_tagged_value(&data, &lv_tags, column, 3)?,
+        None
+    );
+    Ok(())
+}
+
+#[test]
+pub fn lv_data_many_segments_test() -> Result<(), EseError> {
+    let path = prepare_db("test.edb", "", 0, 0, 0);
+    let data = fs::read(&path).unwrap();
+    let reader = Reader::load_db(std::io::Cursor::new(data.clone()), 10)?;
+
+    // 150 segments of 10 bytes, inserted out of order
+    let (segment_size, segments) = (10u32, 150u32);
+    let file_offset = |i: u32| 4096 * 5 + (i as u64 * 17) % 20000;
+    let mut lv_tags = LV_tags::new();
+    for i in (0..segments)
+        .rev()
+        .step_by(2)
+        .chain((0..segments).step_by(2))
+    {
+        let tag = LV_tag {
+            common_page_key: vec![],
+            local_page_key: vec![],
+            offset: file_offset(i),
+            size: segment_size,
+        };
+        lv_tags.entry(7).or_default().insert(i * segment_size, tag);
+    }
+    let expected: Vec<u8> = (0..segments)
+        .flat_map(|i| {
+            let start = file_offset(i) as usize;
+            data[start..start + segment_size as usize].to_vec()
+        })
+        .collect();
+    assert_eq!(reader.load_lv_data(&lv_tags, 7, false)?, expected);
+
+    // the value ends at the first missing segment
+    lv_tags.get_mut(&7).unwrap().remove(&(100 * segment_size));
+    assert_eq!(
+        reader.load_lv_data(&lv_tags, 7, false)?,
+        expected[..100 * segment_size as usize]
+    );
+    assert!(matches!(
+        reader.load_lv_data(&lv_tags, 8, false),
+        Err(EseError::LvKeyNotFound(8))
+    ));
+    Ok(())
+}
+
+#[test]
+pub fn lv_reader_test() -> Result<(), EseError> {
+    let path = prepare_db("test.edb", "", 0, 0, 0);
+    let data = fs::read(&path).unwrap();
+    let reader = Reader::load_db(std::io::Cursor::new(data), 10)?;
+
+    let mut lv_tags = LV_tags::new();
+    for i in (0..50u32).rev() {
+        let tag = LV_tag {
+            common_page_key: vec![],
+            local_page_key: vec![],
+            offset: 4096 * 5 + i as u64 * 7,
+            size: 13,
+        };
+        lv_tags.entry(3).or_default().insert(i * 13, tag);
+    }
+    let read_chunks = |lv_tags: &LV_tags, chunk_size: usize| -> Result<Vec<u8>, EseError> {
+        let mut lv_reader = reader.lv_reader(lv_tags, 3, false)?;
+        let mut value = vec![];
+        let mut chunk = vec![0u8; chunk_size];
+        loop {
+            let n = lv_reader.read(&mut chunk)?;
+            if n == 0 {
+                break;
+            }
+            value.extend_from_slice(&chunk[..n]);
+        }
+        Ok(value)
+    };
+    let expected = reader.load_lv_data(&lv_tags, 3, false)?;
+    assert_eq!(expected.len(), 50 * 13);
+    for chunk_size in [1, 5, 13, 64, 4096] {
+        assert_eq!(read_chunks(&lv_tags, chunk_size)?, expected);
+    }
+
+    // the value ends at the first missing segment
+    lv_tags.get_mut(&3).unwrap().remove(&(20 * 13));
+    assert_eq!(read_chunks(&lv_tags, 64)?, expected[..20 * 13]);
+    assert!(matches!(
+        reader.lv_reader(&lv_tags, 4, false),
+        Err(EseError::LvKeyNotFound(4))
+    ));
+    Ok(())
+}
+
+#[cfg(all(feature = "nt_comparison", target_os = "windows"))]
+#[test]
+pub fn lv_reader_test_windows() -> Result<(), EseError> {
+    use sha2::{Digest, Sha256};
+    let table = "test_table";
+    let record_size = 16 * 1024 * 1024;
+    let path = prepare_db_gen("lv_reader_test.edb", table, 1024 * 8, record_size, 1);
+    {
+        let jdb = EseParser::load_from_path(5, &path)?;
+        let table_id = jdb.open_table(table)?;
+        let columns = jdb.get_columns(table)?;
+        let column = columns
+            .iter()
+            .find(|c| c.name == "compressed_binary")
+            .unwrap();
+        assert!(jdb.move_row(table_id, ESE_MoveFirst)?);
+
+        let expected = format!("Record {number:>width$}", number = 0, width = record_size);
+        let mut lv_reader = jdb.get_column_reader(table_id, column.id)?.unwrap();
+        let mut hasher = Sha256::new();
+        let mut chunk = vec![0u8; 64 * 1024];
+        let mut size = 0;
+        loop {
+            let n = lv_reader.read(&mut chunk)?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&chunk[..n]);
+            size += n;
+        }
+        assert_eq!(size, expected.len());
+        assert_eq!(hasher.finalize(), Sha256::digest(expected.as_bytes()));
+    }
+    clean_db_gen(&path);
+    Ok(())
+}
+
+#[test]
+pub fn column_string_test() -> Result<(), EseError> {
+    let path = prepare_db("decompress_test.edb", "test_table", 1024 * 8, 10, 10);
+    let jdb = EseParser::load_from_path(5, &path)?;
+    let table_id = jdb.open_table("test_table")?;
+    let columns = jdb.get_columns("test_table")?;
+    for col in &columns {
+        let s = jdb.get_column_string(table_id, col, false)?.unwrap();
+        assert!(
+            s.starts_with("Record") && s.ends_with('0'),
+            "{}: {}",
+            col.name,
+            s
+        );
+    }
+
+    let path = prepare_db("test.edb", "TestTable", 1024 * 4, 0, 1);
+    let jdb = EseParser::load_from_path(5, &path)?;
+    let table_id = jdb.open_table("TestTable")?;
+    let columns = jdb.get_columns("TestTable")?;
+    let column = |name: &str| columns.iter().find(|c| c.name == name).unwrap();
+    let text = jdb
+        .get_column_string(table_id, column("Text"), false)?
+        .unwrap();
+    assert!(text.starts_with("ABCDEFG"));
+    let long_text = jdb
+        .get_column_string(table_id, column("LongText"), false)?
+        .unwrap();
+    assert!(long_text.starts_with("ABCDEFG"));
+
+    // binary columns are tried as UTF-8, the GUID starts with 6e e9 36
+    let binary = column("GUID");
+    let e = jdb.get_column_string(table_id, binary, false).unwrap_err();
+    assert_eq!(
+        e.to_string(),
+        "column GUID: invalid text in code page 0 at byte offset 1"
+    );
+    let long_binary = jdb.get_column_string(table_id, column("LongBinary"), false)?;
+    assert_eq!(long_binary.unwrap().len(), 128);
+    let lossy = jdb.get_column_string(table_id, binary, true)?.unwrap();
+    assert!(lossy.contains(char::REPLACEMENT_CHARACTER));
+    assert_eq!(
+        jdb.get_column_string(table_id, column("Short"), false)?,
+        None
+    );
+    Ok(())
+}
+
+#[test]
+pub fn template_columns_test() -> Result<(), EseError> {
+    let cat_def =
+        |cat_type: jet::CatalogType, identifier: u32, name: &str| jet::CatalogDefinition {
+            cat_type: cat_type as u16,
+            identifier,
+            name: name.to_string(),
+            ..Default::default()
+        };
+    let table = |name: &str, template: Option<&str>, columns: &[(u32, &str)]| {
+        let mut table_cat_def = cat_def(jet::CatalogType::Table, 0, name);
+        table_cat_def.template_name = template.map(|t| t.to_string());
+        jet::TableDefinition {
+            table_catalog_definition: Some(table_cat_def),
+            column_catalog_definition_array: columns
+                .iter()
+                .map(|&(id, name)| cat_def(jet::CatalogType::Column, id, name))
+                .collect(),
+            long_value_catalog_definition: None,
+            index_catalog_definition_array: vec![],
+            callback_catalog_definition_array: vec![],
+            template_table: template.map(|t| t.to_string()),
+        }
+    };
+    let mut tables = vec![
+        table(
+            "Derived",
+            Some("Template"),
+            &[(2, "own_long"), (257, "own_text")],
+        ),
+        table(
+            "Template",
+            None,
+            &[(1, "template_long"), (256, "template_text")],
+        ),
+    ];
+    inherit_template_columns(&mut tables)?;
+    let names = |t: &jet::TableDefinition| -> Vec<String> {
+        t.column_catalog_definition_array
+            .iter()
+            .map(|c| c.name.clone())
+            .collect()
+    };
+    assert_eq!(
+        names(&tables[0]),
+        vec!["template_long", "template_text", "own_long", "own_text"]
+    );
+    assert_eq!(names(&tables[1]), vec!["template_long", "template_text"]);
+
+    let mut tables = vec![table("Derived", Some("NoSuchTemplate"), &[(1, "own_long")])];
+    assert!(matches!(
+        inherit_template_columns(&mut tables),
+        Err(EseError::CorruptCatalog(_))
+    ));
+    Ok(())
+}
+
+#[cfg(all(feature = "nt_comparison", target_os = "windows"))]
+#[test]
+pub fn template_table_test_windows() -> Result<(), EseError> {
+    let path = prepare_template_db_gen(
+        "template_test.edb",
+        "template_table",
+        "derived_table",
+        1024 * 8,
+        10,
+    );
+    {
+        let jdb = EseParser::load_from_path(5, &path)?;
+        let columns = jdb.get_columns("derived_table")?;
+        let column = |name: &str| columns.iter().find(|c| c.name == name).unwrap();
+        let table_id = jdb.open_table("derived_table")?;
+        assert!(jdb.move_row(table_id, ESE_MoveFirst)?);
+        for i in 0.. {
+            assert_eq!(
+                jdb.get_column_string(table_id, column("template_text"), false)?,
+                Some(format!("Template {}", i))
+            );
+            assert_eq!(
+                jdb.get_column(table_id, column("template_long").id)?,
+                Some((i as i32).to_le_bytes().to_vec())
+            );
+            assert_eq!(
+                jdb.get_column_string(table_id, column("own_text"), false)?,
+                Some(format!("Own {}", i))
+            );
+            if !jdb.move_row(table_id, ESE_MoveNext)? {
+                break;
+            }
+        }
+    }
+    clean_db_gen(&path);
+    Ok(())
+}
+
+#[cfg(all(feature = "nt_comparison", target_os = "windows"))]
+#[test]
+pub fn deleted_rows_test_windows() -> Result<(), EseError> {
+    use crate::ese_parser::ParserOptions;
+
+    let path =
+        prepare_deleted_rows_db_gen("deleted_rows_test.edb", "test_table", 1024 * 8, 10, &[2, 5]);
+    let read_rows = |include_deleted: bool| -> Result<Vec<(String, bool)>, EseError> {
+        let options = ParserOptions {
+            include_deleted,
+            ..Default::default()
+        };
+        let jdb = EseParser::load_from_path_with_options(5, &path, options)?;
+        let columns = jdb.get_columns("test_table")?;
+        let text = columns.iter().find(|c| c.name == "text").unwrap();
+        let table_id = jdb.open_table("test_table")?;
+        let mut rows = vec![];
+        let mut has_row = jdb.move_row(table_id, ESE_MoveFirst)?;
+        while has_row {
+            // a deleted record may be partly overwritten, it reads as null then
+            let s = jdb.get_column_string(table_id, text, false).ok().flatten();
+            rows.push((s.unwrap_or_default(), jdb.is_current_row_deleted(table_id)?));
+            has_row = jdb.move_row(table_id, ESE_MoveNext)?;
+        }
+        Ok(rows)
+    };
+    {
+        let live = read_rows(false)?;
+        let expected: Vec<(String, bool)> = [0, 1, 3, 4, 6, 7, 8, 9]
+            .iter()
+            .map(|i| (format!("Record {}", i), false))
+            .collect();
+        assert_eq!(live, expected);
+
+        let all = read_rows(true)?;
+        let deleted: Vec<&(String, bool)> = all.iter().filter(|(_, d)| *d).collect();
+        assert_eq!(all.len() - deleted.len(), live.len());
+        // the version store cleanup may have removed the defunct tags already
+        assert!(deleted.len() <= 2);
+        assert!(deleted
+            .iter()
+            .all(|(s, _)| s.is_empty() || s == "Record 2" || s == "Record 5"));
+    }
+    clean_db_gen(&path);
+    Ok(())
+}
+
+#[test]
+pub fn in_memory_reader_test() -> Result<(), EseError> {
+    let path = prepare_db("test.edb", "TestTable", 1024 * 4, 0, 0);
+    let data = fs::read(&path).unwrap();
+    let file_reader = Reader::load_db(File::open(&path).unwrap(), 10)?;
+    let catalog = file_reader.load_catalog()?;
+    let names = |catalog: &[jet::TableDefinition]| -> Vec<String> {
+        catalog
+            .iter()
+            .filter_map(|t| t.table_catalog_definition.as_ref())
+            .map(|c| c.name.clone())
+            .collect()
+    };
+
+    let slice_reader = Reader::from_memory(data.as_slice())?;
+    assert_eq!(slice_reader.page_size(), file_reader.page_size());
+    assert_eq!(names(&slice_reader.load_catalog()?), names(&catalog));
+    for t in &catalog {
+        if let Some(lv) = &t.long_value_catalog_definition {
+            let lv_tags = |reader: &dyn Fn(u32) -> Result<LV_tags, EseError>| {
+                let mut tags: Vec<String> = reader(lv.father_data_page_number)
+                    .unwrap()
+                    .iter()
+                    .map(|(key, segments)| format!("{} {:?}", key, segments))
+                    .collect();
+                tags.sort();
+                tags
+            };
+            assert_eq!(
+                lv_tags(&|page| slice_reader.load_lv_metadata(page)),
+                lv_tags(&|page| file_reader.load_lv_metadata(page))
+            );
+        }
+    }
+
+    let vec_reader = Reader::from_memory(data.clone())?;
+    assert_eq!(names(&vec_reader.load_catalog()?), names(&catalog));
+
+    assert!(Reader::from_memory(&data[..100]).is_err());
+    assert!(Reader::from_memory(vec![]).is_err());
     Ok(())
 }
@@ -0,0 +1,122 @@
+//sqlite.rs
+use crate::error::EseError;
+use crate::ese_trait::*;
+use crate::utils::guid_to_string;
+use crate::value::Value;
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params_from_iter, Connection, Statement};
+use std::convert::TryFrom;
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+fn sqlite_type(coltyp: u32) -> &'static str {
+    match coltyp {
+        ESE_coltypBit
+        | ESE_coltypUnsignedByte
+        | ESE_coltypShort
+        | ESE_coltypLong
+        | ESE_coltypCurrency
+        | ESE_coltypUnsignedLong
+        | ESE_coltypLongLong
+        | ESE_coltypUnsignedShort
+        | ESE_coltypUnsignedLongLong => "INTEGER",
+        ESE_coltypIEEESingle | ESE_coltypIEEEDouble => "REAL",
+        ESE_coltypDateTime | ESE_coltypText | ESE_coltypLongText | ESE_coltypGUID => "TEXT",
+        _ => "BLOB",
+    }
+}
+
+fn to_sqlite_value(value: Value) -> SqlValue {
+    match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(v) => SqlValue::Integer(v as i64),
+        Value::U8(v) => SqlValue::Integer(v as i64),
+        Value::I16(v) => SqlValue::Integer(v as i64),
+        Value::U16(v) => SqlValue::Integer(v as i64),
+        Value::I32(v) => SqlValue::Integer(v as i64),
+        Value::U32(v) => SqlValue::Integer(v as i64),
+        // the raw Currency value, scaled by 10000
+        Value::I64(v) | Value::Currency(v) => SqlValue::Integer(v),
+        // SQLite integers are signed 64-bit
+        Value::U64(v) => i64::try_from(v).map_or(SqlValue::Real(v as f64), SqlValue::Integer),
+        Value::F32(v) => SqlValue::Real(v as f64),
+        Value::F64(v) => SqlValue::Real(v),
+        Value::DateTime(v) => SqlValue::Text(v.to_rfc3339()),
+        Value::Guid(v) => SqlValue::Text(guid_to_string(&v.0)),
+        Value::Text(v) => SqlValue::Text(v),
+        Value::Binary(v) => SqlValue::Blob(v),
+        // the values joined as text
+        value @ Value::MultiValue(_) => SqlValue::Text(value.to_string()),
+    }
+}
+
+fn sql_err(e: rusqlite::Error) -> EseError {
+    EseError::new(format!("sqlite: {}", e))
+}
+
+/// Creates `table` in the SQLite database and copies all of its rows there.
+/// Returns the number of inserted rows. The table is closed on errors too.
+pub fn export_table<D: EseDb + ?Sized>(
+    db: &D,
+    table: &str,
+    conn: &Connection,
+) -> Result<u64, EseError> {
+    let columns = db.get_columns(table)?;
+    let definitions = columns
+        .iter()
+        .map(|c| format!("{} {}", quote_identifier(&c.name), sqlite_type(c.typ)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let placeholders = (1..=columns.len())
+        .map(|i| format!("?{}", i))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let tx = conn.unchecked_transaction().map_err(sql_err)?;
+    tx.execute(
+        &format!("CREATE TABLE {} ({})", quote_identifier(table), definitions),
+        [],
+    )
+    .map_err(sql_err)?;
+
+    let rows = {
+        let mut stmt = tx
+            .prepare(&format!(
+                "INSERT INTO {} VALUES ({})",
+                quote_identifier(table),
+                placeholders
+            ))
+            .map_err(sql_err)?;
+        let table_id = db.open_table(table)?;
+        let rows = insert_rows(db, table_id, &columns, &mut stmt);
+        db.close_table(table_id);
+        rows?
+    };
+    tx.commit().map_err(sql_err)?;
+    Ok(rows)
+}
+
+fn insert_rows<D: EseDb + ?Sized>(
+    db: &D,
+    table_id: u64,
+    columns: &[ColumnInfo],
+    stmt: &mut Statement,
+) -> Result<u64, EseError> {
+    let mut rows = 0;
+    let mut has_row = db.move_row(table_id, ESE_MoveFirst)?;
+    while has_row {
+        let mut values = Vec::with_capacity(columns.len());
+        for c in columns {
+            values.push(match db.get_column(table_id, c.id)? {
+                Some(v) => to_sqlite_value(Value::from_column(c, &v, false)?),
+                None => SqlValue::Null,
+            });
+        }
+        stmt.execute(params_from_iter(values)).map_err(sql_err)?;
+        rows += 1;
+        has_row = db.move_row(table_id, ESE_MoveNext)?;
+    }
+    Ok(rows)
+}
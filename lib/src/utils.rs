@@ -1,4 +1,7 @@
+use encoding::{all::WINDOWS_1252, DecoderTrap, Encoding};
 use std::char::DecodeUtf16Error;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use std::mem;
 
 pub fn from_utf16(v: &[u8]) -> Result<String, DecodeUtf16Error> {
@@ -8,6 +11,176 @@ pub fn from_utf16(v: &[u8]) -> Result<String, DecodeUtf16Error> {
     std::char::decode_utf16(iter).collect::<Result<String, _>>()
 }
 
+/// Decodes a text value stored in code page `cp`: 1200 as UTF-16LE, 1252 as Windows-1252,
+/// any other code page as UTF-8.
+/// Returns the byte offset of the first invalid sequence, unless `lossy` replaces
+/// the invalid sequences with U+FFFD.
+pub fn decode_text(v: &[u8], cp: u16, lossy: bool) -> Result<String, usize> {
+    match cp {
+        1200 => {
+            let mut s = String::with_capacity(v.len() / 2);
+            let chunks = v.chunks_exact(2);
+            let odd_byte = !chunks.remainder().is_empty();
+            let units = chunks.map(|c| u16::from_le_bytes([c[0], c[1]]));
+            let mut offset = 0;
+            for c in std::char::decode_utf16(units) {
+                match c {
+                    Ok(c) => {
+                        offset += c.len_utf16() * 2;
+                        s.push(c);
+                    }
+                    Err(_) if lossy => {
+                        offset += 2;
+                        s.push(char::REPLACEMENT_CHARACTER);
+                    }
+                    Err(_) => return Err(offset),
+                }
+            }
+            if odd_byte {
+                if !lossy {
+                    return Err(v.len() - 1);
+                }
+                s.push(char::REPLACEMENT_CHARACTER);
+            }
+            Ok(s)
+        }
+        // every byte is defined (the undefined ones map to C1 controls)
+        1252 => Ok(WINDOWS_1252
+            .decode(v, DecoderTrap::Replace)
+            .unwrap_or_default()),
+        _ if lossy => Ok(String::from_utf8_lossy(v).into_owned()),
+        _ => std::str::from_utf8(v)
+            .map(|s| s.to_string())
+            .map_err(|e| e.valid_up_to()),
+    }
+}
+
+/// Converts 16 bytes of a GUID column (COM layout: the first three fields are stored
+/// little-endian) to the RFC 4122 byte order.
+pub fn guid_to_rfc4122(v: &[u8; 16]) -> [u8; 16] {
+    let mut guid = *v;
+    guid[0..4].reverse();
+    guid[4..6].reverse();
+    guid[6..8].reverse();
+    guid
+}
+
+/// Formats 16 bytes of a GUID column as `xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx`
+/// (the first three groups are stored little-endian).
+pub fn guid_to_string(v: &[u8; 16]) -> String {
+    let g = guid_to_rfc4122(v);
+    format!(
+        "{:02x}{:02x}{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}",
+        g[0], g[1], g[2], g[3], g[4], g[5], g[6], g[7], g[8], g[9], g[10], g[11], g[12], g[13], g[14], g[15]
+    )
+}
+
+// LCMapString() and CompareString() flags
+const LCMAP_FLAG_NAMES: &[(u32, &str)] = &[
+    (0x0000_0001, "NORM_IGNORECASE"),
+    (0x0000_0002, "NORM_IGNORENONSPACE"),
+    (0x0000_0004, "NORM_IGNORESYMBOLS"),
+    (0x0000_0008, "SORT_DIGITSASNUMBERS"),
+    (0x0000_0010, "LINGUISTIC_IGNORECASE"),
+    (0x0000_0020, "LINGUISTIC_IGNOREDIACRITIC"),
+    (0x0000_0100, "LCMAP_LOWERCASE"),
+    (0x0000_0200, "LCMAP_UPPERCASE"),
+    (0x0000_0400, "LCMAP_SORTKEY"),
+    (0x0000_0800, "LCMAP_BYTEREV"),
+    (0x0000_1000, "SORT_STRINGSORT"),
+    (0x0001_0000, "NORM_IGNOREKANATYPE"),
+    (0x0002_0000, "NORM_IGNOREWIDTH"),
+    (0x0004_0000, "LCMAP_HASH"),
+    (0x0010_0000, "LCMAP_HIRAGANA"),
+    (0x0020_0000, "LCMAP_KATAKANA"),
+    (0x0040_0000, "LCMAP_HALFWIDTH"),
+    (0x0080_0000, "LCMAP_FULLWIDTH"),
+    (0x0100_0000, "LCMAP_LINGUISTIC_CASING"),
+    (0x0200_0000, "LCMAP_SIMPLIFIED_CHINESE"),
+    (0x0400_0000, "LCMAP_TRADITIONAL_CHINESE"),
+    (0x0800_0000, "NORM_LINGUISTIC_CASING"),
+    (0x2000_0000, "LCMAP_SORTHANDLE"),
+];
+
+/// Names of the flags set in `lcmap_flags` of the catalog, unknown bits are given in hex.
+pub fn lcmap_flags_to_description(flags: u32) -> Vec<String> {
+    let mut description = vec![];
+    let mut unknown = flags;
+    for &(flag, name) in LCMAP_FLAG_NAMES {
+        if flags & flag != 0 {
+            description.push(name.to_string());
+            unknown &= !flag;
+        }
+    }
+    if unknown != 0 {
+        description.push(format!("0x{:x}", unknown));
+    }
+    description
+}
+
+/// Compares object names the way ESE does, ignoring case (Unicode simple case folding).
+pub fn eq_ignore_case(a: &str, b: &str) -> bool {
+    a.chars()
+        .flat_map(char::to_lowercase)
+        .eq(b.chars().flat_map(char::to_lowercase))
+}
+
+/// Position of the object `name` in `names`, an exact match is preferred
+/// over a match ignoring case.
+pub fn find_object_name<'a>(names: impl IntoIterator<Item = &'a str>, name: &str) -> Option<usize> {
+    let mut folded = None;
+    for (i, n) in names.into_iter().enumerate() {
+        if n == name {
+            return Some(i);
+        }
+        if folded.is_none() && eq_ignore_case(n, name) {
+            folded = Some(i);
+        }
+    }
+    folded
+}
+
+// HyperLogLog with 2^14 registers, the standard error is 1.04 / sqrt(2^14) = 0.8%
+const HLL_PRECISION: u32 = 14;
+
+/// Cardinality estimator, see Flajolet et al. "HyperLogLog: the analysis of a near-optimal
+/// cardinality estimation algorithm".
+pub(crate) struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    pub(crate) fn new() -> Self {
+        HyperLogLog {
+            registers: vec![0; 1 << HLL_PRECISION],
+        }
+    }
+
+    pub(crate) fn insert(&mut self, value: &[u8]) {
+        // DefaultHasher::new() uses fixed keys, the estimate is reproducible
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        let hash = hasher.finish();
+        let index = (hash >> (64 - HLL_PRECISION)) as usize;
+        // position of the first set bit of the remaining bits, the sentinel bit bounds it
+        let rank = ((hash << HLL_PRECISION) | (1 << (HLL_PRECISION - 1))).leading_zeros() + 1;
+        self.registers[index] = self.registers[index].max(rank as u8);
+    }
+
+    pub(crate) fn estimate(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self.registers.iter().map(|&r| 2f64.powi(-(r as i32))).sum();
+        let estimate = alpha * m * m / sum;
+        let zeros = self.registers.iter().filter(|&&r| r == 0).count();
+        if estimate <= 2.5 * m && zeros > 0 {
+            // small range correction (linear counting)
+            return (m * (m / zeros as f64).ln()).round() as u64;
+        }
+        estimate.round() as u64
+    }
+}
+
 #[test]
 fn test_from_utf16() {
     let expected = vec!["Record          #", "Record", "Flowers "];
@@ -24,3 +197,116 @@ fn test_from_utf16() {
         assert_eq!(expected, &&from_utf16(t).unwrap());
     }
 }
+
+#[test]
+fn test_decode_text() {
+    let unicode = [0x52, 0, 0xe9, 0, 0x3d, 0xd8, 0x00, 0xde]; // "Ré😀"
+    assert_eq!(decode_text(&unicode, 1200, false), Ok("Ré😀".to_string()));
+    // unpaired surrogate, odd number of bytes
+    assert_eq!(
+        decode_text(&[0x52, 0, 0x3d, 0xd8, 0x52, 0], 1200, false),
+        Err(2)
+    );
+    assert_eq!(
+        decode_text(&[0x52, 0, 0x3d, 0xd8, 0x52, 0], 1200, true),
+        Ok("R\u{fffd}R".to_string())
+    );
+    assert_eq!(decode_text(&[0x52, 0, 0x52], 1200, false), Err(2));
+    assert_eq!(
+        decode_text(&[0x52, 0, 0x52], 1200, true),
+        Ok("R\u{fffd}".to_string())
+    );
+
+    assert_eq!(
+        decode_text(b"caf\xe9 \x80", 1252, false),
+        Ok("café €".to_string())
+    );
+
+    assert_eq!(
+        decode_text("café".as_bytes(), 0, false),
+        Ok("café".to_string())
+    );
+    assert_eq!(decode_text(b"ab\xff\xfecd", 0, false), Err(2));
+    assert_eq!(
+        decode_text(b"ab\xffcd", 0, true),
+        Ok("ab\u{fffd}cd".to_string())
+    );
+}
+
+#[test]
+fn test_guid_to_rfc4122() {
+    // schemaIDGUID of the user class and rightsGuid of DS-Replication-Get-Changes,
+    // as stored in ntds.dit
+    let tests = [
+        (
+            [
+                0xba, 0x7a, 0x96, 0xbf, 0xe6, 0x0d, 0xd0, 0x11, 0xa2, 0x85, 0x00, 0xaa, 0x00, 0x30,
+                0x49, 0xe2,
+            ],
+            "bf967aba-0de6-11d0-a285-00aa003049e2",
+        ),
+        (
+            [
+                0xaa, 0xf6, 0x31, 0x11, 0x07, 0x9c, 0xd1, 0x11, 0xf7, 0x9f, 0x00, 0xc0, 0x4f, 0xc2,
+                0xdc, 0xd2,
+            ],
+            "1131f6aa-9c07-11d1-f79f-00c04fc2dcd2",
+        ),
+    ];
+    for (stored, expected) in &tests {
+        let guid = guid_to_rfc4122(stored);
+        let hex: String = guid.iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(hex, expected.replace('-', ""));
+        assert_eq!(&guid_to_string(stored), expected);
+        // the swap is its own inverse
+        assert_eq!(&guid_to_rfc4122(&guid), stored);
+    }
+}
+
+#[test]
+fn test_find_object_name() {
+    let names = ["MSysObjects", "msysobjects", "Größe", "ÄRGER"];
+    assert_eq!(find_object_name(names, "msysobjects"), Some(1));
+    assert_eq!(find_object_name(names, "MSYSOBJECTS"), Some(0));
+    assert_eq!(find_object_name(names, "GRÖßE"), Some(2));
+    assert_eq!(find_object_name(names, "ärger"), Some(3));
+    assert_eq!(find_object_name(names, "Arger"), None);
+    assert!(eq_ignore_case("ΣΊΣΥΦΟΣ", "σίσυφοσ"));
+    assert!(!eq_ignore_case("MSysObjects", "MSysObject"));
+}
+
+#[test]
+fn test_lcmap_flags_to_description() {
+    assert!(lcmap_flags_to_description(0).is_empty());
+    // default flags of the indexes
+    assert_eq!(
+        lcmap_flags_to_description(0x30401),
+        vec![
+            "NORM_IGNORECASE",
+            "LCMAP_SORTKEY",
+            "NORM_IGNOREKANATYPE",
+            "NORM_IGNOREWIDTH"
+        ]
+    );
+    assert_eq!(
+        lcmap_flags_to_description(0x8000_0100),
+        vec!["LCMAP_LOWERCASE", "0x80000000"]
+    );
+}
+
+#[test]
+fn test_hyper_log_log() {
+    let mut hll = HyperLogLog::new();
+    assert_eq!(hll.estimate(), 0);
+    for i in 0..100u32 {
+        hll.insert(&i.to_le_bytes());
+        hll.insert(&i.to_le_bytes());
+    }
+    // linear counting is almost exact for small sets
+    assert!((98..=102).contains(&hll.estimate()));
+    for i in 0..100_000u32 {
+        hll.insert(&i.to_le_bytes());
+    }
+    let estimate = hll.estimate() as f64;
+    assert!((estimate - 100_000.0).abs() < 100_000.0 * 0.03);
+}
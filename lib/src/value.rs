@@ -0,0 +1,177 @@
+//value.rs
+use crate::error::EseError;
+use crate::ese_trait::*;
+use crate::utils::{decode_text, guid_to_rfc4122};
+use chrono::{DateTime, Utc};
+use std::convert::TryInto;
+use std::fmt;
+
+// Currency values are stored as 64-bit integers scaled by 10000
+const CURRENCY_SCALE: i64 = 10000;
+
+/// GUID column value, the 16 bytes as stored (COM layout: the first three fields
+/// are little-endian).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Guid(pub [u8; 16]);
+
+impl Guid {
+    /// Bytes in RFC 4122 order, as expected by `uuid::Uuid::from_bytes`.
+    pub fn to_rfc4122(&self) -> [u8; 16] {
+        guid_to_rfc4122(&self.0)
+    }
+}
+
+// registry format: {XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX}
+impl fmt::Display for Guid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let g = self.to_rfc4122();
+        write!(f, "{{")?;
+        for (i, b) in g.iter().enumerate() {
+            if i == 4 || i == 6 || i == 8 || i == 10 {
+                write!(f, "-")?;
+            }
+            write!(f, "{:02X}", b)?;
+        }
+        write!(f, "}}")
+    }
+}
+
+/// Formats a raw Currency value (scaled by 10000) as a decimal string, e.g. `35.0050`.
+pub fn currency_to_string(raw: i64) -> String {
+    let sign = if raw < 0 { "-" } else { "" };
+    let abs = raw.unsigned_abs();
+    let scale = CURRENCY_SCALE as u64;
+    format!("{}{}.{:04}", sign, abs / scale, abs % scale)
+}
+
+/// Column value converted by the catalog column type and code page.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    U8(u8),
+    I16(i16),
+    U16(u16),
+    I32(i32),
+    U32(u32),
+    I64(i64),
+    U64(u64),
+    F32(f32),
+    F64(f64),
+    DateTime(DateTime<Utc>),
+    Guid(Guid),
+    /// Raw value, scaled by 10000 (see `Value::currency_decimal`).
+    Currency(i64),
+    Text(String),
+    Binary(Vec<u8>),
+    MultiValue(Vec<Value>),
+}
+
+impl Value {
+    /// Converts the bytes of a column value of type `column_type`,
+    /// text is decoded by the code page `cp`.
+    pub fn from_column_bytes(column_type: u32, cp: u16, v: &[u8]) -> Result<Value, EseError> {
+        Value::convert(column_type, cp, v, false)
+    }
+
+    /// Like `from_column_bytes`, the invalid sequences of text values are replaced with U+FFFD.
+    pub fn from_column_bytes_lossy(column_type: u32, cp: u16, v: &[u8]) -> Result<Value, EseError> {
+        Value::convert(column_type, cp, v, true)
+    }
+
+    // the errors name the column
+    pub(crate) fn from_column(
+        column: &ColumnInfo,
+        v: &[u8],
+        lossy: bool,
+    ) -> Result<Value, EseError> {
+        Value::convert(column.typ, column.cp, v, lossy)
+            .map_err(|e| EseError::new(format!("column {}: {}", column.name, e)))
+    }
+
+    fn convert(column_type: u32, cp: u16, v: &[u8], lossy: bool) -> Result<Value, EseError> {
+        let value = match column_type {
+            ESE_coltypBit => Value::Bool(to_array::<1>(v, column_type)?[0] != 0),
+            ESE_coltypUnsignedByte => Value::U8(to_array::<1>(v, column_type)?[0]),
+            ESE_coltypShort => Value::I16(i16::from_le_bytes(to_array(v, column_type)?)),
+            ESE_coltypUnsignedShort => Value::U16(u16::from_le_bytes(to_array(v, column_type)?)),
+            ESE_coltypLong => Value::I32(i32::from_le_bytes(to_array(v, column_type)?)),
+            ESE_coltypUnsignedLong => Value::U32(u32::from_le_bytes(to_array(v, column_type)?)),
+            ESE_coltypLongLong => Value::I64(i64::from_le_bytes(to_array(v, column_type)?)),
+            ESE_coltypUnsignedLongLong => Value::U64(u64::from_le_bytes(to_array(v, column_type)?)),
+            ESE_coltypCurrency => Value::Currency(i64::from_le_bytes(to_array(v, column_type)?)),
+            ESE_coltypIEEESingle => Value::F32(f32::from_le_bytes(to_array(v, column_type)?)),
+            ESE_coltypIEEEDouble => Value::F64(f64::from_le_bytes(to_array(v, column_type)?)),
+            ESE_coltypDateTime => match date_from_bytes(v)? {
+                Some(dt) => Value::DateTime(dt),
+                None => Value::Null,
+            },
+            ESE_coltypGUID => Value::Guid(Guid(to_array(v, column_type)?)),
+            ESE_coltypText | ESE_coltypLongText => {
+                Value::Text(decode_text(v, cp, lossy).map_err(|offset| {
+                    EseError::new(format!(
+                        "invalid text in code page {} at byte offset {}",
+                        cp, offset
+                    ))
+                })?)
+            }
+            _ => Value::Binary(v.to_vec()),
+        };
+        Ok(value)
+    }
+
+    pub fn is_null(&self) -> bool {
+        *self == Value::Null
+    }
+
+    /// Currency value as a decimal string, `None` for the other variants.
+    pub fn currency_decimal(&self) -> Option<String> {
+        match self {
+            Value::Currency(raw) => Some(currency_to_string(*raw)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Null => Ok(()),
+            Value::Bool(v) => write!(f, "{}", v),
+            Value::U8(v) => write!(f, "{}", v),
+            Value::I16(v) => write!(f, "{}", v),
+            Value::U16(v) => write!(f, "{}", v),
+            Value::I32(v) => write!(f, "{}", v),
+            Value::U32(v) => write!(f, "{}", v),
+            Value::I64(v) => write!(f, "{}", v),
+            Value::U64(v) => write!(f, "{}", v),
+            Value::F32(v) => write!(f, "{}", v),
+            Value::F64(v) => write!(f, "{}", v),
+            Value::DateTime(v) => write!(f, "{}", v.to_rfc3339()),
+            Value::Guid(v) => write!(f, "{}", v),
+            Value::Currency(v) => write!(f, "{}", currency_to_string(*v)),
+            Value::Text(v) => write!(f, "{}", v),
+            Value::Binary(v) => v.iter().try_for_each(|b| write!(f, "{:02x}", b)),
+            Value::MultiValue(v) => {
+                for (i, value) in v.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn to_array<const N: usize>(v: &[u8], column_type: u32) -> Result<[u8; N], EseError> {
+    v.try_into().map_err(|_| {
+        EseError::new(format!(
+            "column type {}: unexpected value size {}, expected {}",
+            column_type,
+            v.len(),
+            N
+        ))
+    })
+}
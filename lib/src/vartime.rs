@@ -1,7 +1,9 @@
 #![allow(non_upper_case_globals)]
 #![allow(non_snake_case)]
 
-use chrono::{DateTime, Utc};
+use crate::parser::jet;
+use chrono::{DateTime, TimeZone, Utc};
+use std::convert::TryFrom;
 
 // implementation is taken from ReacOS: dll/win32/oleaut32/variant.c
 
@@ -135,6 +137,8 @@ pub fn get_date_time_from_filetime(filetime: u64) -> DateTime<Utc> {
     const UNIX_EPOCH_SECONDS_SINCE_WINDOWS_EPOCH: i128 = 11644473600;
     const UNIX_EPOCH_NANOS: i128 = UNIX_EPOCH_SECONDS_SINCE_WINDOWS_EPOCH * 1_000_000_000;
     let filetime_nanos: i128 = filetime as i128 * 100;
+    // nanoseconds from the Unix epoch overflow i64 before 1677, add the seconds separately
+    let nanos = filetime_nanos - UNIX_EPOCH_NANOS;
 
     // Add nanoseconds to timestamp via Duration
     DateTime::<Utc>::from_utc(
@@ -142,11 +146,48 @@ pub fn get_date_time_from_filetime(filetime: u64) -> DateTime<Utc> {
             .unwrap()
             .and_hms_nano_opt(0, 0, 0, 0)
             .unwrap()
-            + chrono::Duration::nanoseconds((filetime_nanos - UNIX_EPOCH_NANOS) as i64),
+            + chrono::Duration::seconds(nanos.div_euclid(1_000_000_000) as i64)
+            + chrono::Duration::nanoseconds(nanos.rem_euclid(1_000_000_000) as i64),
         Utc,
     )
 }
 
+const MILLISECONDS_PER_DAY: f64 = 86_400_000.0;
+
+fn ole_epoch() -> chrono::NaiveDateTime {
+    chrono::NaiveDate::from_ymd_opt(1899, 12, 30)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+}
+
+/// Converts an OLE automation date (days since 1899-12-30, rounded to the millisecond)
+/// to a DateTime<Utc>. The fraction is the time of the day even before the epoch:
+/// -1.25 is 1899-12-29 06:00. Returns None out of the 100-01-01 - 9999-12-31 range.
+pub fn get_date_time_from_ole(date: f64) -> Option<DateTime<Utc>> {
+    if date.is_nan() || date <= (DATE_MIN as f64 - 1.0) || date >= (DATE_MAX as f64 + 1.0) {
+        return None;
+    }
+    let days = date.trunc();
+    let time = (date - days).abs();
+    let milliseconds = (days * MILLISECONDS_PER_DAY + time * MILLISECONDS_PER_DAY).round();
+    Some(
+        Utc.from_utc_datetime(&(ole_epoch() + chrono::Duration::milliseconds(milliseconds as i64))),
+    )
+}
+
+/// Converts a DateTime<Utc> to an OLE automation date, the reverse of `get_date_time_from_ole`
+pub fn get_ole_from_date_time(date_time: &DateTime<Utc>) -> f64 {
+    let milliseconds = (date_time.naive_utc() - ole_epoch()).num_milliseconds();
+    let days = milliseconds.div_euclid(MILLISECONDS_PER_DAY as i64) as f64;
+    let time = milliseconds.rem_euclid(MILLISECONDS_PER_DAY as i64) as f64 / MILLISECONDS_PER_DAY;
+    if days < 0.0 {
+        days - time
+    } else {
+        days + time
+    }
+}
+
 pub fn VariantTimeToSystemTime(dateIn: f64, st: &mut SYSTEMTIME) -> bool {
     if dateIn < 1_f64 || dateIn <= (DATE_MIN as f64 - 1.0) || dateIn >= (DATE_MAX as f64 + 1.0) {
         return false;
@@ -215,6 +256,25 @@ pub fn VariantTimeToSystemTime(dateIn: f64, st: &mut SYSTEMTIME) -> bool {
     true
 }
 
+// jet::DateTime stores the year as an offset from 1900 in a byte,
+// dates outside of 1900..=2155 can't be represented
+pub(crate) fn jet_date_time_from_ole(ole: f64) -> Option<jet::DateTime> {
+    let mut st = SYSTEMTIME::default();
+    if ole == 0.0 || !VariantTimeToSystemTime(ole, &mut st) {
+        return None;
+    }
+    Some(jet::DateTime {
+        seconds: u8::try_from(st.wSecond).ok()?,
+        minutes: u8::try_from(st.wMinute).ok()?,
+        hours: u8::try_from(st.wHour).ok()?,
+        day: u8::try_from(st.wDay).ok()?,
+        month: u8::try_from(st.wMonth).ok()?,
+        year: u8::try_from(st.wYear.checked_sub(1900)?).ok()?,
+        time_is_utc: 0,
+        os_snapshot: 0,
+    })
+}
+
 #[test]
 fn test_vartimes() {
     let t1: f64 = 44_286.466_608_796_3;
@@ -273,3 +333,47 @@ fn test_curr_time_with_API() {
     assert_eq!(st_orig, st_sys);
     assert_eq!(st_orig, st_our);
 }
+
+#[test]
+fn test_ole_dates() {
+    let date = |d: f64| {
+        get_date_time_from_ole(d)
+            .unwrap()
+            .format("%Y-%m-%d %H:%M:%S%.3f")
+            .to_string()
+    };
+    assert_eq!(date(0.0), "1899-12-30 00:00:00.000");
+    assert_eq!(date(1.5), "1899-12-31 12:00:00.000");
+    assert_eq!(date(-1.25), "1899-12-29 06:00:00.000");
+    assert_eq!(date(-36522.0), "1800-01-01 00:00:00.000");
+    assert_eq!(date(44286.4666087963), "2021-03-31 11:11:55.000");
+    assert!(get_date_time_from_ole(f64::NAN).is_none());
+    assert!(get_date_time_from_ole(DATE_MAX as f64 + 1.0).is_none());
+    assert!(get_date_time_from_ole(DATE_MIN as f64 - 1.0).is_none());
+
+    for d in [
+        0.0, 0.75, 1.0, -1.0, -1.25, -2.999, -657434.0, 2958465.5, 44286.5,
+    ] {
+        let date_time = get_date_time_from_ole(d).unwrap();
+        assert_eq!(get_ole_from_date_time(&date_time), d);
+        assert_eq!(
+            get_date_time_from_ole(get_ole_from_date_time(&date_time)),
+            Some(date_time)
+        );
+    }
+}
+
+#[test]
+fn test_jet_date_time_from_ole() {
+    // 2021-03-29 11:49:47
+    let dt = jet_date_time_from_ole(44284.49290509259).unwrap();
+    assert_eq!(
+        (dt.year, dt.month, dt.day, dt.hours, dt.minutes, dt.seconds),
+        (121, 3, 29, 11, 49, 47)
+    );
+    assert_eq!(dt.to_utc().unwrap().to_string(), "2021-03-29 11:49:47 UTC");
+    assert!(jet_date_time_from_ole(0.0).is_none());
+    // 1800-01-01 and 2200-01-01 don't fit in the year byte
+    assert!(jet_date_time_from_ole(-36522.0).is_none());
+    assert!(jet_date_time_from_ole(109575.0).is_none());
+}
@@ -7,6 +7,7 @@ use pyo3::exceptions;
 use pyo3::prelude::*;
 
 use crate::utils::*;
+use ese_parser_lib::parser::backend::CursorBackend;
 use ese_parser_lib::{ese_parser::FromBytes, ese_parser::*, ese_trait::*};
 use std::convert::TryFrom;
 use std::fs::File;
@@ -28,7 +29,7 @@ pub struct PyColumnInfo {
 
 #[pyclass]
 pub struct PyEseDb {
-    jdb: EseParser<Box<dyn ReadSeek + Send>>,
+    jdb: EseParser<CursorBackend<Box<dyn ReadSeek + Send>>>,
 }
 
 #[pymethods]
@@ -47,7 +48,7 @@ impl PyEseDb {
         };
 
         let parser = EseParser::load(10, boxed_read_seek)
-            .map_err(|e| PyErr::new::<exceptions::PyTypeError, _>(e.as_str().to_string()))?;
+            .map_err(|e| PyErr::new::<exceptions::PyTypeError, _>(e.to_string()))?;
 
         Ok(Self { jdb: parser })
     }
@@ -55,7 +56,7 @@ impl PyEseDb {
     fn open_table(&self, table: &str) -> PyResult<u64> {
         self.jdb
             .open_table(table)
-            .map_err(|e| PyErr::new::<exceptions::PyTypeError, _>(e.as_str().to_string()))
+            .map_err(|e| PyErr::new::<exceptions::PyTypeError, _>(e.to_string()))
     }
 
     fn close_table(&self, table: u64) -> bool {
@@ -65,7 +66,7 @@ impl PyEseDb {
     fn get_tables(&self) -> PyResult<Vec<String>> {
         self.jdb
             .get_tables()
-            .map_err(|e| PyErr::new::<exceptions::PyTypeError, _>(e.as_str().to_string()))
+            .map_err(|e| PyErr::new::<exceptions::PyTypeError, _>(e.to_string()))
     }
 
     fn get_columns(&self, table: &str) -> PyResult<Vec<PyColumnInfo>> {
@@ -84,9 +85,7 @@ impl PyEseDb {
                 }
                 Ok(r)
             }
-            Err(e) => Err(PyErr::new::<exceptions::PyTypeError, _>(
-                e.as_str().to_string(),
-            )),
+            Err(e) => Err(PyErr::new::<exceptions::PyTypeError, _>(e.to_string())),
         }
     }
 
@@ -109,16 +108,14 @@ impl PyEseDb {
                     column_name
                 )))
             }
-            Err(e) => Err(PyErr::new::<exceptions::PyTypeError, _>(
-                e.as_str().to_string(),
-            )),
+            Err(e) => Err(PyErr::new::<exceptions::PyTypeError, _>(e.to_string())),
         }
     }
 
     fn move_row(&self, table: u64, crow: i32) -> PyResult<bool> {
         self.jdb
             .move_row(table, crow)
-            .map_err(|e| PyErr::new::<exceptions::PyTypeError, _>(e.as_str().to_string()))
+            .map_err(|e| PyErr::new::<exceptions::PyTypeError, _>(e.to_string()))
     }
 
     fn get_row_mv(
@@ -136,11 +133,7 @@ impl PyEseDb {
                 Some(n) => return Ok(Some(n.to_object(py))),
                 None => return Ok(None),
             },
-            Err(e) => {
-                return Err(PyErr::new::<exceptions::PyTypeError, _>(
-                    e.as_str().to_string(),
-                ))
-            }
+            Err(e) => return Err(PyErr::new::<exceptions::PyTypeError, _>(e.to_string())),
         }
     }
 
@@ -158,11 +151,7 @@ impl PyEseDb {
                     Some(n) => return Ok(Some(n)),
                     None => return Ok(None),
                 },
-                Err(e) => {
-                    return Err(PyErr::new::<exceptions::PyTypeError, _>(
-                        e.as_str().to_string(),
-                    ))
-                }
+                Err(e) => return Err(PyErr::new::<exceptions::PyTypeError, _>(e.to_string())),
             }
         }
 
@@ -217,11 +206,7 @@ impl PyEseDb {
                     Some(n) => return Ok(Some(n.to_object(py))),
                     None => return Ok(None),
                 },
-                Err(e) => {
-                    return Err(PyErr::new::<exceptions::PyTypeError, _>(
-                        e.as_str().to_string(),
-                    ))
-                }
+                Err(e) => return Err(PyErr::new::<exceptions::PyTypeError, _>(e.to_string())),
             },
             ESE_coltypText => match self.jdb.get_column(table, column.id) {
                 Ok(ov) => match ov {
@@ -231,11 +216,7 @@ impl PyEseDb {
                     }
                     None => return Ok(None),
                 },
-                Err(e) => {
-                    return Err(PyErr::new::<exceptions::PyTypeError, _>(
-                        e.as_str().to_string(),
-                    ))
-                }
+                Err(e) => return Err(PyErr::new::<exceptions::PyTypeError, _>(e.to_string())),
             },
             ESE_coltypLongText => match self.jdb.get_column(table, column.id) {
                 Ok(ov) => match ov {
@@ -245,22 +226,14 @@ impl PyEseDb {
                     }
                     None => return Ok(None),
                 },
-                Err(e) => {
-                    return Err(PyErr::new::<exceptions::PyTypeError, _>(
-                        e.as_str().to_string(),
-                    ))
-                }
+                Err(e) => return Err(PyErr::new::<exceptions::PyTypeError, _>(e.to_string())),
             },
             ESE_coltypLongBinary => match self.jdb.get_column(table, column.id) {
                 Ok(ov) => match ov {
                     Some(n) => return Ok(Some(n.to_object(py))),
                     None => return Ok(None),
                 },
-                Err(e) => {
-                    return Err(PyErr::new::<exceptions::PyTypeError, _>(
-                        e.as_str().to_string(),
-                    ))
-                }
+                Err(e) => return Err(PyErr::new::<exceptions::PyTypeError, _>(e.to_string())),
             },
             ESE_coltypGUID => {
                 match self.jdb.get_column(table, column.id) {
@@ -275,11 +248,7 @@ impl PyEseDb {
                             None => return Ok(None),
                         }
                     }
-                    Err(e) => {
-                        return Err(PyErr::new::<exceptions::PyTypeError, _>(
-                            e.as_str().to_string(),
-                        ))
-                    }
+                    Err(e) => return Err(PyErr::new::<exceptions::PyTypeError, _>(e.to_string())),
                 }
             }
             ESE_coltypDateTime => match self.jdb.get_column_date(table, column.id) {
@@ -289,11 +258,7 @@ impl PyEseDb {
                     }
                     None => return Ok(None),
                 },
-                Err(e) => {
-                    return Err(PyErr::new::<exceptions::PyTypeError, _>(
-                        e.as_str().to_string(),
-                    ))
-                }
+                Err(e) => return Err(PyErr::new::<exceptions::PyTypeError, _>(e.to_string())),
             },
             _ => {
                 return Err(PyErr::new::<exceptions::PyTypeError, _>(format!(